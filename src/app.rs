@@ -1,33 +1,94 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate};
 use lru::LruCache;
 use notify::{RecommendedWatcher, Watcher};
 use ratatui::Frame;
-use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::crossterm::event::{
+    Event, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::layout::{Position, Rect};
+use ratatui_image::picker::Picker;
 use ratatui_image::protocol::StatefulProtocol;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use std::sync::mpsc::{self, Receiver, Sender};
-use std::time::Instant;
+use std::time::{Instant, SystemTime};
 use tui_input::Input;
 use tui_input::backend::crossterm::EventHandler;
 
 use crate::clipboard::ClipboardBackend;
-use crate::image::ImageProtocol;
+use crate::image::{ConvertOptions, ImageInfo, ImageProtocol, ImageZoom, ZoomView};
 use crate::logging::FlashMessage;
-use crate::models::{ClipboardHistory, Registry, SearchIndex};
+use crate::models::{
+    Action, ClipContent, ClipEntry, ClipboardHistory, FileHead, FormatError, HistorySnapshot,
+    KeyScope, Keymap, MatchMode, Registry, SearchIndex, SearchQuery, StructuredFormat, Template,
+    TemplateContext, UndoStack,
+};
 use crate::storage::Config;
 use crate::ui;
 use crate::ui::Theme;
 
+/// Where the background loader gets image bytes from
+enum ImageSource {
+    /// Image data stored in the clip
+    Memory(Vec<u8>),
+    /// Image file referenced by a File clip, read on the loader thread
+    File(PathBuf),
+}
+
 /// Request to load an image in the background
 struct ImageLoadRequest {
     clip_id: u64,
-    image_data: Vec<u8>,
+    source: ImageSource,
+    /// Shrink to this size in pixels for a list thumbnail (None loads the full preview)
+    thumbnail: Option<(u32, u32)>,
 }
 
 /// Result of loading an image in the background
 struct ImageLoadResult {
     clip_id: u64,
-    protocol_image: Option<StatefulProtocol>,
+    /// Whether this is a list thumbnail rather than the full preview
+    thumbnail: bool,
+    info: Option<ImageInfo>,
+    protocol_image: Result<StatefulProtocol, String>,
+}
+
+/// Where to load the image of a clip from, if it has one
+fn image_source(entry: &ClipEntry) -> Option<ImageSource> {
+    match &entry.content {
+        ClipContent::Image { data, .. } => Some(ImageSource::Memory(data.clone())),
+        ClipContent::File { path, mime_type } if mime_type.starts_with("image/") => {
+            Some(ImageSource::File(path.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// Read and decode an image, refusing anything over the preview size limit
+fn decode_image(source: ImageSource, max_bytes: u64) -> Result<image::DynamicImage> {
+    let data = match source {
+        ImageSource::Memory(data) => data,
+        ImageSource::File(path) => {
+            let size = fs::metadata(&path)
+                .with_context(|| format!("Failed to read {:?}", path))?
+                .len();
+            anyhow::ensure!(
+                size <= max_bytes,
+                "{} bytes is over max_image_preview_size_bytes",
+                size
+            );
+            fs::read(&path).with_context(|| format!("Failed to read {:?}", path))?
+        }
+    };
+    anyhow::ensure!(
+        data.len() as u64 <= max_bytes,
+        "{} bytes is over max_image_preview_size_bytes",
+        data.len()
+    );
+    Ok(image::load_from_memory(&data)?)
 }
 
 /// Application mode determines which keybindings are active
@@ -48,6 +109,18 @@ pub enum AppMode {
     Numeric,
     /// Theme picker modal (activated with 'T')
     ThemePicker,
+    /// Tag editing prompt for the selected clip (activated with 't')
+    TagEdit,
+    /// Prompting for snippet template inputs before copy/paste
+    TemplateInput,
+    /// Visual range selection (activated with 'V')
+    Visual,
+    /// Trash view listing deleted clips (activated with 'X')
+    Trash,
+    /// Preview pane has focus for scrolling (activated with Tab)
+    Preview,
+    /// Prompting for the format to convert the selected image to (activated with 'C')
+    Convert,
 }
 
 /// Register filter state
@@ -72,6 +145,119 @@ pub enum ViewMode {
     Comfortable,
 }
 
+/// Order of the clip list and search results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    /// History order (most recently copied first), search results by relevance
+    Recent,
+    /// Least recently copied first
+    Oldest,
+    /// Highest use count first
+    MostUsed,
+    /// Most frequently and recently used first, blended with search relevance
+    Frecency,
+    /// Largest first
+    Size,
+    /// Named clips alphabetically, then unnamed clips
+    Name,
+    /// Text, then images, then files
+    Type,
+}
+
+impl SortMode {
+    /// All sort modes, in the order 'o' cycles through them
+    const ALL: [SortMode; 7] = [
+        SortMode::Recent,
+        SortMode::Oldest,
+        SortMode::MostUsed,
+        SortMode::Frecency,
+        SortMode::Size,
+        SortMode::Name,
+        SortMode::Type,
+    ];
+
+    /// Parse a sort mode name from config (e.g. "most-used")
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.name() == name.to_lowercase().replace('_', "-"))
+    }
+
+    /// Config name of this sort mode
+    pub fn name(self) -> &'static str {
+        match self {
+            SortMode::Recent => "recent",
+            SortMode::Oldest => "oldest",
+            SortMode::MostUsed => "most-used",
+            SortMode::Frecency => "frecency",
+            SortMode::Size => "size",
+            SortMode::Name => "name",
+            SortMode::Type => "type",
+        }
+    }
+
+    /// Next sort mode in the cycle
+    fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&m| m == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// What the visible clip list is computed from, to tell when the cached list is stale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct VisibleKey {
+    history_revision: u64,
+    search_revision: u64,
+    trash: bool,
+    sort_mode: SortMode,
+    group_clips: bool,
+    register_filter: RegisterFilter,
+    /// Day the grouped view's Today/Yesterday sections were computed for
+    today: Option<NaiveDate>,
+}
+
+/// Section of the grouped clip list view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ClipGroup {
+    Pinned,
+    Registers,
+    Today,
+    Yesterday,
+    Older,
+}
+
+impl ClipGroup {
+    /// Group a clip belongs to, given today's local date
+    fn of(entry: &ClipEntry, today: NaiveDate) -> Self {
+        if entry.pinned {
+            return ClipGroup::Pinned;
+        }
+        if !entry.temporary_registers.is_empty() || !entry.permanent_registers.is_empty() {
+            return ClipGroup::Registers;
+        }
+
+        let date = DateTime::<Local>::from(entry.timestamp).date_naive();
+        if date >= today {
+            ClipGroup::Today
+        } else if today.pred_opt() == Some(date) {
+            ClipGroup::Yesterday
+        } else {
+            ClipGroup::Older
+        }
+    }
+
+    /// Section header text
+    pub fn label(self) -> &'static str {
+        match self {
+            ClipGroup::Pinned => "Pinned",
+            ClipGroup::Registers => "Registers",
+            ClipGroup::Today => "Today",
+            ClipGroup::Yesterday => "Yesterday",
+            ClipGroup::Older => "Older",
+        }
+    }
+}
+
 /// Paste request - content to paste after TUI exits
 #[derive(Debug, Clone)]
 pub enum PasteRequest {
@@ -81,6 +267,47 @@ pub enum PasteRequest {
     PasteFromClipboard(crate::models::ClipContent),
 }
 
+/// What to do with a clip once its content is ready
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipAction {
+    /// Copy to clipboard (Enter)
+    Copy,
+    /// Paste via Ctrl-V after exit (Ctrl-Space)
+    Paste,
+}
+
+/// Snippet template waiting for user inputs before it can be copied/pasted
+#[derive(Debug, Clone)]
+pub struct PendingTemplate {
+    /// Clip the template came from
+    pub clip_id: u64,
+    /// Parsed template from the clip content
+    pub template: Template,
+    /// Action to perform once all inputs are provided
+    pub action: ClipAction,
+    /// Input labels to prompt for, in order
+    pub labels: Vec<String>,
+    /// Values collected so far (plus clipboard text)
+    pub context: TemplateContext,
+}
+
+impl PendingTemplate {
+    /// Label of the input currently being prompted for
+    pub fn current_label(&self) -> Option<&str> {
+        self.labels
+            .get(self.context.inputs.len())
+            .map(String::as_str)
+    }
+}
+
+/// Pretty-printed view of the previewed clip, cached until the clip or fold depth changes
+struct FormattedPreview {
+    clip_id: Option<u64>,
+    fold_depth: Option<usize>,
+    /// None when the clip isn't JSON or XML
+    formatted: Option<(StructuredFormat, Result<String, FormatError>)>,
+}
+
 /// Main application state
 pub struct App {
     /// Current interaction mode
@@ -114,6 +341,27 @@ pub struct App {
     /// Channel for receiving completed image loads
     image_load_rx: Receiver<ImageLoadResult>,
 
+    /// Terminal graphics capabilities, for zoomed previews
+    picker: Picker,
+
+    /// LRU cache of list thumbnails (clip_id -> protocol_image)
+    thumbnail_cache: LruCache<u64, StatefulProtocol>,
+
+    /// Thumbnails requested from the loader but not yet received
+    pending_thumbnails: HashSet<u64>,
+
+    /// Preview images requested from the loader but not yet received
+    pending_images: HashSet<u64>,
+
+    /// Dimensions and pixel format of decoded images
+    image_info: HashMap<u64, ImageInfo>,
+
+    /// How the preview scales image clips
+    pub image_zoom: ImageZoom,
+
+    /// Decoded image shown when zoomed in, with its pan position
+    zoom_view: Option<ZoomView>,
+
     /// File watcher for theme development mode (only present if theme_dev_mode enabled)
     /// Kept alive to maintain the watch
     _theme_watcher: Option<RecommendedWatcher>,
@@ -127,8 +375,10 @@ pub struct App {
     /// Search input with cursor support
     pub search_input: Input,
 
-    /// Filtered and sorted clip IDs from search
-    /// Empty means show all clips in chronological order
+    /// Clip IDs matching the search query, best match first
+    /// Fuzzy matches arrive from the background search, so until then these are the
+    /// previous query's results. Ignored while the query is empty; `sort_mode`,
+    /// the register filter and grouping are applied on top (see `visible_clips`)
     pub search_results: Vec<u64>,
 
     /// Register key being assigned (when in RegisterAssign mode)
@@ -140,12 +390,55 @@ pub struct App {
     /// Active register filter (None, Temporary, or Permanent)
     pub register_filter: RegisterFilter,
 
+    /// Parsed search query: field filters (`type:image`, `#tag`, ...) and fuzzy text
+    /// A clip must satisfy all field filters to be visible
+    pub search_query: SearchQuery,
+
+    /// Tag input for the tag editing prompt
+    pub tag_input: Input,
+
+    /// Snippet template being filled in (when in TemplateInput mode)
+    pub pending_template: Option<PendingTemplate>,
+
+    /// Input for the current template placeholder prompt
+    pub template_input: Input,
+
+    /// Image conversion prompt (format and options, kept between conversions)
+    pub convert_input: Input,
+
+    /// Clip IDs marked for batch operations, in the order they were marked
+    pub marked: Vec<u64>,
+
+    /// Index where the visual range selection started (when in Visual mode)
+    pub visual_anchor: Option<usize>,
+
+    /// Undo/redo history for destructive actions (u / Ctrl-r)
+    undo_stack: UndoStack,
+
     /// Current view mode (Compact or Comfortable)
     pub view_mode: ViewMode,
+    /// Order of the clip list ('o' cycles; Recent keeps search relevance order)
+    pub sort_mode: SortMode,
+    /// Show the clip list in sections (Pinned, Registers, Today, ...)
+    pub group_clips: bool,
+    /// Bumped whenever the search query or results change
+    search_revision: u64,
+    /// Last filtered and sorted list from `visible_clips`, reused until its key changes
+    visible_cache: RefCell<Option<(VisibleKey, Vec<u64>)>>,
+
+    /// Launcher mode (`clipr pick`): search-first list without preview; the first
+    /// selection ends the TUI and is handed back in `picked` instead of copied
+    pub pick_mode: bool,
+
+    /// Clip chosen in pick mode: its ID and content (snippet templates expanded)
+    pub picked: Option<(u64, ClipContent)>,
 
     /// Startup error message (shown in modal, dismissible with ESC)
     pub startup_error: Option<String>,
 
+    /// Key bindings for the list, preview and overlays
+    pub keymap: Keymap,
+
     /// List area height in terminal rows (updated each frame)
     /// Used to calculate half-page and full-page movements
     list_height: u16,
@@ -176,6 +469,62 @@ pub struct App {
 
     /// Maximum scroll value for help modal (updated each frame)
     help_max_scroll: usize,
+
+    /// Preview scroll offset in rows (None follows the first search match)
+    preview_scroll: Option<usize>,
+
+    /// Preview scroll state from the last render (offset used, max, height)
+    preview_position: ui::preview::PreviewScroll,
+
+    /// Horizontal preview scroll in columns (when wrapping is off)
+    preview_hscroll: usize,
+
+    /// Clip shown in the preview at the last render (scrolling resets on change)
+    preview_clip_id: Option<u64>,
+
+    /// Show line numbers in the preview
+    pub preview_line_numbers: bool,
+
+    /// Wrap long lines in the preview
+    pub preview_wrap: bool,
+
+    /// Pretty-print JSON/XML clips in the preview
+    pub preview_formatted: bool,
+
+    /// Nesting depth beyond which formatted JSON/XML is collapsed (None shows everything)
+    preview_fold: Option<usize>,
+
+    /// Cached formatted view of the previewed clip
+    formatted_preview: Option<FormattedPreview>,
+
+    /// Show the preview as a hex dump
+    pub preview_hex: bool,
+
+    /// Render invisible characters (tabs, CR, zero-width, control) in the preview
+    pub preview_invisibles: bool,
+
+    /// Leading bytes of the previewed file clip, for the hex dump
+    file_head: Option<(u64, Result<FileHead>)>,
+
+    /// Why image clips failed to load (not retried; image data is shown as hex)
+    failed_images: HashMap<u64, String>,
+
+    /// Screen areas from the last render, for mouse clicks
+    mouse_targets: MouseTargets,
+
+    /// Last left click on a clip (time, index), to detect double clicks
+    last_click: Option<(Instant, usize)>,
+}
+
+/// Where things were drawn in the last frame
+#[derive(Default)]
+struct MouseTargets {
+    /// Clip rows of the list (below the header)
+    list: Rect,
+    preview: Rect,
+    hints: Vec<ui::status::HintTarget>,
+    /// Theme picker entries and the first visible one
+    theme_picker: Option<(Rect, usize)>,
 }
 
 impl App {
@@ -193,20 +542,31 @@ impl App {
         let (result_tx, result_rx) = mpsc::channel::<ImageLoadResult>();
 
         // Spawn background thread for image loading
+        let max_preview_bytes = config.general.max_image_preview_size_bytes;
+        let picker = image_protocol.picker;
         std::thread::spawn(move || {
             log::debug!("Image loader thread started");
             while let Ok(request) = load_rx.recv() {
                 log::debug!("Loading image for clip {}", request.clip_id);
 
                 // Decode image and create protocol state
-                let protocol_image = match image::load_from_memory(&request.image_data) {
+                let mut info = None;
+                let protocol_image = match decode_image(request.source, max_preview_bytes) {
                     Ok(img) => {
-                        let protocol = image_protocol.picker.new_resize_protocol(img);
-                        Some(protocol)
+                        info = Some(ImageInfo::of(&img));
+                        let img = match request.thumbnail {
+                            Some((width, height)) => img.thumbnail(width, height),
+                            None => img,
+                        };
+                        Ok(image_protocol.picker.new_resize_protocol(img))
                     }
                     Err(e) => {
-                        log::warn!("Failed to decode image for clip {}: {}", request.clip_id, e);
-                        None
+                        log::warn!(
+                            "Failed to decode image for clip {}: {:#}",
+                            request.clip_id,
+                            e
+                        );
+                        Err(format!("{:#}", e))
                     }
                 };
 
@@ -214,6 +574,8 @@ impl App {
                 if result_tx
                     .send(ImageLoadResult {
                         clip_id: request.clip_id,
+                        thumbnail: request.thumbnail.is_some(),
+                        info,
                         protocol_image,
                     })
                     .is_err()
@@ -226,7 +588,7 @@ impl App {
         });
 
         // Load theme from config
-        let (theme, startup_error) = match Theme::load(&config.general.theme) {
+        let (theme, mut startup_error) = match Theme::load(&config.general.theme) {
             Ok(t) => (t, None),
             Err(e) => {
                 log::error!("Failed to load theme '{}': {}", config.general.theme, e);
@@ -234,6 +596,22 @@ impl App {
             }
         };
 
+        // Key bindings from [keys], with conflicts and typos shown at startup
+        let (keymap, key_problems) = Keymap::new(&config.keys);
+        if !key_problems.is_empty() {
+            for problem in &key_problems {
+                log::warn!("Key bindings: {}", problem);
+            }
+            let message = format!(
+                "Problems with [keys] in config:\n{}",
+                key_problems.join("\n")
+            );
+            startup_error = Some(match startup_error {
+                Some(error) => format!("{}\n\n{}", error, message),
+                None => message,
+            });
+        }
+
         // Set up file watcher for theme development mode
         let (theme_watcher, theme_watch_rx) = if config.general.theme_dev_mode {
             log::info!("Theme development mode enabled - watching for theme file changes");
@@ -274,6 +652,8 @@ impl App {
         let cache_size = NonZeroUsize::new(config.general.image_cache_size)
             .unwrap_or_else(|| NonZeroUsize::new(20).unwrap());
         let image_cache = LruCache::new(cache_size);
+        let thumbnail_cache =
+            LruCache::new(NonZeroUsize::new(crate::image::THUMBNAIL_CACHE_SIZE).unwrap());
 
         // Parse view mode from config
         let view_mode = match config.general.view_mode.to_lowercase().as_str() {
@@ -281,10 +661,18 @@ impl App {
             _ => ViewMode::Compact, // Default to compact for invalid values
         };
 
+        // Parse sort mode from config, defaulting to history order for invalid values
+        let sort_mode = SortMode::from_name(&config.general.sort_mode).unwrap_or(SortMode::Recent);
+        let group_clips = config.general.group_clips;
+        let preview_line_numbers = config.general.preview_line_numbers;
+        let preview_wrap = config.general.preview_wrap;
+        let preview_formatted = config.general.pretty_print;
+        let preview_invisibles = config.general.show_invisibles;
+
         // Store current theme name before moving config
         let current_theme_name = config.general.theme.clone();
 
-        let mut app = App {
+        let app = App {
             mode: AppMode::default(),
             history,
             registers,
@@ -295,6 +683,13 @@ impl App {
             image_cache,
             image_load_tx: load_tx,
             image_load_rx: result_rx,
+            picker,
+            thumbnail_cache,
+            pending_thumbnails: HashSet::new(),
+            pending_images: HashSet::new(),
+            image_info: HashMap::new(),
+            image_zoom: ImageZoom::Fit,
+            zoom_view: None,
             _theme_watcher: theme_watcher,
             theme_watch_rx,
             selected_index: 0,
@@ -303,8 +698,23 @@ impl App {
             register_key: None,
             numeric_prefix: String::new(),
             register_filter: RegisterFilter::None,
+            search_query: SearchQuery::default(),
+            tag_input: Input::default(),
+            pending_template: None,
+            template_input: Input::default(),
+            convert_input: Input::new("jpeg".to_string()),
+            marked: Vec::new(),
+            visual_anchor: None,
+            undo_stack: UndoStack::new(),
             view_mode,
+            sort_mode,
+            group_clips,
+            search_revision: 0,
+            visible_cache: RefCell::new(None),
+            pick_mode: false,
+            picked: None,
             startup_error,
+            keymap,
             list_height: 20, // Default, will be updated each frame
             list_scroll_offset: 0,
             theme_picker_themes: Vec::new(),
@@ -316,11 +726,23 @@ impl App {
             flash_rx,
             help_scroll: 0,
             help_max_scroll: 0,
+            preview_scroll: None,
+            preview_position: ui::preview::PreviewScroll::default(),
+            preview_hscroll: 0,
+            preview_clip_id: None,
+            preview_line_numbers,
+            preview_wrap,
+            preview_formatted,
+            preview_fold: None,
+            formatted_preview: None,
+            preview_hex: false,
+            preview_invisibles,
+            file_head: None,
+            failed_images: HashMap::new(),
+            mouse_targets: MouseTargets::default(),
+            last_click: None,
         };
 
-        // Request initial image load for the first selected clip (if it's an image)
-        app.request_image_load();
-
         Ok(app)
     }
 
@@ -350,9 +772,87 @@ impl App {
         self.flash_messages.clear();
     }
 
+    /// Show an informational flash message regardless of the flash log level
+    fn flash(&mut self, message: String) {
+        log::info!("{}", message);
+        self.flash_messages.push(FlashMessage {
+            level: log::Level::Info,
+            message,
+            timestamp: Instant::now(),
+        });
+    }
+
+    /// Record an undo step for clips about to be changed by an action
+    fn record_undo(&mut self, description: String, clip_ids: &[u64]) {
+        let snapshot = HistorySnapshot::capture(&self.history, clip_ids);
+        self.undo_stack.push(description, snapshot);
+    }
+
+    /// Undo the most recent change to the history
+    pub fn undo(&mut self) {
+        match self.undo_stack.undo(&mut self.history) {
+            Some(description) => {
+                self.after_undo_redo();
+                self.flash(format!("Undid {}", description));
+            }
+            None => self.flash("Already at oldest change".to_string()),
+        }
+    }
+
+    /// Redo the most recently undone change
+    pub fn redo(&mut self) {
+        match self.undo_stack.redo(&mut self.history) {
+            Some(description) => {
+                self.after_undo_redo();
+                self.flash(format!("Redid {}", description));
+            }
+            None => self.flash("Already at newest change".to_string()),
+        }
+    }
+
+    /// Resync registers, search results and selection after restoring clips
+    fn after_undo_redo(&mut self) {
+        self.registers.rebuild_from_history(&self.history);
+        self.clear_marks();
+        if !self.search_input.value().is_empty() {
+            let selected = self.selected_index;
+            self.update_search_results();
+            self.selected_index = selected;
+        }
+        self.clamp_selection();
+    }
+
     /// Get the currently visible clip IDs (either search results or all history)
     /// Applies both search filtering and register filtering
+    /// The list is cached until history, search, filters or sorting change
     pub fn visible_clips(&self) -> Vec<u64> {
+        let key = VisibleKey {
+            history_revision: self.history.revision(),
+            search_revision: self.search_revision,
+            trash: self.mode == AppMode::Trash,
+            sort_mode: self.sort_mode,
+            group_clips: self.group_clips,
+            register_filter: self.register_filter,
+            today: self.group_clips.then(|| Local::now().date_naive()),
+        };
+        if let Some((cached_key, ids)) = &*self.visible_cache.borrow()
+            && *cached_key == key
+        {
+            return ids.clone();
+        }
+
+        let ids = self.filter_and_sort_clips();
+        *self.visible_cache.borrow_mut() = Some((key, ids.clone()));
+        ids
+    }
+
+    /// Compute the visible clip IDs for `visible_clips`
+    fn filter_and_sort_clips(&self) -> Vec<u64> {
+        // The trash view lists trashed clips, unfiltered
+        if self.mode == AppMode::Trash {
+            return self.history.trash.iter().map(|t| t.entry.id).collect();
+        }
+
         let base_clips: Vec<u64> =
             if self.search_results.is_empty() && self.search_input.value().is_empty() {
                 // Show all clips in chronological order (newest first)
@@ -363,7 +863,7 @@ impl App {
             };
 
         // Apply register filter if active
-        match self.register_filter {
+        let filtered: Vec<u64> = match self.register_filter {
             RegisterFilter::None => base_clips,
             RegisterFilter::Temporary => base_clips
                 .into_iter()
@@ -395,6 +895,126 @@ impl App {
                     }
                 })
                 .collect(),
+        };
+
+        // Apply field filters from the search query if active
+        let mut filtered: Vec<u64> = if self.search_query.has_filters() {
+            filtered
+                .into_iter()
+                .filter(|&id| {
+                    self.history
+                        .get_entry(id)
+                        .is_some_and(|entry| self.search_query.matches(entry))
+                })
+                .collect()
+        } else {
+            filtered
+        };
+
+        self.sort_clips(&mut filtered);
+        filtered
+    }
+
+    /// Order visible clips by the sort mode, then by group in the grouped view
+    /// The trash view keeps deletion order
+    fn sort_clips(&self, ids: &mut Vec<u64>) {
+        if self.mode == AppMode::Trash {
+            return;
+        }
+
+        if self.sort_mode == SortMode::Frecency {
+            self.sort_by_frecency(ids);
+        } else if self.sort_mode != SortMode::Recent {
+            let mut entries: Vec<&ClipEntry> = ids
+                .iter()
+                .filter_map(|&id| self.history.get_entry(id))
+                .collect();
+            match self.sort_mode {
+                SortMode::Oldest => entries.sort_by_key(|e| e.timestamp),
+                SortMode::MostUsed => {
+                    entries.sort_by_key(|e| std::cmp::Reverse((e.use_count, e.last_used)))
+                }
+                SortMode::Size => {
+                    entries.sort_by_cached_key(|e| std::cmp::Reverse(e.content.size_bytes()))
+                }
+                SortMode::Name => entries.sort_by_cached_key(|e| {
+                    (e.name.is_none(), e.name.as_ref().map(|n| n.to_lowercase()))
+                }),
+                SortMode::Type => entries.sort_by_key(|e| match e.content {
+                    ClipContent::Text(_) => 0,
+                    ClipContent::Image { .. } => 1,
+                    ClipContent::File { .. } => 2,
+                }),
+                SortMode::Recent | SortMode::Frecency => {}
+            }
+            *ids = entries.iter().map(|e| e.id).collect();
+        }
+
+        if self.group_clips {
+            let today = Local::now().date_naive();
+            ids.sort_by_cached_key(|&id| {
+                self.history
+                    .get_entry(id)
+                    .map_or(ClipGroup::Older, |e| ClipGroup::of(e, today))
+            });
+        }
+    }
+
+    /// Section headers for the grouped view: (index of the group's first clip, group)
+    fn clip_groups(&self, ids: &[u64]) -> Vec<(usize, ClipGroup)> {
+        if !self.group_clips || self.mode == AppMode::Trash {
+            return Vec::new();
+        }
+
+        let today = Local::now().date_naive();
+        let mut groups: Vec<(usize, ClipGroup)> = Vec::new();
+        for (index, &id) in ids.iter().enumerate() {
+            let Some(entry) = self.history.get_entry(id) else {
+                continue;
+            };
+            let group = ClipGroup::of(entry, today);
+            if groups.last().is_none_or(|&(_, last)| last != group) {
+                groups.push((index, group));
+            }
+        }
+        groups
+    }
+
+    /// Order clips by frecency
+    /// Fuzzy results are in relevance order, so their rank is blended in
+    /// rather than discarded
+    fn sort_by_frecency(&self, ids: &mut [u64]) {
+        let ranked = !self.search_query.fuzzy.is_empty()
+            && self.search_index.match_mode() == MatchMode::Fuzzy;
+        let now = SystemTime::now();
+
+        let mut keyed: Vec<(f64, u64)> = ids
+            .iter()
+            .enumerate()
+            .map(|(rank, &id)| {
+                let frecency = self.history.get_entry(id).map_or(0.0, |e| e.frecency(now));
+                let relevance = if ranked {
+                    1.0 / (1.0 + rank as f64 / 8.0)
+                } else {
+                    1.0
+                };
+                (frecency * relevance, id)
+            })
+            .collect();
+
+        // Stable sort keeps the current order for equal scores
+        keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+        for (slot, (_, id)) in ids.iter_mut().zip(keyed) {
+            *slot = id;
+        }
+    }
+
+    /// Look up a visible clip (from the trash when in the trash view)
+    fn visible_entry(&self, id: u64) -> Option<&ClipEntry> {
+        if self.mode == AppMode::Trash {
+            self.history.get_trashed(id)
+        } else {
+            self.history.get_entry(id)
         }
     }
 
@@ -404,6 +1024,49 @@ impl App {
         visible.get(self.selected_index).copied()
     }
 
+    /// Get the clip IDs covered by the visual range selection
+    fn visual_range_ids(&self) -> Vec<u64> {
+        let Some(anchor) = self.visual_anchor else {
+            return Vec::new();
+        };
+        let visible = self.visible_clips();
+        let start = anchor.min(self.selected_index);
+        let end = anchor.max(self.selected_index);
+        visible
+            .get(start..=end.min(visible.len().saturating_sub(1)))
+            .map(|ids| ids.to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Get the set of clips shown as marked (marks plus the visual range)
+    pub fn marked_set(&self) -> HashSet<u64> {
+        let mut set: HashSet<u64> = self.marked.iter().copied().collect();
+        set.extend(self.visual_range_ids());
+        set
+    }
+
+    /// Get the clips an action should apply to
+    /// Marked clips (and the visual range) in list order, otherwise the selected clip
+    fn target_clip_ids(&self) -> Vec<u64> {
+        let marked = self.marked_set();
+        if marked.is_empty() {
+            return self.selected_clip_id().into_iter().collect();
+        }
+
+        // Visible clips first, in list order, then marked clips hidden by the filter
+        let mut ids: Vec<u64> = self
+            .visible_clips()
+            .into_iter()
+            .filter(|id| marked.contains(id))
+            .collect();
+        for &id in &self.marked {
+            if !ids.contains(&id) && self.history.get_entry(id).is_some() {
+                ids.push(id);
+            }
+        }
+        ids
+    }
+
     /// Calculate the number of entries for a half-page movement
     /// Takes into account the view mode (Comfortable uses 3 rows per entry)
     fn half_page_size(&self) -> usize {
@@ -428,17 +1091,9 @@ impl App {
 
     /// Update scroll offset to keep selected item visible with scroll padding
     /// Maintains 3 rows of context above/below selection (vim-style)
-    fn update_scroll_offset(&mut self, total_visible_rows: usize) {
+    fn update_scroll_offset(&mut self, total_visible_rows: usize, selected_row: usize) {
         const SCROLL_PADDING: usize = 3;
 
-        let rows_per_entry = match self.view_mode {
-            ViewMode::Compact => 1,
-            ViewMode::Comfortable => 3, // Each entry takes 3 rows
-        };
-
-        // Calculate the row index of the selected item
-        let selected_row = self.selected_index * rows_per_entry;
-
         // Calculate visible height in rows
         let visible_rows = self.list_height as usize;
         if visible_rows == 0 {
@@ -473,26 +1128,105 @@ impl App {
 
     /// Request async loading of the currently selected image
     /// If the selected clip is an image and not already cached, sends a load request
-    fn request_image_load(&mut self) {
+    /// (never in pick mode, which has no preview)
+    pub fn request_image_load(&mut self) {
+        if self.pick_mode {
+            return;
+        }
         if let Some(clip_id) = self.selected_clip_id() {
             // Check if already cached
             if self.image_cache.contains(&clip_id) {
                 log::debug!("Image {} already cached", clip_id);
                 return; // Already cached
             }
+            if self.pending_images.contains(&clip_id) {
+                return; // Already loading
+            }
+            if self.failed_images.contains_key(&clip_id) {
+                return; // Shown as hex or an error instead
+            }
 
-            // Check if this is an image clip
-            if let Some(entry) = self.history.get_entry(clip_id)
-                && let crate::models::ClipContent::Image { data, .. } = &entry.content
-            {
-                log::debug!("Requesting async load for clip {}", clip_id);
-                // Send load request (non-blocking)
-                let _ = self.image_load_tx.send(ImageLoadRequest {
+            // Check if this is an image clip or a reference to an image file
+            let Some(source) = self.visible_entry(clip_id).and_then(image_source) else {
+                return;
+            };
+
+            log::debug!("Requesting async load for clip {}", clip_id);
+            // Send load request (non-blocking)
+            if self
+                .image_load_tx
+                .send(ImageLoadRequest {
                     clip_id,
-                    image_data: data.clone(),
-                });
+                    source,
+                    thumbnail: None,
+                })
+                .is_ok()
+            {
+                self.pending_images.insert(clip_id);
+            }
+        }
+    }
+
+    /// Request list thumbnails for image clips that aren't cached or loading yet
+    fn request_thumbnails(&mut self, clip_ids: &[u64]) {
+        if self.pick_mode {
+            return;
+        }
+        let (font_width, font_height) = self.picker.font_size();
+        let size = (
+            crate::image::THUMBNAIL_COLS as u32 * font_width as u32,
+            crate::image::THUMBNAIL_ROWS as u32 * font_height as u32,
+        );
+        for &clip_id in clip_ids {
+            if self.thumbnail_cache.contains(&clip_id)
+                || self.pending_thumbnails.contains(&clip_id)
+                || self.failed_images.contains_key(&clip_id)
+            {
+                continue;
+            }
+            let Some(source) = self.visible_entry(clip_id).and_then(image_source) else {
+                continue;
+            };
+            self.pending_thumbnails.insert(clip_id);
+            let _ = self.image_load_tx.send(ImageLoadRequest {
+                clip_id,
+                source,
+                thumbnail: Some(size),
+            });
+        }
+    }
+
+    /// Change how the previewed image is scaled
+    /// Zooming in decodes the image on the spot so the visible region can be cropped from it
+    fn set_image_zoom(&mut self, zoom: ImageZoom) {
+        if zoom == ImageZoom::Fit {
+            self.image_zoom = zoom;
+            self.zoom_view = None;
+            return;
+        }
+        let Some(clip_id) = self.selected_clip_id() else {
+            return;
+        };
+        if self
+            .zoom_view
+            .as_ref()
+            .is_none_or(|view| view.clip_id != clip_id)
+        {
+            let Some(source) = self.visible_entry(clip_id).and_then(image_source) else {
+                return;
+            };
+            match decode_image(source, self.config.general.max_image_preview_size_bytes) {
+                Ok(img) => {
+                    self.image_info.insert(clip_id, ImageInfo::of(&img));
+                    self.zoom_view = Some(ZoomView::new(clip_id, img));
+                }
+                Err(e) => {
+                    log::warn!("Can't zoom image: {:#}", e);
+                    return;
+                }
             }
         }
+        self.image_zoom = zoom;
     }
 
     /// Poll for completed image loads and update cache
@@ -500,39 +1234,73 @@ impl App {
     pub fn update_image_cache(&mut self) {
         // Check for any completed image loads (non-blocking)
         while let Ok(result) = self.image_load_rx.try_recv() {
-            if let Some(protocol_image) = result.protocol_image {
-                log::debug!("Caching loaded image for clip {}", result.clip_id);
-                // Add to LRU cache (automatically evicts least recently used if full)
-                self.image_cache.put(result.clip_id, protocol_image);
+            if let Some(info) = result.info {
+                self.image_info.insert(result.clip_id, info);
+            }
+            if result.thumbnail {
+                self.pending_thumbnails.remove(&result.clip_id);
             } else {
-                log::warn!("Failed to load image for clip {}", result.clip_id);
-                // Don't cache failed loads
+                self.pending_images.remove(&result.clip_id);
+            }
+            match result.protocol_image {
+                Ok(protocol_image) if result.thumbnail => {
+                    self.thumbnail_cache.put(result.clip_id, protocol_image);
+                }
+                Ok(protocol_image) => {
+                    log::debug!("Caching loaded image for clip {}", result.clip_id);
+                    // Add to LRU cache (automatically evicts least recently used if full)
+                    self.image_cache.put(result.clip_id, protocol_image);
+                }
+                Err(e) => {
+                    // Don't cache failed loads, the preview shows the error or a hex dump
+                    self.failed_images.insert(result.clip_id, e);
+                }
             }
         }
     }
 
     /// Update search results based on current query
     pub fn update_search_results(&mut self) {
+        self.search_revision += 1;
         let query = self.search_input.value();
         if query.is_empty() {
+            self.search_index.cancel_background();
             self.search_results.clear();
+            self.search_query = SearchQuery::default();
             self.selected_index = 0;
             self.request_image_load();
             return;
         }
 
-        // Perform fuzzy search
-        let results = self.search_index.search(self.history.entries(), query);
+        // Split field filters (type:, pinned:, #tag, ...) from the fuzzy query
+        self.search_query = SearchQuery::parse(query);
 
-        // Extract just the clip IDs
-        self.search_results = results.into_iter().map(|(id, _score)| id).collect();
+        // Fuzzy searches run in the background; results arrive via poll_search_results
+        // Until then the previous results stay visible
+        if let Some(results) = self
+            .search_index
+            .search_background(self.history.entries(), &self.search_query.fuzzy)
+        {
+            self.search_results = results.into_iter().map(|(id, _score)| id).collect();
+        }
 
         // Reset selection to top of results
         self.selected_index = 0;
+        self.poll_search_results();
         self.request_image_load();
     }
 
+    /// Pick up results from the background search once they are ready
+    pub fn poll_search_results(&mut self) {
+        if let Some(ids) = self.search_index.poll_results() {
+            self.search_results = ids;
+            self.search_revision += 1;
+            self.clamp_selection();
+        }
+    }
+
     /// Select the currently highlighted entry and copy to clipboard
+    /// Permanent register snippets with placeholders are expanded first
     pub fn select_entry(&mut self) -> Result<()> {
         let clip_id = self.selected_clip_id().context("No clip selected")?;
 
@@ -541,20 +1309,33 @@ impl App {
             .get_entry(clip_id)
             .context("Clip not found in history")?;
 
+        if let Some(template) = snippet_template(entry) {
+            return self.start_template(clip_id, template, ClipAction::Copy);
+        }
+
+        // The picker hands the clip back instead of copying it
+        if self.pick_mode {
+            self.picked = Some((clip_id, entry.content.clone()));
+            self.history.record_use(clip_id);
+            self.should_quit = true;
+            return Ok(());
+        }
+
         // Copy to clipboard using backend
         match &entry.content {
-            crate::models::ClipContent::Text(text) => {
+            ClipContent::Text(text) => {
                 self.clipboard_backend.write_text(text)?;
             }
-            crate::models::ClipContent::Image { data, .. } => {
-                self.clipboard_backend.write_image(data)?;
+            ClipContent::Image { data, mime_type } => {
+                self.clipboard_backend.write_image(data, mime_type)?;
             }
-            crate::models::ClipContent::File { .. } => {
+            ClipContent::File { .. } => {
                 // For file references, we would copy the file path as text
                 // This is a simplified implementation
                 anyhow::bail!("File clipboard entries not yet supported for selection");
             }
         }
+        self.history.record_use(clip_id);
 
         // Exit if configured to do so
         if self.config.general.exit_on_select {
@@ -576,61 +1357,395 @@ impl App {
             .get_entry(clip_id)
             .context("Clip not found in history")?;
 
+        if let Some(template) = snippet_template(entry) {
+            return self.start_template(clip_id, template, ClipAction::Paste);
+        }
+
         self.paste_request = PasteRequest::PasteFromClipboard(entry.content.clone());
+        self.history.record_use(clip_id);
         self.should_quit = true;
 
         Ok(())
     }
 
-    /// Toggle pin status of currently selected clip
-    pub fn toggle_pin(&mut self) -> Result<()> {
-        let clip_id = self.selected_clip_id().context("No clip selected")?;
+    /// Begin expanding a snippet template
+    /// Prompts for `{{input:...}}` values first if the template has any
+    fn start_template(
+        &mut self,
+        clip_id: u64,
+        template: Template,
+        action: ClipAction,
+    ) -> Result<()> {
+        let mut context = TemplateContext::default();
+        if template.uses_clipboard() {
+            context.clipboard = match self.clipboard_backend.read_text() {
+                Ok(text) => Some(text),
+                Err(e) => {
+                    log::warn!("Failed to read clipboard for template: {}", e);
+                    None
+                }
+            };
+        }
+
+        let pending = PendingTemplate {
+            clip_id,
+            labels: template.input_labels(),
+            template,
+            action,
+            context,
+        };
 
-        self.history.toggle_pin(clip_id)?;
+        if pending.current_label().is_none() {
+            return self.finish_template(pending);
+        }
 
+        self.pending_template = Some(pending);
+        self.template_input.reset();
+        self.mode = AppMode::TemplateInput;
         Ok(())
     }
 
-    /// Delete the currently selected clip
-    /// Cannot delete clips with permanent registers
-    pub fn delete_entry(&mut self) -> Result<()> {
-        let clip_id = self.selected_clip_id().context("No clip selected")?;
-
-        // Check if clip can be deleted (no permanent registers)
-        let entry = self
-            .history
-            .get_entry(clip_id)
-            .context("Clip not found in history")?;
+    /// Render a completed template and copy or paste the result
+    fn finish_template(&mut self, pending: PendingTemplate) -> Result<()> {
+        let text = pending.template.render(&pending.context)?;
 
-        if !entry.can_delete() {
-            anyhow::bail!("Cannot delete clips with permanent registers");
+        match pending.action {
+            ClipAction::Copy if self.pick_mode => {
+                self.picked = Some((pending.clip_id, ClipContent::Text(text)));
+                self.should_quit = true;
+            }
+            ClipAction::Copy => {
+                self.clipboard_backend.write_text(&text)?;
+                if self.config.general.exit_on_select {
+                    self.should_quit = true;
+                }
+            }
+            ClipAction::Paste => {
+                self.paste_request = PasteRequest::PasteFromClipboard(ClipContent::Text(text));
+                self.should_quit = true;
+            }
         }
+        self.history.record_use(pending.clip_id);
 
-        // Remove any temporary register assignments first
-        let temp_regs: Vec<char> = entry.temporary_registers.clone();
-        for key in temp_regs {
-            self.registers.remove_temporary(key, &mut self.history)?;
-        }
+        Ok(())
+    }
 
-        // Remove from history
-        self.history.remove_entry(clip_id);
+    /// Store the value typed for the current template input and advance
+    pub fn submit_template_input(&mut self) -> Result<()> {
+        let Some(mut pending) = self.pending_template.take() else {
+            self.mode = AppMode::Normal;
+            return Ok(());
+        };
 
-        // Adjust selection if needed
-        let visible_count = self.visible_clips().len();
-        if visible_count > 0 && self.selected_index >= visible_count {
-            self.selected_index = visible_count - 1;
+        if let Some(label) = pending.current_label().map(str::to_string) {
+            let value = self.template_input.value().to_string();
+            pending.context.inputs.insert(label, value);
         }
+        self.template_input.reset();
 
-        // Request image load for new selection
-        self.request_image_load();
+        if pending.current_label().is_some() {
+            self.pending_template = Some(pending);
+            return Ok(());
+        }
 
-        Ok(())
+        self.mode = AppMode::Normal;
+        self.finish_template(pending)
     }
 
-    /// Move selection up by n items
-    pub fn move_up(&mut self, n: usize) {
-        self.selected_index = self.selected_index.saturating_sub(n);
-        self.request_image_load();
+    /// Abandon the template currently being filled in
+    pub fn cancel_template(&mut self) {
+        self.pending_template = None;
+        self.template_input.reset();
+        // The picker goes back to its search prompt
+        self.mode = if self.pick_mode {
+            AppMode::Search
+        } else {
+            AppMode::Normal
+        };
+    }
+
+    /// Toggle pin status of the selected or marked clips
+    /// With several clips, all are pinned unless they are all pinned already
+    pub fn toggle_pin(&mut self) -> Result<()> {
+        let clip_ids = self.target_clip_ids();
+        if clip_ids.is_empty() {
+            anyhow::bail!("No clip selected");
+        }
+
+        let pin = !clip_ids
+            .iter()
+            .all(|&id| self.history.get_entry(id).is_some_and(|e| e.pinned));
+
+        let action = if pin { "pin" } else { "unpin" };
+        self.record_undo(
+            format!("{} of {}", action, clip_count(clip_ids.len())),
+            &clip_ids,
+        );
+
+        for clip_id in clip_ids {
+            if let Some(entry) = self.history.get_entry_mut(clip_id) {
+                entry.pinned = pin;
+            }
+        }
+
+        self.clear_marks();
+        Ok(())
+    }
+
+    /// Delete the selected or marked clips
+    /// Clips with permanent registers are skipped
+    pub fn delete_entry(&mut self) -> Result<()> {
+        let clip_ids = self.target_clip_ids();
+        if clip_ids.is_empty() {
+            anyhow::bail!("No clip selected");
+        }
+
+        let deletable: Vec<u64> = clip_ids
+            .iter()
+            .copied()
+            .filter(|&id| self.history.get_entry(id).is_some_and(|e| e.can_delete()))
+            .collect();
+        if !deletable.is_empty() {
+            self.record_undo(
+                format!("delete of {}", clip_count(deletable.len())),
+                &deletable,
+            );
+        }
+
+        let mut deleted = 0;
+        let mut skipped = 0;
+        for clip_id in clip_ids {
+            // Check if clip can be deleted (no permanent registers)
+            let entry = self
+                .history
+                .get_entry(clip_id)
+                .context("Clip not found in history")?;
+
+            if !entry.can_delete() {
+                skipped += 1;
+                continue;
+            }
+
+            // Remove any temporary register assignments first
+            let temp_regs: Vec<char> = entry.temporary_registers.clone();
+            for key in temp_regs {
+                self.registers.remove_temporary(key, &mut self.history)?;
+            }
+
+            // Move to the trash
+            self.history.trash_entry(clip_id);
+            deleted += 1;
+        }
+
+        self.clear_marks();
+
+        // Adjust selection if needed
+        let visible_count = self.visible_clips().len();
+        if visible_count > 0 && self.selected_index >= visible_count {
+            self.selected_index = visible_count - 1;
+        }
+
+        // Request image load for new selection
+        self.request_image_load();
+
+        if skipped > 0 {
+            if deleted == 0 {
+                anyhow::bail!("Cannot delete clips with permanent registers");
+            }
+            log::warn!("Skipped {} clips with permanent registers", skipped);
+        }
+
+        Ok(())
+    }
+
+    /// Toggle the mark on the selected clip and move to the next one
+    pub fn toggle_mark(&mut self) {
+        let Some(clip_id) = self.selected_clip_id() else {
+            return;
+        };
+
+        if let Some(pos) = self.marked.iter().position(|&id| id == clip_id) {
+            self.marked.remove(pos);
+        } else {
+            self.marked.push(clip_id);
+        }
+        self.move_down(1);
+    }
+
+    /// Enter visual mode, anchoring the range at the selected clip
+    pub fn enter_visual_mode(&mut self) {
+        if self.selected_clip_id().is_none() {
+            return;
+        }
+        self.visual_anchor = Some(self.selected_index);
+        self.mode = AppMode::Visual;
+    }
+
+    /// Add the visual range to the marked clips and return to normal mode
+    pub fn mark_visual_range(&mut self) {
+        for clip_id in self.visual_range_ids() {
+            if !self.marked.contains(&clip_id) {
+                self.marked.push(clip_id);
+            }
+        }
+        self.exit_visual_mode();
+    }
+
+    /// Leave visual mode without marking the range
+    pub fn exit_visual_mode(&mut self) {
+        self.visual_anchor = None;
+        if self.mode == AppMode::Visual {
+            self.mode = AppMode::Normal;
+        }
+    }
+
+    /// Clear all marks and any visual range
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+        self.exit_visual_mode();
+    }
+
+    /// Join the text of the selected or marked clips into a new clip and copy it
+    /// Non-text clips are skipped; the separator comes from `join_separator`
+    pub fn join_entries(&mut self) -> Result<()> {
+        let texts: Vec<String> = self
+            .target_clip_ids()
+            .into_iter()
+            .filter_map(|id| match &self.history.get_entry(id)?.content {
+                ClipContent::Text(text) => Some(text.clone()),
+                _ => None,
+            })
+            .collect();
+
+        if texts.is_empty() {
+            anyhow::bail!("No text clips to join");
+        }
+
+        let joined = texts.join(&self.config.general.join_separator);
+        let content = ClipContent::Text(joined.clone());
+
+        // The joined clip is new, or an existing duplicate that moves to the top
+        // A new clip can push the oldest ones into the trash, undo brings them back
+        let snapshot_ids = match self.history.find_by_hash(content.content_hash()) {
+            Some(existing_id) => vec![existing_id],
+            None => {
+                let mut ids = vec![self.history.next_id()];
+                ids.extend(self.history.rotation_candidates());
+                ids
+            }
+        };
+        self.record_undo(
+            format!("join of {}", clip_count(texts.len())),
+            &snapshot_ids,
+        );
+
+        self.history.add_entry(content);
+        self.clipboard_backend.write_text(&joined)?;
+        log::info!("Joined {} clips", texts.len());
+
+        self.clear_marks();
+        self.selected_index = 0;
+        self.request_image_load();
+
+        // Exit if configured to do so
+        if self.config.general.exit_on_select {
+            self.should_quit = true;
+        }
+
+        Ok(())
+    }
+
+    /// Write the selected or marked clips to files in the export directory
+    /// Files are named `clip-<id>.<ext>`; file references are copied
+    pub fn export_entries(&mut self) -> Result<()> {
+        let clip_ids = self.target_clip_ids();
+        if clip_ids.is_empty() {
+            anyhow::bail!("No clip selected");
+        }
+
+        let export_dir = match &self.config.general.export_dir {
+            Some(dir) => dir.clone(),
+            None => crate::storage::data_dir()?.join("exports"),
+        };
+        fs::create_dir_all(&export_dir)
+            .with_context(|| format!("Failed to create export directory {:?}", export_dir))?;
+
+        for &clip_id in &clip_ids {
+            let entry = self
+                .history
+                .get_entry(clip_id)
+                .context("Clip not found in history")?;
+            let path = export_dir.join(format!(
+                "clip-{}.{}",
+                clip_id,
+                entry.content.file_extension()
+            ));
+
+            match &entry.content {
+                ClipContent::Text(text) => fs::write(&path, text),
+                ClipContent::Image { data, .. } => fs::write(&path, data),
+                ClipContent::File { path: source, .. } => fs::copy(source, &path).map(|_| ()),
+            }
+            .with_context(|| format!("Failed to export clip {} to {:?}", clip_id, path))?;
+        }
+
+        log::info!("Exported {} clips to {:?}", clip_ids.len(), export_dir);
+        self.clear_marks();
+
+        Ok(())
+    }
+
+    /// Open the trash view
+    pub fn open_trash(&mut self) {
+        self.clear_marks();
+        self.mode = AppMode::Trash;
+        self.selected_index = 0;
+        self.request_image_load();
+    }
+
+    /// Leave the trash view back to the history list
+    pub fn close_trash(&mut self) {
+        self.mode = AppMode::Normal;
+        self.selected_index = 0;
+        self.request_image_load();
+    }
+
+    /// Move the selected trashed clip back into history
+    pub fn restore_from_trash(&mut self) -> Result<()> {
+        let clip_id = self.selected_clip_id().context("No clip selected")?;
+        let restored_id = self.history.restore_from_trash(clip_id)?;
+
+        if restored_id == clip_id {
+            self.flash(format!("Restored clip {}", clip_id));
+        } else {
+            self.flash(format!("Clip {} is already in history", clip_id));
+        }
+        self.clamp_selection();
+
+        Ok(())
+    }
+
+    /// Permanently delete the selected trashed clip
+    pub fn purge_from_trash(&mut self) -> Result<()> {
+        let clip_id = self.selected_clip_id().context("No clip selected")?;
+        self.history.remove_from_trash(clip_id);
+        self.clamp_selection();
+        Ok(())
+    }
+
+    /// Keep the selection within the visible list after clips were removed
+    fn clamp_selection(&mut self) {
+        let visible_count = self.visible_clips().len();
+        if self.selected_index >= visible_count {
+            self.selected_index = visible_count.saturating_sub(1);
+        }
+        self.request_image_load();
+    }
+
+    /// Move selection up by n items
+    pub fn move_up(&mut self, n: usize) {
+        self.selected_index = self.selected_index.saturating_sub(n);
+        self.request_image_load();
     }
 
     /// Move selection down by n items
@@ -682,8 +1797,11 @@ impl App {
 
     /// Clear search query and results, returning to full history
     pub fn clear_search(&mut self) {
+        self.search_index.cancel_background();
         self.search_input.reset();
         self.search_results.clear();
+        self.search_revision += 1;
+        self.search_query = SearchQuery::default();
         self.selected_index = 0;
         self.request_image_load();
     }
@@ -699,6 +1817,11 @@ impl App {
     pub fn assign_register(&mut self, key: char) -> Result<()> {
         let clip_id = self.selected_clip_id().context("No clip selected")?;
 
+        // Snapshot both this clip and the clip currently holding the register
+        let mut affected = vec![clip_id];
+        affected.extend(self.registers.get_temporary(key));
+        self.record_undo(format!("register '{}'", key), &affected);
+
         // Check if the current clip already has this register
         let clip_has_register = self
             .history
@@ -722,6 +1845,56 @@ impl App {
         Ok(())
     }
 
+    /// Enter tag editing mode for the selected or marked clips
+    pub fn enter_tag_mode(&mut self) {
+        if self.target_clip_ids().is_empty() {
+            return;
+        }
+        self.tag_input.reset();
+        self.mode = AppMode::TagEdit;
+    }
+
+    /// Toggle the tags typed in the tag prompt on the selected or marked clips
+    /// Each whitespace-separated tag is removed if every clip has it, otherwise added
+    pub fn apply_tag_input(&mut self) -> Result<()> {
+        let clip_ids = self.target_clip_ids();
+        if clip_ids.is_empty() {
+            anyhow::bail!("No clip selected");
+        }
+        let input = self.tag_input.value().to_string();
+
+        self.tag_input.reset();
+        self.mode = AppMode::Normal;
+
+        if input.split_whitespace().next().is_some() {
+            self.record_undo(
+                format!("tag edit of {}", clip_count(clip_ids.len())),
+                &clip_ids,
+            );
+        }
+
+        for tag in input.split_whitespace() {
+            let remove = clip_ids
+                .iter()
+                .all(|&id| self.history.get_entry(id).is_some_and(|e| e.has_tag(tag)));
+
+            for &clip_id in &clip_ids {
+                let Some(entry) = self.history.get_entry_mut(clip_id) else {
+                    continue;
+                };
+                if remove {
+                    entry.remove_tag(tag);
+                } else if !entry.has_tag(tag) && !entry.add_tag(tag) {
+                    log::warn!("Invalid tag '{}'", tag);
+                    break;
+                }
+            }
+        }
+
+        self.clear_marks();
+        Ok(())
+    }
+
     /// Toggle help overlay
     pub fn toggle_help(&mut self) {
         self.mode = match self.mode {
@@ -772,6 +1945,21 @@ impl App {
         };
     }
 
+    /// Cycle through sort modes
+    pub fn cycle_sort_mode(&mut self) {
+        self.sort_mode = self.sort_mode.next();
+        self.selected_index = 0;
+        self.request_image_load();
+        self.flash(format!("Sorted by {}", self.sort_mode.name()));
+    }
+
+    /// Toggle the grouped view with section headers
+    pub fn toggle_group_clips(&mut self) {
+        self.group_clips = !self.group_clips;
+        self.selected_index = 0;
+        self.request_image_load();
+    }
+
     /// Enter confirmation mode for clear all
     pub fn enter_confirm_clear_all(&mut self) {
         self.mode = AppMode::Confirm;
@@ -779,6 +1967,21 @@ impl App {
 
     /// Clear all unpinned, non-registered entries
     pub fn clear_all_unpinned(&mut self) {
+        let protect_tagged = self.history.protect_tagged;
+        let removed: Vec<u64> = self
+            .history
+            .entries()
+            .iter()
+            .filter(|e| !e.is_protected(protect_tagged))
+            .map(|e| e.id)
+            .collect();
+        if !removed.is_empty() {
+            self.record_undo(
+                format!("clear all ({})", clip_count(removed.len())),
+                &removed,
+            );
+        }
+
         self.history.clear_unpinned();
         self.selected_index = 0;
         self.mode = AppMode::Normal;
@@ -794,6 +1997,80 @@ impl App {
         self.should_quit = true;
     }
 
+    /// Screen layout: [clip_list, divider, preview, keyboard_hints]
+    /// Pick mode has no divider or preview (empty areas)
+    fn layout(&self, area: Rect) -> Vec<Rect> {
+        if self.pick_mode {
+            let chunks = ui::create_pick_layout(area);
+            vec![chunks[0], Rect::default(), Rect::default(), chunks[1]]
+        } else {
+            ui::create_main_layout(area, self.view_mode)
+        }
+    }
+
+    /// Switch to pick mode: compact list only, typing straight into the search bar
+    pub fn enter_pick_mode(&mut self) {
+        self.pick_mode = true;
+        self.view_mode = ViewMode::Compact;
+        self.enter_search_mode();
+    }
+
+    /// Handle a terminal resize
+    /// Re-fits the list; decoded preview images are kept and re-encoded for the
+    /// new size when drawn (thumbnails have a fixed cell size)
+    pub fn handle_resize(&mut self, width: u16, height: u16) {
+        let chunks = self.layout(Rect::new(0, 0, width, height));
+        let header_height = match self.view_mode {
+            ViewMode::Comfortable => 4,
+            ViewMode::Compact => 1,
+        };
+        self.list_height = chunks[0].height.saturating_sub(header_height);
+
+        let visible_clip_ids = self.visible_clips();
+        let groups = self.clip_groups(&visible_clip_ids);
+        let rows_per_entry = match self.view_mode {
+            ViewMode::Compact => 1,
+            ViewMode::Comfortable => 3,
+        };
+        let total_visible_rows = visible_clip_ids.len() * rows_per_entry + groups.len();
+        let selected_row = ui::clip_list::list_row(self.selected_index, rows_per_entry, &groups);
+        self.update_scroll_offset(total_visible_rows, selected_row);
+
+        // Cached images stay decoded: StatefulImage re-fits a protocol to the
+        // new preview area on the next draw
+        self.request_image_load();
+    }
+
+    /// Handle bracketed paste: insert the text into the prompt being edited
+    /// Line breaks and tabs become spaces; other modes ignore pastes
+    pub fn handle_paste(&mut self, text: &str) {
+        let input = match self.mode {
+            AppMode::Search => &mut self.search_input,
+            AppMode::TagEdit => &mut self.tag_input,
+            AppMode::Convert => &mut self.convert_input,
+            AppMode::TemplateInput => &mut self.template_input,
+            _ => return,
+        };
+        for c in text.chars() {
+            let c = if c.is_whitespace() { ' ' } else { c };
+            if !c.is_control() {
+                input.handle(tui_input::InputRequest::InsertChar(c));
+            }
+        }
+        if self.mode == AppMode::Search {
+            self.update_search_results();
+        }
+    }
+
+    /// Handle terminal focus changes: quit when focus is lost if configured
+    /// (for clipr running in a floating popup terminal)
+    pub fn handle_focus(&mut self, focused: bool) {
+        if !focused && self.config.general.quit_on_focus_lost {
+            log::debug!("Terminal lost focus, quitting");
+            self.quit();
+        }
+    }
+
     /// Reload theme from config file
     /// Performs atomic swap: load → validate → apply only if valid
     /// On error, displays error modal and keeps previous theme
@@ -870,194 +2147,753 @@ impl App {
             AppMode::Help => self.handle_help_key(key),
             AppMode::Numeric => self.handle_numeric_key(key),
             AppMode::ThemePicker => self.handle_theme_picker_key(key),
+            AppMode::TagEdit => self.handle_tag_key(key),
+            AppMode::TemplateInput => self.handle_template_key(key),
+            AppMode::Visual => self.handle_visual_key(key),
+            AppMode::Trash => self.handle_trash_key(key),
+            AppMode::Preview => self.handle_preview_key(key),
+            AppMode::Convert => self.handle_convert_key(key),
+        }
+    }
+
+    /// Handle mouse event: clicks select, double clicks copy, the wheel scrolls
+    pub fn handle_mouse(&mut self, event: MouseEvent) -> Result<()> {
+        const DOUBLE_CLICK_MS: u128 = 400;
+        const WHEEL_PREVIEW_ROWS: isize = 3;
+
+        if self.startup_error.is_some() {
+            return Ok(());
+        }
+        let position = Position::new(event.column, event.row);
+        let on_list = self.mouse_targets.list.contains(position);
+        let on_preview = self.mouse_targets.preview.contains(position);
+        let browsing = matches!(
+            self.mode,
+            AppMode::Normal | AppMode::Visual | AppMode::Trash | AppMode::Preview
+        );
+
+        match event.kind {
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+                let down = event.kind == MouseEventKind::ScrollDown;
+                let action = if down { Action::Down } else { Action::Up };
+                match self.mode {
+                    // Overlays scroll with the wheel wherever the pointer is
+                    AppMode::Help => return self.press_action(KeyScope::Help, action),
+                    AppMode::ThemePicker => {
+                        return self.press_action(KeyScope::ThemePicker, action);
+                    }
+                    _ if !browsing => {}
+                    _ if on_preview => {
+                        let rows = if down {
+                            WHEEL_PREVIEW_ROWS
+                        } else {
+                            -WHEEL_PREVIEW_ROWS
+                        };
+                        self.scroll_preview(rows);
+                    }
+                    _ if on_list && down => self.move_down(1),
+                    _ if on_list => self.move_up(1),
+                    _ => {}
+                }
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(target) = self
+                    .mouse_targets
+                    .hints
+                    .iter()
+                    .find(|target| target.area.contains(position))
+                {
+                    return self.handle_key(target.key);
+                }
+
+                if self.mode == AppMode::ThemePicker {
+                    if let Some((area, offset)) = self.mouse_targets.theme_picker
+                        && area.contains(position)
+                    {
+                        let index = offset + (position.y - area.y) as usize;
+                        if index < self.theme_picker_themes.len() {
+                            // First click selects, clicking the selected theme applies it
+                            if index == self.theme_picker_selected {
+                                self.select_theme_from_picker();
+                            }
+                            self.theme_picker_selected = index;
+                        }
+                    }
+                    return Ok(());
+                }
+
+                if !browsing {
+                    return Ok(());
+                }
+                if on_preview {
+                    if self.mode == AppMode::Normal {
+                        self.mode = AppMode::Preview;
+                    }
+                    return Ok(());
+                }
+                if !on_list {
+                    return Ok(());
+                }
+
+                let visible = self.visible_clips();
+                let rows_per_entry = match self.view_mode {
+                    ViewMode::Compact => 1,
+                    ViewMode::Comfortable => 3,
+                };
+                let row =
+                    self.list_scroll_offset + (position.y - self.mouse_targets.list.y) as usize;
+                let groups = self.clip_groups(&visible);
+                let Some(index) =
+                    ui::clip_list::clip_at_row(row, rows_per_entry, &groups, visible.len())
+                else {
+                    return Ok(());
+                };
+
+                if self.mode == AppMode::Preview {
+                    self.mode = AppMode::Normal;
+                }
+                let double_click = self.last_click.is_some_and(|(at, clicked)| {
+                    clicked == index && at.elapsed().as_millis() < DOUBLE_CLICK_MS
+                });
+                self.selected_index = index;
+                self.request_image_load();
+                if double_click {
+                    // Copy, or restore in the trash
+                    self.last_click = None;
+                    return match self.mode {
+                        AppMode::Normal => self.select_entry(),
+                        AppMode::Trash => self.press_action(KeyScope::Trash, Action::Restore),
+                        _ => Ok(()),
+                    };
+                }
+                self.last_click = Some((Instant::now(), index));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Act as if the first key bound to an action was pressed (nothing if it's unbound)
+    fn press_action(&mut self, scope: KeyScope, action: Action) -> Result<()> {
+        match self.keymap.keys(scope, action).first() {
+            Some(chord) => self.handle_key(KeyEvent::from(*chord)),
+            None => Ok(()),
         }
     }
 
     /// Handle keys in normal mode (vim-style navigation)
     fn handle_normal_key(&mut self, key: KeyEvent) -> Result<()> {
-        match key.code {
+        let Some(action) = self.keymap.action(KeyScope::Normal, key) else {
             // Entering a digit starts Numeric mode
-            KeyCode::Char(c) if c.is_ascii_digit() && key.modifiers.is_empty() => {
+            if let KeyCode::Char(c) = key.code
+                && c.is_ascii_digit()
+                && key.modifiers.is_empty()
+            {
                 self.numeric_prefix.push(c);
                 self.mode = AppMode::Numeric;
             }
+            // Unknown keys do nothing in Normal mode
+            return Ok(());
+        };
 
+        match action {
             // Vim navigation (simple - no numeric prefix in Normal mode)
-            KeyCode::Char('j') | KeyCode::Down => {
+            Action::Down => {
                 self.move_down(1);
             }
-            KeyCode::Char('k') | KeyCode::Up => {
+            Action::Up => {
                 self.move_up(1);
             }
-            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::HalfPageDown => {
                 let count = self.half_page_size();
                 self.move_down(count);
             }
-            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::HalfPageUp => {
                 let count = self.half_page_size();
                 self.move_up(count);
             }
-            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                // Reload theme from config file
-                let _ = self.reload_theme();
+            Action::PageUp => {
+                let count = self.full_page_size();
+                self.move_up(count);
             }
-
-            // Home/End for jump to top/bottom (replacing gg/G)
-            KeyCode::Home => {
+            Action::PageDown => {
+                let count = self.full_page_size();
+                self.move_down(count);
+            }
+            Action::Top => {
                 self.jump_to_top();
             }
-            KeyCode::End => {
+            Action::Bottom => {
                 self.jump_to_bottom();
             }
-
-            // PageUp/PageDown
-            KeyCode::PageUp => {
-                let count = self.full_page_size();
-                self.move_up(count);
+            Action::Redo => {
+                self.redo();
             }
-            KeyCode::PageDown => {
-                let count = self.full_page_size();
-                self.move_down(count);
+            Action::Undo => {
+                self.undo();
+            }
+            Action::ReloadTheme => {
+                // Reload theme from config file
+                let _ = self.reload_theme();
             }
 
             // Actions
-            KeyCode::Enter => {
+            Action::Copy => {
                 self.select_entry()?;
             }
-            KeyCode::Char(' ') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                // Ctrl-Space - paste entry via Ctrl-V simulation
-                self.paste_entry()?;
+            Action::Paste => {
+                // Paste entry via Ctrl-V simulation
+                self.paste_entry()?;
+            }
+            Action::ToggleMark => {
+                self.toggle_mark();
+            }
+            Action::Visual => {
+                self.enter_visual_mode();
+            }
+            Action::Join => {
+                self.join_entries()?;
+            }
+            Action::Export => {
+                self.export_entries()?;
+            }
+            Action::CopyPretty => {
+                if let Err(e) = self.copy_formatted(false) {
+                    log::warn!("{:#}", e);
+                }
+            }
+            Action::CopyMinified => {
+                if let Err(e) = self.copy_formatted(true) {
+                    log::warn!("{:#}", e);
+                }
+            }
+            Action::Convert => {
+                self.enter_convert_mode();
+            }
+            Action::AssignRegister => {
+                self.enter_register_mode();
+            }
+            Action::TogglePin => {
+                self.toggle_pin()?;
+            }
+            Action::PinnedFilter => {
+                self.toggle_pinned_filter();
+            }
+            Action::ClearMessages => {
+                self.clear_flash_messages();
+            }
+            Action::TestMessages => {
+                // Test flash messages at different levels
+                log::trace!("Test trace message");
+                log::debug!("Test debug message");
+                log::info!("Test info message");
+                log::warn!("Test warning message");
+                log::error!("Test error message");
+            }
+            Action::Search => {
+                self.enter_search_mode();
+            }
+            Action::TagSearch => {
+                // Start a tag filter in the search bar
+                self.enter_search_mode();
+                if !self.search_input.value().is_empty() {
+                    self.search_input
+                        .handle(tui_input::InputRequest::InsertChar(' '));
+                }
+                self.search_input
+                    .handle(tui_input::InputRequest::InsertChar('#'));
+                self.update_search_results();
+            }
+            Action::EditTags => {
+                self.enter_tag_mode();
+            }
+            Action::Help => {
+                self.toggle_help();
+            }
+            Action::TemporaryFilter => {
+                self.toggle_temporary_filter();
+            }
+            Action::PermanentFilter => {
+                self.toggle_permanent_filter();
+            }
+            Action::ToggleView => {
+                self.toggle_view_mode();
+            }
+            Action::CycleSort => {
+                self.cycle_sort_mode();
+            }
+            Action::FocusPreview => {
+                self.mode = AppMode::Preview;
+            }
+            Action::ToggleGroups => {
+                self.toggle_group_clips();
+            }
+            Action::SaveTheme => {
+                let _ = self.save_theme_as_default();
+            }
+            Action::CycleTheme => {
+                self.cycle_theme();
+            }
+            Action::ThemePicker => {
+                self.open_theme_picker();
+            }
+            Action::Delete => {
+                // Delete entry - silently ignore errors (e.g., can't delete permanent register clips)
+                let _ = self.delete_entry();
+            }
+            Action::ClearAll => {
+                self.enter_confirm_clear_all();
+            }
+            Action::Trash => {
+                self.open_trash();
+            }
+            Action::Quit => {
+                self.quit();
+            }
+            Action::Back => {
+                // Clears in order: marks, search filter (including tags), register filter, then quit
+                if !self.marked.is_empty() {
+                    self.clear_marks();
+                } else if !self.search_input.value().is_empty() {
+                    self.clear_search();
+                } else if self.register_filter != RegisterFilter::None {
+                    self.register_filter = RegisterFilter::None;
+                    self.selected_index = 0;
+                } else {
+                    self.quit();
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Handle keys in search mode
+    fn handle_search_key(&mut self, key: KeyEvent) -> Result<()> {
+        match self.keymap.action(KeyScope::Search, key) {
+            Some(Action::Up) => {
+                self.move_up(1);
+            }
+            Some(Action::Down) => {
+                self.move_down(1);
+            }
+            Some(Action::Back) if self.pick_mode => {
+                // Cancel the picker with nothing picked
+                self.quit();
+            }
+            Some(Action::Back) => {
+                self.exit_search_mode();
+            }
+            Some(Action::Copy) => {
+                self.mode = AppMode::Normal;
+                self.select_entry()?;
+            }
+            Some(Action::Paste) => {
+                // Paste entry via Ctrl-V simulation
+                self.paste_entry()?;
+            }
+            Some(Action::MatchMode) => {
+                // Cycle fuzzy/exact/regex/prefix matching
+                self.search_index.cycle_match_mode();
+                self.update_search_results();
+            }
+            Some(Action::CaseSensitive) => {
+                // Toggle smart case / case-sensitive
+                self.search_index.toggle_mode();
+                self.update_search_results();
+            }
+            _ => {
+                // Delegate all other keys to tui-input (characters, backspace,
+                // delete, left/right arrows, Ctrl+A/E, Ctrl+W, etc.)
+                let event = Event::Key(key);
+                if self.search_input.handle_event(&event).is_some() {
+                    self.update_search_results();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Scroll the preview by a number of rows (negative scrolls up)
+    fn scroll_preview(&mut self, rows: isize) {
+        let position = self.preview_position;
+        let offset = position
+            .offset
+            .saturating_add_signed(rows)
+            .min(position.max);
+        self.preview_scroll = Some(offset);
+    }
+
+    /// Copy the selected JSON/XML clip to the clipboard pretty-printed or minified
+    pub fn copy_formatted(&mut self, minified: bool) -> Result<()> {
+        let clip_id = self.selected_clip_id().context("No clip selected")?;
+        let entry = self
+            .history
+            .get_entry(clip_id)
+            .context("Clip not found in history")?;
+        let (Some(format), ClipContent::Text(text)) =
+            (StructuredFormat::for_entry(entry), &entry.content)
+        else {
+            anyhow::bail!("Clip is not JSON or XML");
+        };
+
+        let formatted = if minified {
+            format.minify(text)
+        } else {
+            format.pretty(text, None)
+        }
+        .with_context(|| format!("Invalid {}", format.name()))?;
+        self.clipboard_backend.write_text(&formatted)?;
+        log::info!(
+            "Copied {} {}",
+            if minified {
+                "minified"
+            } else {
+                "pretty-printed"
+            },
+            format.name()
+        );
+
+        // Exit if configured to do so
+        if self.config.general.exit_on_select {
+            self.should_quit = true;
+        }
+
+        Ok(())
+    }
+
+    /// Open the conversion prompt if the selected clip is an image
+    pub fn enter_convert_mode(&mut self) {
+        let is_image = self
+            .selected_clip_id()
+            .and_then(|id| self.visible_entry(id))
+            .is_some_and(|entry| entry.content.mime_type().starts_with("image/"));
+        if is_image {
+            self.mode = AppMode::Convert;
+        } else {
+            log::warn!("Only image clips can be converted");
+        }
+    }
+
+    /// Re-encode the selected image as typed in the conversion prompt and copy it
+    pub fn copy_converted_image(&mut self) -> Result<()> {
+        let options = ConvertOptions::parse(self.convert_input.value())?;
+        let clip_id = self.selected_clip_id().context("No clip selected")?;
+        let source = self
+            .visible_entry(clip_id)
+            .and_then(image_source)
+            .context("Clip is not an image")?;
+        let data = match source {
+            ImageSource::Memory(data) => data,
+            ImageSource::File(path) => {
+                fs::read(&path).with_context(|| format!("Failed to read {:?}", path))?
+            }
+        };
+
+        let converted = crate::image::convert::convert(&data, &options)?;
+        self.clipboard_backend
+            .write_image(&converted, options.format.mime_type())?;
+        log::info!(
+            "Copied image as {} ({} bytes)",
+            options.format.name(),
+            converted.len()
+        );
+
+        // Exit if configured to do so
+        if self.config.general.exit_on_select {
+            self.should_quit = true;
+        }
+
+        Ok(())
+    }
+
+    /// Handle keys while the preview has focus (list selection is kept)
+    fn handle_preview_key(&mut self, key: KeyEvent) -> Result<()> {
+        const HSCROLL_STEP: usize = 8;
+        let half_page = (self.preview_position.height / 2).max(1) as isize;
+        let full_page = self.preview_position.height.max(1) as isize;
+
+        let Some(action) = self.keymap.action(KeyScope::Preview, key) else {
+            return Ok(());
+        };
+
+        // Movement keys pan a zoomed image instead of scrolling
+        if self.image_zoom != ImageZoom::Fit
+            && let Some(view) = self.zoom_view.as_mut()
+        {
+            let step = match action {
+                Action::Left => Some((-1, 0)),
+                Action::Right => Some((1, 0)),
+                Action::Up => Some((0, -1)),
+                Action::Down => Some((0, 1)),
+                _ => None,
+            };
+            if let Some((dx, dy)) = step {
+                view.pan(dx, dy);
+                return Ok(());
+            }
+        }
+
+        match action {
+            Action::Down => self.scroll_preview(1),
+            Action::Up => self.scroll_preview(-1),
+            Action::HalfPageDown => self.scroll_preview(half_page),
+            Action::HalfPageUp => self.scroll_preview(-half_page),
+            Action::PageDown => self.scroll_preview(full_page),
+            Action::PageUp => self.scroll_preview(-full_page),
+            Action::Top => self.preview_scroll = Some(0),
+            Action::Bottom => self.preview_scroll = Some(self.preview_position.max),
+            Action::Left => {
+                self.preview_hscroll = self.preview_hscroll.saturating_sub(HSCROLL_STEP)
+            }
+            Action::Right if !self.preview_wrap => self.preview_hscroll += HSCROLL_STEP,
+            Action::LineNumbers => {
+                self.preview_line_numbers = !self.preview_line_numbers;
+            }
+            Action::Wrap => {
+                self.preview_wrap = !self.preview_wrap;
+                self.preview_hscroll = 0;
+            }
+            Action::Format => {
+                self.preview_formatted = !self.preview_formatted;
+                self.preview_scroll = None;
+            }
+            Action::Fold => {
+                // Start by showing only the top level, then collapse that too
+                self.preview_fold =
+                    Some(self.preview_fold.map_or(1, |depth| depth.saturating_sub(1)));
+                self.preview_scroll = None;
+            }
+            Action::Unfold => {
+                self.preview_fold = self.preview_fold.map(|depth| depth + 1);
+            }
+            Action::CopyPretty => {
+                if let Err(e) = self.copy_formatted(false) {
+                    log::warn!("{:#}", e);
+                }
+            }
+            Action::CopyMinified => {
+                if let Err(e) = self.copy_formatted(true) {
+                    log::warn!("{:#}", e);
+                }
+            }
+            Action::Hex => {
+                self.preview_hex = !self.preview_hex;
+                self.preview_scroll = None;
+            }
+            Action::Invisibles => {
+                self.preview_invisibles = !self.preview_invisibles;
+            }
+            Action::ZoomIn => {
+                self.set_image_zoom(self.image_zoom.zoom_in());
+            }
+            Action::ZoomOut => {
+                self.set_image_zoom(self.image_zoom.zoom_out());
+            }
+            Action::ZoomFit => {
+                self.set_image_zoom(ImageZoom::Fit);
+            }
+            Action::ZoomActual => {
+                self.set_image_zoom(ImageZoom::Scale(1));
+            }
+            Action::Back => {
+                self.mode = AppMode::Normal;
+            }
+            Action::Help => {
+                self.mode = AppMode::Help;
+                self.help_scroll = 0;
+                self.help_max_scroll = 0;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Handle keys in visual mode (range selection for batch actions)
+    fn handle_visual_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(action) = self.keymap.action(KeyScope::Visual, key) else {
+            return Ok(());
+        };
+
+        match action {
+            Action::Down => {
+                self.move_down(1);
+            }
+            Action::Up => {
+                self.move_up(1);
+            }
+            Action::HalfPageDown => {
+                let count = self.half_page_size();
+                self.move_down(count);
+            }
+            Action::HalfPageUp => {
+                let count = self.half_page_size();
+                self.move_up(count);
+            }
+            Action::Top => {
+                self.jump_to_top();
+            }
+            Action::Bottom => {
+                self.jump_to_bottom();
+            }
+            Action::PageUp => {
+                let count = self.full_page_size();
+                self.move_up(count);
+            }
+            Action::PageDown => {
+                let count = self.full_page_size();
+                self.move_down(count);
             }
-            KeyCode::Char('m') => {
-                self.enter_register_mode();
+
+            // Batch actions on the range (plus any marked clips)
+            Action::ToggleMark => {
+                self.mark_visual_range();
             }
-            KeyCode::Char('p') => {
+            Action::TogglePin => {
                 self.toggle_pin()?;
             }
-            KeyCode::Char('P') => {
-                self.toggle_pinned_filter();
+            Action::Delete => {
+                // Errors (e.g. only permanent register clips) are ignored like in Normal mode
+                let _ = self.delete_entry();
             }
-            KeyCode::Char('c') => {
-                self.clear_flash_messages();
+            Action::EditTags => {
+                self.enter_tag_mode();
             }
-            KeyCode::Char('L') => {
-                // Test flash messages at different levels (capital L)
-                log::trace!("Test trace message");
-                log::debug!("Test debug message");
-                log::info!("Test info message");
-                log::warn!("Test warning message");
-                log::error!("Test error message");
+            Action::Join => {
+                self.join_entries()?;
             }
-            KeyCode::Char('/') => {
-                self.enter_search_mode();
+            Action::Export => {
+                self.export_entries()?;
             }
-            KeyCode::Char('?') => {
-                self.toggle_help();
+            Action::Back => {
+                self.exit_visual_mode();
             }
-            KeyCode::Char('\'') => {
-                self.toggle_temporary_filter();
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Handle keys in the trash view
+    fn handle_trash_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(action) = self.keymap.action(KeyScope::Trash, key) else {
+            return Ok(());
+        };
+
+        match action {
+            Action::Down => {
+                self.move_down(1);
             }
-            KeyCode::Char('"') => {
-                self.toggle_permanent_filter();
+            Action::Up => {
+                self.move_up(1);
             }
-            KeyCode::Char('v') => {
-                self.toggle_view_mode();
+            Action::Top => {
+                self.jump_to_top();
             }
-            KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::ALT) => {
-                // Alt-T - save current theme as default
-                let _ = self.save_theme_as_default();
+            Action::Bottom => {
+                self.jump_to_bottom();
             }
-            KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                // Ctrl-T - cycle to next theme
-                self.cycle_theme();
+            Action::PageUp => {
+                let count = self.full_page_size();
+                self.move_up(count);
             }
-            KeyCode::Char('T') => {
-                // Capital T - open theme picker
-                self.open_theme_picker();
+            Action::PageDown => {
+                let count = self.full_page_size();
+                self.move_down(count);
             }
-            KeyCode::Char('d') => {
-                // Delete entry - silently ignore errors (e.g., can't delete permanent register clips)
-                let _ = self.delete_entry();
+            Action::Restore => {
+                self.restore_from_trash()?;
             }
-            KeyCode::Char('D') => {
-                self.enter_confirm_clear_all();
+            Action::Delete => {
+                self.purge_from_trash()?;
             }
-            KeyCode::Char('q') => {
-                self.quit();
+            Action::Back => {
+                self.close_trash();
             }
-            KeyCode::Esc => {
-                // ESC clears filters in order: search filter, register filter, then quit
-                if !self.search_input.value().is_empty() {
-                    self.clear_search();
-                } else if self.register_filter != RegisterFilter::None {
-                    self.register_filter = RegisterFilter::None;
-                    self.selected_index = 0;
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Handle keys in register assignment mode
+    fn handle_register_key(&mut self, key: KeyEvent) -> Result<()> {
+        if self.keymap.action(KeyScope::RegisterAssign, key) == Some(Action::Back) {
+            self.mode = AppMode::Normal;
+            self.register_key = None;
+        } else if let KeyCode::Char(c) = key.code
+            && c.is_alphanumeric()
+        {
+            self.assign_register(c)?;
+        }
+        Ok(())
+    }
+
+    /// Handle keys in tag editing mode
+    fn handle_tag_key(&mut self, key: KeyEvent) -> Result<()> {
+        match self.keymap.action(KeyScope::TagEdit, key) {
+            Some(Action::Select) => {
+                self.apply_tag_input()?;
+            }
+            Some(Action::Back) => {
+                self.tag_input.reset();
+                // Return to the visual range if tagging started from it
+                self.mode = if self.visual_anchor.is_some() {
+                    AppMode::Visual
                 } else {
-                    self.quit();
-                }
+                    AppMode::Normal
+                };
             }
-
             _ => {
-                // Unknown keys do nothing in Normal mode
+                // Delegate editing keys to tui-input
+                self.tag_input.handle_event(&Event::Key(key));
             }
         }
         Ok(())
     }
 
-    /// Handle keys in search mode
-    fn handle_search_key(&mut self, key: KeyEvent) -> Result<()> {
-        match key.code {
-            KeyCode::Up => {
-                self.move_up(1);
-            }
-            KeyCode::Down => {
-                self.move_down(1);
-            }
-            KeyCode::Esc => {
-                self.exit_search_mode();
-            }
-            KeyCode::Enter => {
+    /// Handle keys in the image conversion prompt
+    fn handle_convert_key(&mut self, key: KeyEvent) -> Result<()> {
+        match self.keymap.action(KeyScope::Convert, key) {
+            Some(Action::Select) => {
                 self.mode = AppMode::Normal;
-                self.select_entry()?;
+                if let Err(e) = self.copy_converted_image() {
+                    log::warn!("Image conversion failed: {:#}", e);
+                }
             }
-            KeyCode::Char(' ') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                // Ctrl-Space - paste entry via Ctrl-V simulation
-                self.paste_entry()?;
+            Some(Action::Back) => {
+                self.mode = AppMode::Normal;
             }
             _ => {
-                // Delegate all other keys to tui-input (characters, backspace,
-                // delete, left/right arrows, Ctrl+A/E, Ctrl+W, etc.)
-                let event = Event::Key(key);
-                if self.search_input.handle_event(&event).is_some() {
-                    self.update_search_results();
-                }
+                // Delegate editing keys to tui-input
+                self.convert_input.handle_event(&Event::Key(key));
             }
         }
         Ok(())
     }
 
-    /// Handle keys in register assignment mode
-    fn handle_register_key(&mut self, key: KeyEvent) -> Result<()> {
-        match key.code {
-            KeyCode::Char(c) if c.is_alphanumeric() => {
-                self.assign_register(c)?;
+    /// Handle keys while prompting for template inputs
+    fn handle_template_key(&mut self, key: KeyEvent) -> Result<()> {
+        match self.keymap.action(KeyScope::TemplateInput, key) {
+            Some(Action::Select) => {
+                self.submit_template_input()?;
             }
-            KeyCode::Esc => {
-                self.mode = AppMode::Normal;
-                self.register_key = None;
+            Some(Action::Back) => {
+                self.cancel_template();
+            }
+            _ => {
+                // Delegate editing keys to tui-input
+                self.template_input.handle_event(&Event::Key(key));
             }
-            _ => {}
         }
         Ok(())
     }
 
     /// Handle keys in confirmation mode
     fn handle_confirm_key(&mut self, key: KeyEvent) -> Result<()> {
-        match key.code {
-            KeyCode::Char('y') | KeyCode::Char('Y') => {
+        match self.keymap.action(KeyScope::Confirm, key) {
+            Some(Action::Select) => {
                 self.clear_all_unpinned();
             }
-            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+            Some(Action::Back) => {
                 self.cancel_confirm();
             }
             _ => {}
@@ -1070,44 +2906,40 @@ impl App {
         const HELP_HALF_PAGE: usize = 5;
         const HELP_FULL_PAGE: usize = 10;
 
-        match key.code {
+        match self.keymap.action(KeyScope::Help, key) {
             // Line-by-line navigation with bounds checking
-            KeyCode::Char('j') | KeyCode::Down => {
+            Some(Action::Down) => {
                 if self.help_scroll < self.help_max_scroll {
                     self.help_scroll += 1;
                 }
             }
-            KeyCode::Char('k') | KeyCode::Up => {
+            Some(Action::Up) => {
                 if self.help_scroll > 0 {
                     self.help_scroll -= 1;
                 }
             }
             // Page navigation with clamping
-            KeyCode::PageDown => {
+            Some(Action::PageDown) => {
                 self.help_scroll = (self.help_scroll + HELP_FULL_PAGE).min(self.help_max_scroll);
             }
-            KeyCode::PageUp => {
+            Some(Action::PageUp) => {
                 self.help_scroll = self.help_scroll.saturating_sub(HELP_FULL_PAGE);
             }
             // Half-page navigation with clamping
-            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Some(Action::HalfPageDown) => {
                 self.help_scroll = (self.help_scroll + HELP_HALF_PAGE).min(self.help_max_scroll);
             }
-            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Some(Action::HalfPageUp) => {
                 self.help_scroll = self.help_scroll.saturating_sub(HELP_HALF_PAGE);
             }
             // Jump to top/bottom
-            KeyCode::Home => {
+            Some(Action::Top) => {
                 self.help_scroll = 0;
             }
-            KeyCode::End => {
+            Some(Action::Bottom) => {
                 self.help_scroll = self.help_max_scroll;
             }
-            // Close help with '?' or Esc
-            KeyCode::Char('?') | KeyCode::Esc => {
-                self.mode = AppMode::Normal;
-            }
-            // Any other key also closes help (for backward compatibility)
+            // Close help ('?' or Esc); any other key also closes it (for backward compatibility)
             _ => {
                 self.mode = AppMode::Normal;
             }
@@ -1117,84 +2949,67 @@ impl App {
 
     /// Handle keys in numeric mode (command palette with numeric prefix)
     fn handle_numeric_key(&mut self, key: KeyEvent) -> Result<()> {
-        match key.code {
-            // Additional digits extend the prefix
-            KeyCode::Char(c) if c.is_ascii_digit() => {
-                self.numeric_prefix.push(c);
-            }
+        // Additional digits extend the prefix
+        if let KeyCode::Char(c) = key.code
+            && c.is_ascii_digit()
+        {
+            self.numeric_prefix.push(c);
+            return Ok(());
+        }
 
-            // Commands that use the numeric prefix
-            KeyCode::Char('j') | KeyCode::Down => {
-                let count = self.numeric_prefix.parse::<usize>().unwrap_or(1);
-                self.move_down(count);
-                self.numeric_prefix.clear();
-                self.mode = AppMode::Normal;
+        // Commands that use the numeric prefix (bound as in Normal mode)
+        let count = self.numeric_prefix.parse::<usize>().ok();
+        match self.keymap.action(KeyScope::Normal, key) {
+            Some(Action::Down) => {
+                self.move_down(count.unwrap_or(1));
             }
-            KeyCode::Char('k') | KeyCode::Up => {
-                let count = self.numeric_prefix.parse::<usize>().unwrap_or(1);
-                self.move_up(count);
-                self.numeric_prefix.clear();
-                self.mode = AppMode::Normal;
+            Some(Action::Up) => {
+                self.move_up(count.unwrap_or(1));
             }
-            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let multiplier = self.numeric_prefix.parse::<usize>().unwrap_or(1);
-                let count = self.half_page_size() * multiplier;
+            Some(Action::HalfPageDown) => {
+                let count = self.half_page_size() * count.unwrap_or(1);
                 self.move_down(count);
-                self.numeric_prefix.clear();
-                self.mode = AppMode::Normal;
             }
-            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let multiplier = self.numeric_prefix.parse::<usize>().unwrap_or(1);
-                let count = self.half_page_size() * multiplier;
+            Some(Action::HalfPageUp) => {
+                let count = self.half_page_size() * count.unwrap_or(1);
                 self.move_up(count);
-                self.numeric_prefix.clear();
-                self.mode = AppMode::Normal;
             }
-            KeyCode::Enter => {
+            _ if key.code == KeyCode::Enter => {
                 // Enter jumps to the typed number
-                let count = self.numeric_prefix.parse::<usize>().unwrap_or(0);
-                self.jump_to_number(count);
-                self.numeric_prefix.clear();
-                self.mode = AppMode::Normal;
-            }
-            KeyCode::Esc => {
-                // Escape cancels numeric mode
-                self.numeric_prefix.clear();
-                self.mode = AppMode::Normal;
+                self.jump_to_number(count.unwrap_or(0));
             }
-
             _ => {
-                // Any other key cancels numeric mode
-                self.numeric_prefix.clear();
-                self.mode = AppMode::Normal;
+                // Escape or any other key cancels numeric mode
             }
         }
+        self.numeric_prefix.clear();
+        self.mode = AppMode::Normal;
         Ok(())
     }
 
     /// Handle keys in theme picker mode
     fn handle_theme_picker_key(&mut self, key: KeyEvent) -> Result<()> {
-        match key.code {
-            KeyCode::Esc => {
+        let Some(action) = self.keymap.action(KeyScope::ThemePicker, key) else {
+            return Ok(());
+        };
+
+        match action {
+            Action::Back => {
                 self.mode = AppMode::Normal;
             }
-            KeyCode::Enter => {
+            Action::Select => {
                 self.select_theme_from_picker();
             }
-            KeyCode::Up | KeyCode::Char('k') => {
-                if self.theme_picker_selected > 0 {
-                    self.theme_picker_selected -= 1;
-                }
+            Action::Up if self.theme_picker_selected > 0 => {
+                self.theme_picker_selected -= 1;
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if self.theme_picker_selected + 1 < self.theme_picker_themes.len() {
-                    self.theme_picker_selected += 1;
-                }
+            Action::Down if self.theme_picker_selected + 1 < self.theme_picker_themes.len() => {
+                self.theme_picker_selected += 1;
             }
-            KeyCode::Home | KeyCode::Char('g') => {
+            Action::Top => {
                 self.theme_picker_selected = 0;
             }
-            KeyCode::End | KeyCode::Char('G') => {
+            Action::Bottom => {
                 self.theme_picker_selected = self.theme_picker_themes.len().saturating_sub(1);
             }
             _ => {}
@@ -1282,7 +3097,7 @@ impl App {
         );
 
         // Create layout: [clip_list, divider, preview, keyboard_hints]
-        let chunks = ui::create_main_layout(size, self.view_mode);
+        let chunks = self.layout(size);
         let clip_list_area = chunks[0];
         let divider_area = chunks[1];
         let preview_area = chunks[2];
@@ -1300,22 +3115,71 @@ impl App {
         let visible_clip_ids = self.visible_clips();
         let visible_count = visible_clip_ids.len();
 
+        // Section headers in the grouped view take a row each
+        let groups = self.clip_groups(&visible_clip_ids);
+
         // Calculate total rows needed for all entries
         let rows_per_entry = match self.view_mode {
             ViewMode::Compact => 1,
             ViewMode::Comfortable => 3,
         };
-        let total_visible_rows = visible_count * rows_per_entry;
+        let total_visible_rows = visible_count * rows_per_entry + groups.len();
+        let selected_row = ui::clip_list::list_row(self.selected_index, rows_per_entry, &groups);
 
         // Update scroll offset to maintain padding (must happen before borrowing entries)
-        self.update_scroll_offset(total_visible_rows);
+        self.update_scroll_offset(total_visible_rows, selected_row);
+
+        // Load thumbnails for the image clips on screen
+        let show_thumbnails =
+            self.view_mode == ViewMode::Comfortable && self.config.general.list_thumbnails;
+        if show_thumbnails {
+            let first_visible = (self.list_scroll_offset / 3).saturating_sub(groups.len());
+            let on_screen = visible_clip_ids
+                .iter()
+                .skip(first_visible)
+                .take(self.list_height as usize / 3 + 2)
+                .copied()
+                .collect::<Vec<_>>();
+            self.request_thumbnails(&on_screen);
+        }
 
         // Get visible entries for rendering (after scroll offset update)
+        // Field-level borrows so the thumbnail cache can be borrowed mutably below
+        let history = &self.history;
+        let trash = self.mode == AppMode::Trash;
         let visible_entries: Vec<&crate::models::ClipEntry> = visible_clip_ids
             .iter()
-            .filter_map(|&id| self.history.get_entry(id))
+            .filter_map(|&id| {
+                if trash {
+                    history.get_trashed(id)
+                } else {
+                    history.get_entry(id)
+                }
+            })
             .collect();
 
+        // Inline prompt for tag editing or template inputs
+        let prompt = match self.mode {
+            AppMode::TagEdit => Some(("tag: ".to_string(), &self.tag_input)),
+            AppMode::Convert => Some(("convert to: ".to_string(), &self.convert_input)),
+            AppMode::TemplateInput => self
+                .pending_template
+                .as_ref()
+                .and_then(|p| p.current_label())
+                .map(|label| (format!("{}: ", label), &self.template_input)),
+            _ => None,
+        };
+
+        // Clips marked for batch actions (including the visual range)
+        let marked = self.marked_set();
+
+        // Highlight why clips matched the current search (trash isn't searchable)
+        let mut highlighter = if self.mode == AppMode::Trash {
+            None
+        } else {
+            self.search_index.highlighter(&self.search_query.fuzzy)
+        };
+
         // Render clip list (with inline search or numeric prefix display)
         ui::render_clip_list(
             frame,
@@ -1327,57 +3191,158 @@ impl App {
                 search_input: &self.search_input,
                 numeric_prefix: &self.numeric_prefix,
                 register_filter: self.register_filter,
+                prompt,
+                marked: &marked,
+                groups: &groups,
+                sort_mode: self.sort_mode,
+                search_indicator: ui::search_mode_indicator(
+                    self.search_index.match_mode(),
+                    self.search_index.mode(),
+                    self.search_index.regex_error().is_some(),
+                    &self.theme,
+                ),
+                highlighter: highlighter.as_mut(),
                 view_mode: self.view_mode,
                 scroll_offset: self.list_scroll_offset,
+                thumbnails: show_thumbnails.then_some(&mut self.thumbnail_cache),
                 theme: &self.theme,
             },
         );
 
-        // Render divider between history and preview
-        ui::render_divider(frame, divider_area, self.view_mode, &self.theme);
-
-        // Render preview for selected clip
-        let selected_entry = self
-            .selected_clip_id()
-            .and_then(|id| self.history.get_entry(id));
+        // Pick mode shows the list only
+        if !self.pick_mode {
+            // Render divider between history and preview
+            ui::render_divider(frame, divider_area, self.view_mode, &self.theme);
+
+            // Reset preview scrolling when a different clip is selected
+            let preview_clip_id = self.selected_clip_id();
+            if preview_clip_id != self.preview_clip_id {
+                self.preview_clip_id = preview_clip_id;
+                self.preview_scroll = None;
+                self.preview_hscroll = 0;
+                self.preview_fold = None;
+                self.image_zoom = ImageZoom::Fit;
+                self.zoom_view = None;
+            }
+
+            // Render preview for selected clip
+            // Field-level borrows so the image cache can be borrowed mutably below
+            let selected_entry = self.selected_clip_id().and_then(|id| {
+                if self.mode == AppMode::Trash {
+                    self.history.get_trashed(id)
+                } else {
+                    self.history.get_entry(id)
+                }
+            });
+
+            // Pretty-print JSON/XML clips, reusing the last result while nothing changed
+            if !self.preview_formatted {
+                self.formatted_preview = None;
+            } else if self.formatted_preview.as_ref().is_none_or(|cached| {
+                cached.clip_id != preview_clip_id || cached.fold_depth != self.preview_fold
+            }) {
+                let formatted = selected_entry.and_then(|entry| {
+                    let format = StructuredFormat::for_entry(entry)?;
+                    let ClipContent::Text(text) = &entry.content else {
+                        return None;
+                    };
+                    Some((format, format.pretty(text, self.preview_fold)))
+                });
+                self.formatted_preview = Some(FormattedPreview {
+                    clip_id: preview_clip_id,
+                    fold_depth: self.preview_fold,
+                    formatted,
+                });
+            }
+            let formatted = self
+                .formatted_preview
+                .as_ref()
+                .and_then(|cached| cached.formatted.as_ref())
+                .map(|(format, result)| (*format, result));
+
+            // Read the start of file clips for the hex dump, once per selected clip
+            match (preview_clip_id, selected_entry.map(|entry| &entry.content)) {
+                (Some(id), Some(ClipContent::File { path, .. })) => {
+                    if self
+                        .file_head
+                        .as_ref()
+                        .is_none_or(|(cached, _)| *cached != id)
+                    {
+                        self.file_head = Some((id, FileHead::read(path)));
+                    }
+                }
+                _ => self.file_head = None,
+            }
 
-        // Get cached image if available for current selection
-        // peek() doesn't update LRU order, get_mut() does
-        let cached_image = if let Some(clip_id) = self.selected_clip_id() {
-            self.image_cache.get_mut(&clip_id)
-        } else {
-            None
-        };
+            // Get cached image if available for current selection
+            // peek() doesn't update LRU order, get_mut() does
+            let cached_image = if let Some(clip_id) = self.selected_clip_id() {
+                self.image_cache.get_mut(&clip_id)
+            } else {
+                None
+            };
 
-        ui::render_preview(
-            frame,
-            preview_area,
-            selected_entry,
-            cached_image,
-            self.config.general.show_preview_metadata,
-            &self.flash_messages,
-            &self.theme,
-        );
+            self.preview_position = ui::render_preview(
+                frame,
+                preview_area,
+                selected_entry,
+                ui::preview::PreviewRenderContext {
+                    cached_image,
+                    highlighter: highlighter.as_mut(),
+                    show_metadata: self.config.general.show_preview_metadata,
+                    scroll: self.preview_scroll,
+                    hscroll: self.preview_hscroll,
+                    line_numbers: self.preview_line_numbers,
+                    wrap: self.preview_wrap,
+                    focused: self.mode == AppMode::Preview,
+                    formatted,
+                    hex: self.preview_hex,
+                    image_error: preview_clip_id
+                        .and_then(|id| self.failed_images.get(&id))
+                        .map(String::as_str),
+                    file_head: self.file_head.as_ref().map(|(_, head)| head),
+                    invisibles: self.preview_invisibles,
+                    image_info: preview_clip_id.and_then(|id| self.image_info.get(&id).copied()),
+                    zoom: match self.image_zoom {
+                        ImageZoom::Scale(scale) => {
+                            self.zoom_view.as_mut().map(|view| (view, scale))
+                        }
+                        ImageZoom::Fit => None,
+                    },
+                    picker: &mut self.picker,
+                    flash_messages: &self.flash_messages,
+                    theme: &self.theme,
+                },
+            );
+        }
 
         // Render mode-specific keyboard hints
-        ui::render_keyboard_hints(frame, keyboard_hints_area, self, &self.theme);
+        self.mouse_targets.hints =
+            ui::render_keyboard_hints(frame, keyboard_hints_area, self, &self.theme);
+        self.mouse_targets.list = Rect {
+            y: clip_list_area.y + header_height.min(clip_list_area.height),
+            height: clip_list_area.height.saturating_sub(header_height),
+            ..clip_list_area
+        };
+        self.mouse_targets.preview = preview_area;
 
         // Render help overlay if in help mode
         if matches!(self.mode, AppMode::Help) {
             (self.help_scroll, self.help_max_scroll) =
-                ui::render_help_overlay(frame, size, &self.theme, self.help_scroll);
+                ui::render_help_overlay(frame, size, &self.keymap, &self.theme, self.help_scroll);
         }
 
         // Render theme picker if in theme picker mode
+        self.mouse_targets.theme_picker = None;
         if matches!(self.mode, AppMode::ThemePicker) {
-            ui::render_theme_picker(
+            self.mouse_targets.theme_picker = Some(ui::render_theme_picker(
                 frame,
                 size,
                 &self.theme_picker_themes,
                 self.theme_picker_selected,
                 &self.current_theme_name,
                 &self.theme,
-            );
+            ));
         }
 
         // Render confirmation dialog if in confirm mode
@@ -1391,3 +3356,617 @@ impl App {
         }
     }
 }
+
+/// Get the snippet template for a clip, if it is one
+/// Only permanent register text containing placeholders is treated as a template,
+/// so ordinary clips with `{{...}}` (e.g. Handlebars) are copied verbatim
+fn snippet_template(entry: &ClipEntry) -> Option<Template> {
+    match &entry.content {
+        ClipContent::Text(text)
+            if !entry.permanent_registers.is_empty() && Template::has_placeholders(text) =>
+        {
+            Some(Template::parse(text))
+        }
+        _ => None,
+    }
+}
+
+/// Describe a number of clips for undo messages (e.g. "1 clip", "3 clips")
+fn clip_count(n: usize) -> String {
+    if n == 1 {
+        "1 clip".to_string()
+    } else {
+        format!("{} clips", n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::KeyModifiers;
+    use std::sync::{Arc, Mutex};
+
+    /// Clipboard backend that records written text
+    #[derive(Default)]
+    struct TestBackend {
+        written: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl ClipboardBackend for TestBackend {
+        fn write_text(&self, text: &str) -> Result<()> {
+            self.written.lock().unwrap().push(text.to_string());
+            Ok(())
+        }
+
+        fn read_text(&self) -> Result<String> {
+            Ok(String::new())
+        }
+
+        fn write_image(&self, _data: &[u8], mime_type: &str) -> Result<()> {
+            self.written.lock().unwrap().push(mime_type.to_string());
+            Ok(())
+        }
+
+        fn paste_from_clipboard(&self, _delay_ms: u64) -> Result<()> {
+            Ok(())
+        }
+
+        fn supports_images(&self) -> bool {
+            true
+        }
+
+        fn name(&self) -> &'static str {
+            "test"
+        }
+    }
+
+    /// App over text clips (first argument ends up at the top of the list)
+    /// Returns the app and the clip IDs in list order
+    fn test_app(texts: &[&str]) -> (App, Vec<u64>) {
+        let mut history = ClipboardHistory::new(100);
+        let mut ids: Vec<u64> = texts
+            .iter()
+            .rev()
+            .map(|t| history.add_entry(ClipContent::Text(t.to_string())))
+            .collect();
+        ids.reverse();
+
+        let mut config = Config::default();
+        config.general.theme = "#catppuccin-mocha".to_string();
+        config.general.exit_on_select = false;
+
+        let mut app = App::new(
+            history,
+            Registry::new(),
+            config,
+            Box::new(TestBackend::default()),
+            ImageProtocol::without_query(),
+            None,
+        )
+        .unwrap();
+        app.handle_resize(120, 40);
+        (app, ids)
+    }
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+            .unwrap();
+    }
+
+    /// Draw a frame like the event loop does, returning the screen text
+    fn render(app: &mut App) -> String {
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect()
+    }
+
+    fn list_ids(app: &App) -> Vec<u64> {
+        app.history.entries().iter().map(|e| e.id).collect()
+    }
+
+    #[test]
+    fn test_join_can_be_undone() {
+        let (mut app, ids) = test_app(&["a", "b", "c"]);
+
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char('J'));
+        assert_eq!(app.history.len(), 4);
+        assert!(matches!(&app.history.entries()[0].content, ClipContent::Text(t) if t == "a\nb"));
+
+        press(&mut app, KeyCode::Char('u'));
+        assert_eq!(list_ids(&app), ids);
+
+        app.redo();
+        assert_eq!(app.history.len(), 4);
+    }
+
+    #[test]
+    fn test_join_at_capacity_can_be_undone() {
+        let (mut app, ids) = test_app(&["a", "b", "c"]);
+        app.history.max_entries = 3;
+
+        // The joined clip pushes the oldest clip into the trash
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char('J'));
+        let joined = app.history.entries()[0].id;
+        assert_eq!(list_ids(&app), vec![joined, ids[0], ids[1]]);
+        assert!(app.history.get_trashed(ids[2]).is_some());
+
+        press(&mut app, KeyCode::Char('u'));
+        assert_eq!(list_ids(&app), ids);
+        assert!(app.history.get_trashed(ids[2]).is_none());
+
+        app.redo();
+        assert_eq!(list_ids(&app), vec![joined, ids[0], ids[1]]);
+        assert!(app.history.get_trashed(ids[2]).is_some());
+    }
+
+    #[test]
+    fn test_register_can_be_undone() {
+        let (mut app, ids) = test_app(&["a", "b"]);
+        app.assign_register('q').unwrap();
+        assert_eq!(app.registers.get_temporary('q'), Some(ids[0]));
+
+        app.undo();
+        assert_eq!(app.registers.get_temporary('q'), None);
+        assert!(
+            app.history
+                .get_entry(ids[0])
+                .unwrap()
+                .temporary_registers
+                .is_empty()
+        );
+        assert_eq!(
+            app.flash_messages.last().unwrap().message,
+            "Undid register 'q'"
+        );
+    }
+
+    #[test]
+    fn test_sort_orders() {
+        let (mut app, ids) = test_app(&["ccc", "a", "bb"]);
+        let image = app.history.add_entry(ClipContent::Image {
+            data: vec![0; 2],
+            mime_type: "image/png".to_string(),
+        });
+        app.history.get_entry_mut(ids[2]).unwrap().name = Some("alpha".to_string());
+        app.history.get_entry_mut(ids[0]).unwrap().name = Some("Beta".to_string());
+        app.history.record_use(ids[1]);
+        app.history.record_use(ids[1]);
+        app.history.record_use(ids[2]);
+
+        let order = |app: &mut App, mode: SortMode| {
+            app.sort_mode = mode;
+            app.visible_clips()
+        };
+        assert_eq!(
+            order(&mut app, SortMode::Recent),
+            vec![image, ids[0], ids[1], ids[2]]
+        );
+        assert_eq!(
+            order(&mut app, SortMode::Oldest),
+            vec![ids[2], ids[1], ids[0], image]
+        );
+        assert_eq!(
+            order(&mut app, SortMode::MostUsed),
+            vec![ids[1], ids[2], image, ids[0]]
+        );
+        assert_eq!(
+            order(&mut app, SortMode::Size),
+            vec![ids[0], image, ids[2], ids[1]]
+        );
+        assert_eq!(
+            order(&mut app, SortMode::Name),
+            vec![ids[2], ids[0], image, ids[1]]
+        );
+        assert_eq!(
+            order(&mut app, SortMode::Type),
+            vec![ids[0], ids[1], ids[2], image]
+        );
+    }
+
+    #[test]
+    fn test_visible_clips_follow_changes() {
+        let (mut app, ids) = test_app(&["one", "two"]);
+        assert_eq!(app.visible_clips(), ids);
+
+        let new = app
+            .history
+            .add_entry(ClipContent::Text("three".to_string()));
+        assert_eq!(app.visible_clips(), vec![new, ids[0], ids[1]]);
+
+        app.history.trash_entry(ids[0]);
+        assert_eq!(app.visible_clips(), vec![new, ids[1]]);
+
+        app.history.get_entry_mut(ids[1]).unwrap().name = Some("twin".to_string());
+        app.enter_search_mode();
+        app.handle_paste("name:tw");
+        assert_eq!(app.visible_clips(), vec![ids[1]]);
+        app.clear_search();
+        assert_eq!(app.visible_clips(), vec![new, ids[1]]);
+    }
+
+    #[test]
+    fn test_preview_focus_scrolling() {
+        let long: String = (1..=100).map(|n| format!("line {}\n", n)).collect();
+        let (mut app, _) = test_app(&[&long, "short"]);
+        render(&mut app);
+
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.mode, AppMode::Preview);
+        press(&mut app, KeyCode::Char('j'));
+        let screen = render(&mut app);
+        assert_eq!(app.preview_position.offset, 1);
+        assert!(screen.contains(" 2/100 "));
+
+        // Scrolling stops at the end of the clip
+        press(&mut app, KeyCode::Char('G'));
+        render(&mut app);
+        let max = app.preview_position.max;
+        assert!(max > 0);
+        assert_eq!(app.preview_position.offset, max);
+        press(&mut app, KeyCode::Char('j'));
+        render(&mut app);
+        assert_eq!(app.preview_position.offset, max);
+
+        press(&mut app, KeyCode::Char('g'));
+        render(&mut app);
+        assert_eq!(app.preview_position.offset, 0);
+
+        // Sideways scrolling only applies without wrapping, and toggling wrap resets it
+        press(&mut app, KeyCode::Char('l'));
+        assert_eq!(app.preview_hscroll, 0);
+        press(&mut app, KeyCode::Char('w'));
+        press(&mut app, KeyCode::Char('l'));
+        assert!(app.preview_hscroll > 0);
+        press(&mut app, KeyCode::Char('w'));
+        assert_eq!(app.preview_hscroll, 0);
+
+        let line_numbers = app.preview_line_numbers;
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.preview_line_numbers, !line_numbers);
+
+        // Back in the list, selecting another clip starts its preview at the top
+        press(&mut app, KeyCode::Char('G'));
+        render(&mut app);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.mode, AppMode::Normal);
+        press(&mut app, KeyCode::Char('j'));
+        render(&mut app);
+        assert_eq!(app.preview_scroll, None);
+    }
+
+    #[test]
+    fn test_use_recorded_only_when_template_completes() {
+        let (mut app, ids) = test_app(&["Hello {{input:name}}", "other"]);
+        app.history
+            .get_entry_mut(ids[0])
+            .unwrap()
+            .add_permanent_register('a');
+        let use_count = |app: &App| app.history.get_entry(ids[0]).unwrap().use_count;
+
+        app.select_entry().unwrap();
+        assert_eq!(app.mode, AppMode::TemplateInput);
+        app.cancel_template();
+        assert_eq!(use_count(&app), 0);
+
+        app.select_entry().unwrap();
+        app.handle_paste("Ann");
+        app.submit_template_input().unwrap();
+        assert_eq!(use_count(&app), 1);
+    }
+
+    /// Encode a small PNG in memory
+    fn png_bytes() -> Vec<u8> {
+        let mut data = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::new_rgb8(3, 2)
+            .write_to(&mut data, image::ImageFormat::Png)
+            .unwrap();
+        data.into_inner()
+    }
+
+    /// Poll the background loader until the selected clip's image is done
+    fn wait_for_image(app: &mut App, clip_id: u64) {
+        let start = Instant::now();
+        while app.pending_images.contains(&clip_id) {
+            assert!(start.elapsed().as_secs() < 10, "image load timed out");
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            app.update_image_cache();
+        }
+    }
+
+    #[test]
+    fn test_decode_image_limits() {
+        let png = png_bytes();
+        let size = png.len() as u64;
+
+        let img = decode_image(ImageSource::Memory(png.clone()), size).unwrap();
+        assert_eq!((img.width(), img.height()), (3, 2));
+        let error = decode_image(ImageSource::Memory(png), size - 1).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("over max_image_preview_size_bytes")
+        );
+
+        // File sources are checked against the limit before they are read
+        let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        let error = decode_image(ImageSource::File(manifest.clone()), 10).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("over max_image_preview_size_bytes")
+        );
+        assert!(decode_image(ImageSource::File(manifest), u64::MAX).is_err());
+
+        let missing = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("missing.png");
+        let error = decode_image(ImageSource::File(missing), u64::MAX).unwrap_err();
+        assert!(error.to_string().starts_with("Failed to read"));
+    }
+
+    #[test]
+    fn test_file_image_preview() {
+        let (mut app, _) = test_app(&["text"]);
+        let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        let not_an_image = app.history.add_entry(ClipContent::File {
+            path: manifest,
+            mime_type: "image/png".to_string(),
+        });
+
+        app.request_image_load();
+        assert!(app.pending_images.contains(&not_an_image));
+        wait_for_image(&mut app, not_an_image);
+        assert!(app.failed_images.contains_key(&not_an_image));
+        assert!(render(&mut app).contains("[No image preview:"));
+
+        // Failed images are not requested again
+        app.request_image_load();
+        assert!(!app.pending_images.contains(&not_an_image));
+
+        let image = app.history.add_entry(ClipContent::Image {
+            data: png_bytes(),
+            mime_type: "image/png".to_string(),
+        });
+        app.request_image_load();
+        wait_for_image(&mut app, image);
+        assert!(app.image_cache.contains(&image));
+        assert!(!app.failed_images.contains_key(&image));
+    }
+
+    fn mouse(app: &mut App, kind: MouseEventKind, column: u16, row: u16) {
+        app.handle_mouse(MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_mouse_click_and_wheel() {
+        let long: String = (1..=100).map(|n| format!("line {}\n", n)).collect();
+        let (mut app, ids) = test_app(&[&long, "b", "c", "d"]);
+        app.view_mode = ViewMode::Compact;
+        app.group_clips = false;
+        render(&mut app);
+        let list = app.mouse_targets.list;
+        let click = MouseEventKind::Down(MouseButton::Left);
+
+        // A click selects the clip on that row, a second click on it copies it
+        mouse(&mut app, click, list.x + 2, list.y + 2);
+        assert_eq!(app.selected_clip_id(), Some(ids[2]));
+        mouse(&mut app, click, list.x + 2, list.y + 1);
+        assert_eq!(app.selected_clip_id(), Some(ids[1]));
+        assert_eq!(app.history.get_entry(ids[2]).unwrap().use_count, 0);
+        mouse(&mut app, click, list.x + 2, list.y + 1);
+        assert_eq!(app.history.get_entry(ids[1]).unwrap().use_count, 1);
+
+        // Rows past the last clip do nothing
+        mouse(&mut app, click, list.x + 2, list.y + 10);
+        assert_eq!(app.selected_clip_id(), Some(ids[1]));
+
+        // The wheel moves the selection over the list
+        mouse(&mut app, MouseEventKind::ScrollDown, list.x + 2, list.y);
+        assert_eq!(app.selected_clip_id(), Some(ids[2]));
+        mouse(&mut app, MouseEventKind::ScrollUp, list.x + 2, list.y);
+        mouse(&mut app, MouseEventKind::ScrollUp, list.x + 2, list.y);
+        assert_eq!(app.selected_clip_id(), Some(ids[0]));
+
+        // ... and scrolls the preview over it
+        render(&mut app);
+        let preview = app.mouse_targets.preview;
+        let (x, y) = (preview.x + 2, preview.y + 2);
+        mouse(&mut app, MouseEventKind::ScrollDown, x, y);
+        assert_eq!(app.preview_scroll, Some(3));
+        mouse(&mut app, MouseEventKind::ScrollUp, x, y);
+        assert_eq!(app.preview_scroll, Some(0));
+        assert_eq!(app.selected_clip_id(), Some(ids[0]));
+
+        // Clicking the preview focuses it, clicking the list goes back
+        mouse(&mut app, click, x, y);
+        assert_eq!(app.mode, AppMode::Preview);
+        mouse(&mut app, click, list.x + 2, list.y + 3);
+        assert_eq!(app.mode, AppMode::Normal);
+        assert_eq!(app.selected_clip_id(), Some(ids[3]));
+
+        // Keyboard hints act like their key
+        render(&mut app);
+        let help = app
+            .mouse_targets
+            .hints
+            .iter()
+            .find(|target| target.key.code == KeyCode::Char('?'))
+            .map(|target| target.area)
+            .unwrap();
+        mouse(&mut app, click, help.x, help.y);
+        assert_eq!(app.mode, AppMode::Help);
+    }
+
+    #[test]
+    fn test_resize_keeps_selection_visible() {
+        let texts: Vec<String> = (0..30).map(|n| format!("clip {}", n)).collect();
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let (mut app, _) = test_app(&texts);
+        app.view_mode = ViewMode::Compact;
+        app.group_clips = false;
+        app.handle_resize(120, 40);
+        app.move_down(25);
+        assert_eq!(app.selected_index, 25);
+        let tall = app.list_height;
+
+        app.handle_resize(120, 12);
+        assert!(app.list_height < tall);
+        let rows = app.list_scroll_offset..app.list_scroll_offset + app.list_height as usize;
+        assert!(rows.contains(&25));
+        assert!(render(&mut app).contains("clip 25"));
+    }
+
+    #[test]
+    fn test_resize_keeps_decoded_images() {
+        let (mut app, _) = test_app(&["text"]);
+        let image = app.history.add_entry(ClipContent::Image {
+            data: png_bytes(),
+            mime_type: "image/png".to_string(),
+        });
+        app.request_image_load();
+        wait_for_image(&mut app, image);
+        render(&mut app);
+
+        app.handle_resize(80, 24);
+        assert!(app.image_cache.contains(&image));
+        assert!(!app.pending_images.contains(&image));
+        render(&mut app);
+        assert!(app.image_cache.contains(&image));
+    }
+
+    #[test]
+    fn test_paste_into_prompts() {
+        let (mut app, ids) = test_app(&["one", "two"]);
+        app.history.get_entry_mut(ids[1]).unwrap().name = Some("twin".to_string());
+
+        // Outside prompts pastes are ignored
+        app.handle_paste("name:tw");
+        assert_eq!(app.mode, AppMode::Normal);
+        assert_eq!(app.search_input.value(), "");
+
+        // Whitespace becomes spaces, other control characters are dropped
+        app.enter_search_mode();
+        app.handle_paste("name:tw\x1b");
+        assert_eq!(app.search_input.value(), "name:tw");
+        assert_eq!(app.visible_clips(), vec![ids[1]]);
+        app.handle_paste("\tx\r\ny");
+        assert_eq!(app.search_input.value(), "name:tw x  y");
+    }
+
+    #[test]
+    fn test_rebound_search_keys() {
+        let (mut app, _) = test_app(&["one"]);
+        let config = toml::from_str("[search]\nmatch-mode = \"Alt-m\"\nback = \"Ctrl-g\"").unwrap();
+        app.keymap = Keymap::new(&config).0;
+
+        press(&mut app, KeyCode::Char('/'));
+        app.handle_key(KeyEvent::new(KeyCode::Char('m'), KeyModifiers::ALT))
+            .unwrap();
+        assert_eq!(app.search_index.match_mode(), MatchMode::Exact);
+        assert!(render(&mut app).contains("Alt-m mode"));
+
+        // Esc no longer leaves search; the configured key does
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.mode, AppMode::Search);
+        app.handle_key(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::CONTROL))
+            .unwrap();
+        assert_eq!(app.mode, AppMode::Normal);
+    }
+
+    #[test]
+    fn test_quit_on_focus_lost() {
+        let (mut app, _) = test_app(&["one"]);
+        app.handle_focus(false);
+        assert!(!app.should_quit);
+
+        app.config.general.quit_on_focus_lost = true;
+        app.handle_focus(true);
+        assert!(!app.should_quit);
+        app.handle_focus(false);
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_pick_mode() {
+        let (mut app, ids) = test_app(&["one", "two"]);
+        app.enter_pick_mode();
+        assert_eq!(app.mode, AppMode::Search);
+
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Enter);
+        assert!(app.should_quit);
+        assert_eq!(
+            app.picked,
+            Some((ids[1], ClipContent::Text("two".to_string())))
+        );
+        assert_eq!(app.history.get_entry(ids[1]).unwrap().use_count, 1);
+    }
+
+    #[test]
+    fn test_pick_mode_cancel() {
+        let (mut app, ids) = test_app(&["Hello {{input:name}}", "other"]);
+        app.history
+            .get_entry_mut(ids[0])
+            .unwrap()
+            .add_permanent_register('a');
+        app.enter_pick_mode();
+        app.handle_paste("Hello");
+
+        // Esc in the template prompt goes back to the search
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.mode, AppMode::TemplateInput);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.mode, AppMode::Search);
+        assert!(!app.should_quit);
+
+        // Esc in the search quits with nothing picked
+        press(&mut app, KeyCode::Esc);
+        assert!(app.should_quit);
+        assert!(app.picked.is_none());
+        assert_eq!(app.history.get_entry(ids[0]).unwrap().use_count, 0);
+    }
+
+    #[test]
+    fn test_pick_mode_template() {
+        let (mut app, ids) = test_app(&["Hello {{input:name}}", "other"]);
+        app.history
+            .get_entry_mut(ids[0])
+            .unwrap()
+            .add_permanent_register('a');
+        app.sort_mode = SortMode::Frecency;
+        app.enter_pick_mode();
+        assert_eq!(app.selected_clip_id(), Some(ids[0]));
+
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.mode, AppMode::TemplateInput);
+        assert!(app.picked.is_none());
+
+        // The list reorders while the template is filled in
+        app.history.record_use(ids[1]);
+        assert_eq!(app.selected_clip_id(), Some(ids[1]));
+
+        app.handle_paste("Ann");
+        app.submit_template_input().unwrap();
+        assert!(app.should_quit);
+        assert_eq!(
+            app.picked,
+            Some((ids[0], ClipContent::Text("Hello Ann".to_string())))
+        );
+        assert_eq!(app.history.get_entry(ids[0]).unwrap().use_count, 1);
+    }
+}
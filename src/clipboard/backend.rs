@@ -2,14 +2,18 @@ use anyhow::Result;
 
 /// Trait for clipboard backend abstraction
 /// Supports different clipboard systems (Wayland, X11)
-/// Backend is write-only: used to copy selected clips back to the clipboard
+/// Backend is used to copy selected clips back to the clipboard, and to read
+/// the current clipboard text for snippet templates
 /// Clipboard monitoring is handled separately via daemon processes
 pub trait ClipboardBackend: Send + Sync {
     /// Write text to clipboard
     fn write_text(&self, text: &str) -> Result<()>;
 
-    /// Write image to clipboard (PNG format)
-    fn write_image(&self, data: &[u8]) -> Result<()>;
+    /// Read the current clipboard text (used by `{{clipboard}}` template placeholders)
+    fn read_text(&self) -> Result<String>;
+
+    /// Write image to clipboard, offered as the given MIME type (e.g. `image/png`)
+    fn write_image(&self, data: &[u8], mime_type: &str) -> Result<()>;
 
     /// Simulate Ctrl-V to paste from clipboard after a delay
     ///
@@ -38,7 +38,9 @@ pub fn start_text_watcher() -> Result<()> {
 }
 
 /// Start watching clipboard for image changes
-/// Spawns detached background process: `wl-paste --type image/png --watch clipr store --type image`
+/// Spawns detached background process: `wl-paste --type image --watch clipr store-image`
+/// `--type image` takes the first image type offered (PNG, JPEG, WebP, GIF, SVG, ...);
+/// store-image detects the actual format from the bytes
 /// Uses process_group(0) to create a new process group, making it independent of the parent
 pub fn start_image_watcher() -> Result<()> {
     log::info!("Starting image clipboard watcher");
@@ -52,11 +54,11 @@ pub fn start_image_watcher() -> Result<()> {
         .open("/dev/null")
         .context("Failed to open /dev/null")?;
 
-    // Spawn wl-paste --type image/png --watch <clipr> store-image
+    // Spawn wl-paste --type image --watch <clipr> store-image
     // process_group(0) creates a new process group, detaching it from the parent's session
     Command::new("wl-paste")
         .arg("--type")
-        .arg("image/png")
+        .arg("image")
         .arg("--watch")
         .arg(&clipr_path)
         .arg("store-image")
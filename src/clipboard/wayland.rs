@@ -4,8 +4,8 @@ use std::process::{Command, Stdio};
 use super::backend::ClipboardBackend;
 
 /// Wayland clipboard backend using wl-clipboard tools
-/// Requires wl-copy to be installed
-/// Write-only: used to copy selected clips back to clipboard
+/// Requires wl-copy to be installed (and wl-paste for reading)
+/// Used to copy selected clips back to clipboard
 pub struct WaylandBackend;
 
 impl WaylandBackend {
@@ -44,10 +44,34 @@ impl ClipboardBackend for WaylandBackend {
         Ok(())
     }
 
-    fn write_image(&self, data: &[u8]) -> Result<()> {
+    fn read_text(&self) -> Result<String> {
+        let output = Command::new("wl-paste")
+            .arg("--no-newline")
+            .arg("--type")
+            .arg("text")
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .context("Failed to run wl-paste")?;
+
+        // wl-paste exits non-zero when the clipboard is empty
+        if !output.status.success() {
+            log::debug!(
+                "wl-paste returned {}, treating clipboard as empty",
+                output.status
+            );
+            return Ok(String::new());
+        }
+
+        let text = String::from_utf8(output.stdout).context("Clipboard text is not valid UTF-8")?;
+        log::debug!("Read {} bytes text from clipboard", text.len());
+        Ok(text)
+    }
+
+    fn write_image(&self, data: &[u8], mime_type: &str) -> Result<()> {
         let mut child = Command::new("wl-copy")
             .arg("--type")
-            .arg("image/png")
+            .arg(mime_type)
             .stdin(Stdio::piped())
             .stdout(Stdio::null())
             .stderr(Stdio::null())
@@ -67,7 +91,7 @@ impl ClipboardBackend for WaylandBackend {
             return Err(anyhow!("wl-copy failed with status: {}", status));
         }
 
-        log::debug!("Wrote {} bytes image to clipboard", data.len());
+        log::debug!("Wrote {} bytes {} to clipboard", data.len(), mime_type);
         Ok(())
     }
 
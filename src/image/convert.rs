@@ -0,0 +1,220 @@
+use anyhow::{Context, Result, bail};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+use std::io::Cursor;
+
+/// JPEG quality used when none is given
+pub const DEFAULT_QUALITY: u8 = 85;
+
+/// Image formats clips can be converted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertFormat {
+    Png,
+    Jpeg,
+    Webp,
+    Bmp,
+    Gif,
+    Tiff,
+}
+
+impl ConvertFormat {
+    /// Names accepted by `--convert` and the convert prompt
+    pub const NAMES: &'static str = "png, jpeg, webp, bmp, gif, tiff";
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "png" => Some(ConvertFormat::Png),
+            "jpeg" | "jpg" => Some(ConvertFormat::Jpeg),
+            "webp" => Some(ConvertFormat::Webp),
+            "bmp" => Some(ConvertFormat::Bmp),
+            "gif" => Some(ConvertFormat::Gif),
+            "tiff" | "tif" => Some(ConvertFormat::Tiff),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ConvertFormat::Png => "png",
+            ConvertFormat::Jpeg => "jpeg",
+            ConvertFormat::Webp => "webp",
+            ConvertFormat::Bmp => "bmp",
+            ConvertFormat::Gif => "gif",
+            ConvertFormat::Tiff => "tiff",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ConvertFormat::Png => "image/png",
+            ConvertFormat::Jpeg => "image/jpeg",
+            ConvertFormat::Webp => "image/webp",
+            ConvertFormat::Bmp => "image/bmp",
+            ConvertFormat::Gif => "image/gif",
+            ConvertFormat::Tiff => "image/tiff",
+        }
+    }
+
+    fn image_format(self) -> ImageFormat {
+        match self {
+            ConvertFormat::Png => ImageFormat::Png,
+            ConvertFormat::Jpeg => ImageFormat::Jpeg,
+            ConvertFormat::Webp => ImageFormat::WebP,
+            ConvertFormat::Bmp => ImageFormat::Bmp,
+            ConvertFormat::Gif => ImageFormat::Gif,
+            ConvertFormat::Tiff => ImageFormat::Tiff,
+        }
+    }
+}
+
+/// Target format and limits for re-encoding an image clip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConvertOptions {
+    pub format: ConvertFormat,
+    /// Shrink so neither side is larger than this (keeps the aspect ratio)
+    pub max_dim: Option<u32>,
+    /// JPEG quality 1-100 (WebP is always lossless)
+    pub quality: u8,
+}
+
+impl ConvertOptions {
+    pub fn new(format: ConvertFormat) -> Self {
+        Self {
+            format,
+            max_dim: None,
+            quality: DEFAULT_QUALITY,
+        }
+    }
+
+    /// Parse a spec like `jpeg max=1920 quality=80`, as typed in the TUI prompt
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut words = spec.split_whitespace();
+        let name = words.next().context("No format given")?;
+        let format = ConvertFormat::from_name(name)
+            .with_context(|| format!("Unknown format '{}' (use {})", name, ConvertFormat::NAMES))?;
+
+        let mut options = Self::new(format);
+        for word in words {
+            match word.split_once('=') {
+                Some(("max", value)) => {
+                    options.max_dim = Some(
+                        value
+                            .parse()
+                            .with_context(|| format!("Invalid max size '{}'", value))?,
+                    );
+                }
+                Some(("quality", value)) => {
+                    options.quality = value
+                        .parse()
+                        .with_context(|| format!("Invalid quality '{}'", value))?;
+                }
+                _ => bail!("Unknown option '{}' (use max=N or quality=Q)", word),
+            }
+        }
+        options.validate()?;
+        Ok(options)
+    }
+
+    /// Check the limits are usable
+    pub fn validate(&self) -> Result<()> {
+        if self.max_dim == Some(0) {
+            bail!("Max size must be at least 1");
+        }
+        if !(1..=100).contains(&self.quality) {
+            bail!("Quality must be between 1 and 100");
+        }
+        Ok(())
+    }
+}
+
+/// Decode image bytes and re-encode them in another format
+pub fn convert(data: &[u8], options: &ConvertOptions) -> Result<Vec<u8>> {
+    options.validate()?;
+    let mut img = image::load_from_memory(data).context("Failed to decode image")?;
+
+    if let Some(max_dim) = options.max_dim
+        && (img.width() > max_dim || img.height() > max_dim)
+    {
+        img = img.resize(max_dim, max_dim, FilterType::Lanczos3);
+    }
+
+    let mut out = Cursor::new(Vec::new());
+    match options.format {
+        ConvertFormat::Jpeg => {
+            // JPEG has no alpha, so flatten transparency onto white
+            let flat = flatten(&img);
+            JpegEncoder::new_with_quality(&mut out, options.quality)
+                .encode_image(&flat)
+                .context("Failed to encode jpeg")?;
+        }
+        ConvertFormat::Png | ConvertFormat::Tiff => img
+            .write_to(&mut out, options.format.image_format())
+            .with_context(|| format!("Failed to encode {}", options.format.name()))?,
+        format => {
+            // The remaining encoders only take 8-bit RGB(A)
+            DynamicImage::ImageRgba8(img.to_rgba8())
+                .write_to(&mut out, format.image_format())
+                .with_context(|| format!("Failed to encode {}", format.name()))?
+        }
+    }
+    Ok(out.into_inner())
+}
+
+/// Composite an image onto a white background
+fn flatten(img: &DynamicImage) -> RgbImage {
+    let rgba = img.to_rgba8();
+    RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend = |c: u8| ((c as u32 * a as u32 + 255 * (255 - a as u32)) / 255) as u8;
+        Rgb([blend(r), blend(g), blend(b)])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut out = Cursor::new(Vec::new());
+        DynamicImage::new_rgba8(width, height)
+            .write_to(&mut out, ImageFormat::Png)
+            .unwrap();
+        out.into_inner()
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options = ConvertOptions::parse("JPG max=1920 quality=70").unwrap();
+        assert_eq!(options.format, ConvertFormat::Jpeg);
+        assert_eq!(options.max_dim, Some(1920));
+        assert_eq!(options.quality, 70);
+        assert_eq!(
+            ConvertOptions::parse("webp").unwrap(),
+            ConvertOptions::new(ConvertFormat::Webp)
+        );
+
+        assert!(ConvertOptions::parse("").is_err());
+        assert!(ConvertOptions::parse("heic").is_err());
+        assert!(ConvertOptions::parse("png size=10").is_err());
+        assert!(ConvertOptions::parse("jpeg quality=0").is_err());
+    }
+
+    #[test]
+    fn test_convert() {
+        let data = png(40, 20);
+
+        let jpeg = convert(&data, &ConvertOptions::new(ConvertFormat::Jpeg)).unwrap();
+        assert_eq!(
+            image::guess_format(&jpeg).unwrap(),
+            image::ImageFormat::Jpeg
+        );
+
+        let mut options = ConvertOptions::new(ConvertFormat::Bmp);
+        options.max_dim = Some(10);
+        let bmp = image::load_from_memory(&convert(&data, &options).unwrap()).unwrap();
+        assert_eq!((bmp.width(), bmp.height()), (10, 5));
+
+        assert!(convert(b"not an image", &options).is_err());
+    }
+}
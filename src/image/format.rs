@@ -0,0 +1,49 @@
+/// Bytes searched for an `<svg` root element
+const SVG_SNIFF_BYTES: usize = 1024;
+
+/// MIME type of image data, detected from its contents
+/// Raster formats are recognised by their signature; SVG is sniffed as XML text
+pub fn detect_mime_type(data: &[u8]) -> Option<&'static str> {
+    if let Ok(format) = image::guess_format(data) {
+        return Some(format.to_mime_type());
+    }
+    is_svg(data).then_some("image/svg+xml")
+}
+
+/// Check whether data starts like an SVG document (optionally after an XML declaration,
+/// doctype or comments)
+fn is_svg(data: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&data[..data.len().min(SVG_SNIFF_BYTES)]);
+    let head = head.trim_start_matches('\u{feff}').trim_start();
+    head.starts_with('<') && head.contains("<svg")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_mime_type() {
+        assert_eq!(
+            detect_mime_type(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            Some("image/png")
+        );
+        assert_eq!(
+            detect_mime_type(b"\xff\xd8\xff\xe0\0\x10JFIF"),
+            Some("image/jpeg")
+        );
+        assert_eq!(detect_mime_type(b"GIF89a\x01\0\x01\0"), Some("image/gif"));
+        assert_eq!(
+            detect_mime_type(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(
+            detect_mime_type(
+                b"<?xml version=\"1.0\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\"/>"
+            ),
+            Some("image/svg+xml")
+        );
+        assert_eq!(detect_mime_type(b"plain text"), None);
+        assert_eq!(detect_mime_type(b"<html><body></body></html>"), None);
+    }
+}
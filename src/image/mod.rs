@@ -1,6 +1,14 @@
+pub mod convert;
+pub mod format;
 pub mod protocol;
+pub mod view;
 
+pub use convert::{ConvertFormat, ConvertOptions};
+pub use format::detect_mime_type;
 pub use protocol::ImageProtocol;
+pub use view::{
+    ImageInfo, ImageZoom, THUMBNAIL_CACHE_SIZE, THUMBNAIL_COLS, THUMBNAIL_ROWS, ZoomView,
+};
 
 /// Create an image protocol handler using ratatui-image
 ///
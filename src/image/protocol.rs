@@ -20,6 +20,14 @@ impl ImageProtocol {
         ImageProtocol { picker }
     }
 
+    /// Create a handler without querying the terminal (halfblocks, default font size)
+    /// Starts instantly, for views that show no images
+    pub fn without_query() -> Self {
+        ImageProtocol {
+            picker: Picker::from_fontsize((8, 12)),
+        }
+    }
+
     /// Check if the terminal supports image rendering
     pub fn is_available(&self) -> bool {
         // Picker always returns something (falls back to Halfblocks)
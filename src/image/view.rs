@@ -0,0 +1,190 @@
+use image::imageops::FilterType;
+use image::{ColorType, DynamicImage};
+use ratatui::layout::Rect;
+use ratatui_image::picker::Picker;
+use ratatui_image::protocol::StatefulProtocol;
+
+/// Width of list thumbnails in terminal cells
+pub const THUMBNAIL_COLS: u16 = 6;
+
+/// Height of list thumbnails in terminal cells (the text rows of a comfortable entry)
+pub const THUMBNAIL_ROWS: u16 = 2;
+
+/// Thumbnails kept decoded for the list
+pub const THUMBNAIL_CACHE_SIZE: usize = 100;
+
+/// Largest zoom factor in the preview
+const MAX_SCALE: u32 = 8;
+
+/// Size and pixel format of a decoded image, shown in the preview metadata
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+    pub color: ColorType,
+}
+
+impl ImageInfo {
+    pub fn of(image: &DynamicImage) -> Self {
+        Self {
+            width: image.width(),
+            height: image.height(),
+            color: image.color(),
+        }
+    }
+
+    /// Bytes the decoded pixels take in memory
+    pub fn decoded_bytes(&self) -> u64 {
+        self.width as u64 * self.height as u64 * self.color.bytes_per_pixel() as u64
+    }
+
+    /// Summary like `1920×1080 Rgba8, 7.9 MiB decoded`
+    pub fn describe(&self) -> String {
+        format!(
+            "{}×{} {:?}, {:.1} MiB decoded",
+            self.width,
+            self.height,
+            self.color,
+            self.decoded_bytes() as f64 / (1024.0 * 1024.0)
+        )
+    }
+}
+
+/// How the preview scales an image
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ImageZoom {
+    /// Shrink to fit the preview pane (never enlarges)
+    #[default]
+    Fit,
+    /// Terminal pixels per image pixel (1 is actual size)
+    Scale(u32),
+}
+
+impl ImageZoom {
+    pub fn zoom_in(self) -> Self {
+        match self {
+            ImageZoom::Fit => ImageZoom::Scale(1),
+            ImageZoom::Scale(scale) => ImageZoom::Scale((scale * 2).min(MAX_SCALE)),
+        }
+    }
+
+    pub fn zoom_out(self) -> Self {
+        match self {
+            ImageZoom::Fit | ImageZoom::Scale(1) => ImageZoom::Fit,
+            ImageZoom::Scale(scale) => ImageZoom::Scale(scale / 2),
+        }
+    }
+
+    /// Short label for the preview indicator
+    pub fn label(self) -> String {
+        match self {
+            ImageZoom::Fit => "fit".to_string(),
+            ImageZoom::Scale(1) => "1:1".to_string(),
+            ImageZoom::Scale(scale) => format!("{}x", scale),
+        }
+    }
+}
+
+/// Zoomed and panned view of a decoded image
+/// The visible region is cropped (and enlarged) here so the terminal only gets what fits
+pub struct ZoomView {
+    pub clip_id: u64,
+    image: DynamicImage,
+    /// Top-left of the visible region, in image pixels
+    pan: (u32, u32),
+    /// Size of the visible region at the last render, in image pixels
+    region: (u32, u32),
+    /// Area, scale and pan the protocol was built for
+    rendered: Option<(Rect, u32, (u32, u32))>,
+    protocol: Option<StatefulProtocol>,
+}
+
+impl ZoomView {
+    pub fn new(clip_id: u64, image: DynamicImage) -> Self {
+        Self {
+            clip_id,
+            image,
+            pan: (0, 0),
+            region: (0, 0),
+            rendered: None,
+            protocol: None,
+        }
+    }
+
+    /// Top-left of the visible region, in image pixels
+    pub fn position(&self) -> (u32, u32) {
+        self.pan
+    }
+
+    /// Move the visible region by quarter-viewport steps (negative is up/left)
+    pub fn pan(&mut self, dx: i32, dy: i32) {
+        let step = |region: u32| (region / 4).max(1) as i64;
+        let x = self.pan.0 as i64 + dx as i64 * step(self.region.0);
+        let y = self.pan.1 as i64 + dy as i64 * step(self.region.1);
+        self.pan = (x.max(0) as u32, y.max(0) as u32);
+        self.clamp_pan();
+    }
+
+    fn clamp_pan(&mut self) {
+        self.pan.0 = self
+            .pan
+            .0
+            .min(self.image.width().saturating_sub(self.region.0));
+        self.pan.1 = self
+            .pan
+            .1
+            .min(self.image.height().saturating_sub(self.region.1));
+    }
+
+    /// Protocol showing the visible region at `scale`, rebuilt when area, scale or pan change
+    pub fn protocol(
+        &mut self,
+        picker: &mut Picker,
+        area: Rect,
+        scale: u32,
+    ) -> &mut StatefulProtocol {
+        let (font_width, font_height) = picker.font_size();
+        let scale = scale.max(1);
+        self.region = (
+            (area.width as u32 * font_width as u32 / scale).clamp(1, self.image.width().max(1)),
+            (area.height as u32 * font_height as u32 / scale).clamp(1, self.image.height().max(1)),
+        );
+        self.clamp_pan();
+
+        let key = (area, scale, self.pan);
+        if self.rendered != Some(key) || self.protocol.is_none() {
+            let (width, height) = self.region;
+            let mut visible = self.image.crop_imm(self.pan.0, self.pan.1, width, height);
+            if scale > 1 {
+                visible = visible.resize_exact(width * scale, height * scale, FilterType::Nearest);
+            }
+            self.protocol = Some(picker.new_resize_protocol(visible));
+            self.rendered = Some(key);
+        }
+        self.protocol.as_mut().expect("protocol built above")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_steps() {
+        let zoom = ImageZoom::Fit.zoom_in();
+        assert_eq!(zoom, ImageZoom::Scale(1));
+        assert_eq!(zoom.zoom_in().zoom_in(), ImageZoom::Scale(4));
+        assert_eq!(ImageZoom::Scale(8).zoom_in(), ImageZoom::Scale(8));
+        assert_eq!(ImageZoom::Scale(2).zoom_out(), ImageZoom::Scale(1));
+        assert_eq!(ImageZoom::Scale(1).zoom_out(), ImageZoom::Fit);
+        assert_eq!(ImageZoom::Scale(2).label(), "2x");
+    }
+
+    #[test]
+    fn test_image_info() {
+        let image = DynamicImage::new_rgba8(1024, 512);
+        let info = ImageInfo::of(&image);
+        assert_eq!(info.decoded_bytes(), 1024 * 512 * 4);
+        assert_eq!(info.describe(), "1024×512 Rgba8, 2.0 MiB decoded");
+    }
+}
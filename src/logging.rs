@@ -39,29 +39,28 @@ impl Log for CliprLogger {
         let timestamp = chrono::Local::now();
 
         // Write to file if level is enabled
-        if level <= self.file_level {
-            if let Ok(mut writer) = self.file_writer.lock() {
-                let _ = writeln!(
-                    writer,
-                    "{} [{}] {}",
-                    timestamp.format("%Y-%m-%d %H:%M:%S"),
-                    level,
-                    message
-                );
-            }
+        if level <= self.file_level
+            && let Ok(mut writer) = self.file_writer.lock()
+        {
+            let _ = writeln!(
+                writer,
+                "{} [{}] {}",
+                timestamp.format("%Y-%m-%d %H:%M:%S"),
+                level,
+                message
+            );
         }
 
         // Send to flash message channel if level is enabled and channel exists
-        if level <= self.flash_level {
-            if let Some(tx) = &self.flash_tx {
-                if let Ok(tx) = tx.lock() {
-                    let _ = tx.send(FlashMessage {
-                        level,
-                        message,
-                        timestamp: Instant::now(),
-                    });
-                }
-            }
+        if level <= self.flash_level
+            && let Some(tx) = &self.flash_tx
+            && let Ok(tx) = tx.lock()
+        {
+            let _ = tx.send(FlashMessage {
+                level,
+                message,
+                timestamp: Instant::now(),
+            });
         }
     }
 
@@ -1,7 +1,10 @@
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use ratatui::crossterm::{
-    event::{self, Event},
+    event::{
+        self, DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture, Event,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
@@ -9,11 +12,16 @@ use ratatui::{Terminal, backend::CrosstermBackend};
 use std::io::{self, Read};
 use std::time::Duration;
 
-use clipr::app::App;
+use clipr::app::{App, PasteRequest};
 use clipr::clipboard::{create_backend, watch};
-use clipr::models::{ClipContent, Registry};
+use clipr::image::{ConvertFormat, ConvertOptions, ImageProtocol};
+use clipr::models::{
+    ClipContent, ClipEntry, ClipboardHistory, Registry, SearchIndex, SearchQuery, Template,
+    TemplateContext, dmenu_line, normalize_tag, parse_dmenu_line,
+};
 use clipr::storage::{
-    BincodeHistoryStorage, ConfigStorage, HistoryStorage, TomlConfigStorage, ensure_directories,
+    BincodeHistoryStorage, Config, ConfigStorage, HistoryStorage, TomlConfigStorage,
+    ensure_directories,
 };
 
 enum ContentType {
@@ -44,10 +52,24 @@ enum Commands {
     Stats,
 
     /// Show clipboard history entries
+    #[command(visible_alias = "list")]
     History {
         /// Number of entries to show (default: 10)
         #[arg(short, long, default_value = "10")]
         limit: usize,
+        /// Only show entries with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+        /// Search query, same syntax as the TUI search bar
+        /// (e.g. "type:text pinned:yes after:2026-01-01 size:>1k !#work deploy")
+        #[arg(short, long)]
+        search: Option<String>,
+    },
+
+    /// Restore a deleted clip from the trash (lists the trash if no ID is given)
+    Restore {
+        /// ID of the trashed clip
+        id: Option<u64>,
     },
 
     /// Export a theme to TOML format
@@ -73,14 +95,63 @@ enum Commands {
         #[arg(long)]
         stdout: bool,
     },
+
+    /// Fast picker for launcher hotkeys: type to search, Enter picks and exits
+    /// Prints the picked clip to stdout (the picker draws on /dev/tty); Esc
+    /// cancels and exits with status 1
+    Pick {
+        /// Print the clip's ID instead of its content
+        #[arg(long, conflicts_with = "paste")]
+        id: bool,
+        /// Paste the clip (via Ctrl-V) instead of printing it
+        #[arg(long)]
+        paste: bool,
+    },
+
+    /// List clips as `id<TAB>preview` lines for dmenu, rofi, fuzzel, etc.
+    /// (cliphist-compatible: each clip on one line, most recent first)
+    DmenuList {
+        /// Maximum preview width in columns
+        #[arg(long, default_value = "100")]
+        preview_width: usize,
+    },
+
+    /// Output the full clip for a line picked from `clipr dmenu-list`
+    /// (e.g. `clipr dmenu-list | fuzzel --dmenu | clipr dmenu-decode --copy`)
+    DmenuDecode {
+        /// Picked line (read from stdin if not given)
+        line: Option<String>,
+        /// Copy to clipboard instead of writing to stdout
+        #[arg(long)]
+        copy: bool,
+    },
+
+    /// Copy a clip from history to clipboard, optionally converting images
+    Get {
+        /// Position in `clipr history` (1 is the most recent clip)
+        #[arg(default_value = "1")]
+        index: usize,
+        /// Re-encode an image clip as png, jpeg, webp, bmp, gif or tiff
+        #[arg(long, value_name = "FORMAT")]
+        convert: Option<String>,
+        /// Shrink the converted image so neither side exceeds N pixels
+        #[arg(long, value_name = "N", requires = "convert")]
+        max_dim: Option<u32>,
+        /// JPEG quality 1-100 (default 85; WebP is always lossless)
+        #[arg(long, value_name = "Q", requires = "convert")]
+        quality: Option<u8>,
+        /// Write to stdout instead of clipboard
+        #[arg(long)]
+        stdout: bool,
+    },
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
     // Initialize logging based on command type
-    // TUI mode initializes its own logger with flash channel
-    if cli.command.is_some() {
+    // TUI and picker modes initialize their own logger with flash channel
+    if !matches!(cli.command, None | Some(Commands::Pick { .. })) {
         // CLI/daemon commands: initialize logger without flash channel
         let (data_dir, config_dir) = ensure_directories()?;
 
@@ -101,7 +172,10 @@ fn main() -> Result<()> {
         Some(Commands::StoreText) => cmd_store_text(),
         Some(Commands::StoreImage) => cmd_store_image(),
         Some(Commands::Stats) => cmd_stats(),
-        Some(Commands::History { limit }) => cmd_history(limit),
+        Some(Commands::History { limit, tag, search }) => {
+            cmd_history(limit, tag.as_deref(), search.as_deref())
+        }
+        Some(Commands::Restore { id }) => cmd_restore(id),
         Some(Commands::ExportTheme { theme_name }) => cmd_export_theme(&theme_name),
         Some(Commands::GrabTempRegister { register, stdout }) => {
             cmd_grab_temp_register(register, stdout)
@@ -109,6 +183,30 @@ fn main() -> Result<()> {
         Some(Commands::GrabPermRegister { register, stdout }) => {
             cmd_grab_perm_register(register, stdout)
         }
+        Some(Commands::Get {
+            index,
+            convert,
+            max_dim,
+            quality,
+            stdout,
+        }) => {
+            let convert = match convert {
+                Some(name) => {
+                    let format = ConvertFormat::from_name(&name).ok_or_else(|| {
+                        anyhow::anyhow!("Unknown format '{}' (use {})", name, ConvertFormat::NAMES)
+                    })?;
+                    let mut options = ConvertOptions::new(format);
+                    options.max_dim = max_dim;
+                    options.quality = quality.unwrap_or(options.quality);
+                    Some(options)
+                }
+                None => None,
+            };
+            cmd_get(index, convert, stdout)
+        }
+        Some(Commands::Pick { id, paste }) => cmd_pick(id, paste),
+        Some(Commands::DmenuList { preview_width }) => cmd_dmenu_list(preview_width),
+        Some(Commands::DmenuDecode { line, copy }) => cmd_dmenu_decode(line, copy),
         None => {
             // Default: launch TUI (initializes its own logger with flash channel)
             cmd_tui()
@@ -162,6 +260,7 @@ fn store_clip(content_type: ContentType) -> Result<()> {
     let history_path = data_dir.join("history.bin");
     let history_storage = BincodeHistoryStorage::new(history_path, config.general.max_history);
     let mut history = history_storage.load()?;
+    apply_history_settings(&mut history, &config);
 
     // Read content from stdin
     let mut buffer = Vec::new();
@@ -181,10 +280,20 @@ fn store_clip(content_type: ContentType) -> Result<()> {
             let text = String::from_utf8(buffer).context("Clipboard text is not valid UTF-8")?;
             ClipContent::Text(text)
         }
-        ContentType::Image => ClipContent::Image {
-            data: buffer,
-            mime_type: "image/png".to_string(),
-        },
+        ContentType::Image => {
+            // The watcher takes whichever image type is offered, so keep its real format
+            let Some(mime_type) = clipr::image::detect_mime_type(&buffer) else {
+                log::warn!(
+                    "Skipping clipboard image in an unrecognized format ({} bytes)",
+                    buffer.len()
+                );
+                return Ok(());
+            };
+            ClipContent::Image {
+                data: buffer,
+                mime_type: mime_type.to_string(),
+            }
+        }
     };
 
     // Add to history
@@ -235,12 +344,75 @@ fn cmd_stats() -> Result<()> {
     println!("  Files: {}", file_count);
     println!("Pinned entries: {}", pinned_count);
     println!("Max history: {}", config.general.max_history);
+    println!("Trash: {}", history.trash.len());
 
     Ok(())
 }
 
-/// Show clipboard history entries
-fn cmd_history(limit: usize) -> Result<()> {
+/// Apply config settings that aren't persisted with the history
+/// and drop trashed clips past the retention period
+fn apply_history_settings(history: &mut ClipboardHistory, config: &Config) {
+    history.max_entries = config.general.max_history;
+    history.protect_tagged = config.general.protect_tagged;
+    history.max_trash = config.general.max_trash;
+    history.max_trash_bytes = config.general.max_trash_bytes;
+    history.trash_retention_days = config.general.trash_retention_days;
+    history.image_dedup_threshold = config
+        .general
+        .image_dedup
+        .then_some(config.general.image_dedup_threshold);
+    history.purge_trash();
+}
+
+/// Restore a clip from the trash, or list the trash when no ID is given
+fn cmd_restore(id: Option<u64>) -> Result<()> {
+    let (data_dir, config_dir) = ensure_directories()?;
+
+    // Load config
+    let config_storage = TomlConfigStorage::new(config_dir.join("clipr.toml"));
+    let config = config_storage.load()?;
+
+    // Load history
+    let history_path = data_dir.join("history.bin");
+    let history_storage = BincodeHistoryStorage::new(history_path, config.general.max_history);
+    let mut history = history_storage.load()?;
+    apply_history_settings(&mut history, &config);
+
+    let Some(id) = id else {
+        println!("Trash ({} clips):", history.trash.len());
+        println!("{}", "=".repeat(60));
+        for trashed in &history.trash {
+            let deleted_at: chrono::DateTime<chrono::Local> = trashed.deleted_at.into();
+            println!(
+                "{:5}  {}  {}",
+                trashed.entry.id,
+                deleted_at.format("%Y-%m-%d %H:%M"),
+                trashed.entry.preview(50)
+            );
+        }
+        if history.trash.is_empty() {
+            println!("(empty)");
+        }
+        return Ok(());
+    };
+
+    let restored_id = history.restore_from_trash(id)?;
+    history_storage.save(&history)?;
+
+    if restored_id == id {
+        println!("Restored clip {}", id);
+    } else {
+        println!(
+            "Clip {} is already in history as clip {}; removed it from the trash",
+            id, restored_id
+        );
+    }
+
+    Ok(())
+}
+
+/// Show clipboard history entries, optionally filtered by tag and search query
+fn cmd_history(limit: usize, tag: Option<&str>, search: Option<&str>) -> Result<()> {
     let (data_dir, config_dir) = ensure_directories()?;
 
     // Load config
@@ -252,10 +424,52 @@ fn cmd_history(limit: usize) -> Result<()> {
     let history_storage = BincodeHistoryStorage::new(history_path, config.general.max_history);
     let history = history_storage.load()?;
 
-    println!("Recent Clipboard Entries (showing up to {}):", limit);
+    let tag = match tag {
+        Some(tag) => {
+            Some(normalize_tag(tag).ok_or_else(|| anyhow::anyhow!("Invalid tag '{}'", tag))?)
+        }
+        None => None,
+    };
+
+    let query = search.map(SearchQuery::parse).unwrap_or_default();
+    if !query.invalid.is_empty() {
+        anyhow::bail!("Invalid search filter: {}", query.invalid.join(", "));
+    }
+
+    match (&tag, search) {
+        (Some(tag), _) => println!(
+            "Recent Clipboard Entries tagged #{} (showing up to {}):",
+            tag, limit
+        ),
+        (None, Some(search)) => println!(
+            "Clipboard Entries matching '{}' (showing up to {}):",
+            search, limit
+        ),
+        (None, None) => println!("Recent Clipboard Entries (showing up to {}):", limit),
+    }
     println!("{}", "=".repeat(60));
 
-    for (i, entry) in history.entries().iter().take(limit).enumerate() {
+    // Apply tag and field filters, then rank by the fuzzy remainder if any
+    let filtered: Vec<ClipEntry> = history
+        .entries()
+        .iter()
+        .filter(|e| tag.as_ref().is_none_or(|t| e.has_tag(t)))
+        .filter(|e| query.matches(e))
+        .cloned()
+        .collect();
+
+    let entries: Vec<&ClipEntry> = if query.fuzzy.is_empty() {
+        filtered.iter().take(limit).collect()
+    } else {
+        SearchIndex::new()
+            .search(&filtered, &query.fuzzy)
+            .into_iter()
+            .filter_map(|(id, _score)| filtered.iter().find(|e| e.id == id))
+            .take(limit)
+            .collect()
+    };
+
+    for (i, entry) in entries.iter().enumerate() {
         let type_label = match &entry.content {
             ClipContent::Text(_) => "TEXT",
             ClipContent::Image { .. } => "IMAGE",
@@ -264,12 +478,22 @@ fn cmd_history(limit: usize) -> Result<()> {
 
         let preview = entry.preview(50);
         let pinned_mark = if entry.pinned { " 📌" } else { "" };
-
-        println!("{:3}. [{}]{} {}", i + 1, type_label, pinned_mark, preview);
+        let tags: String = entry.tags.iter().map(|t| format!(" #{}", t)).collect();
+
+        println!(
+            "{:3}. [{}]{} {}{}",
+            i + 1,
+            type_label,
+            pinned_mark,
+            preview,
+            tags
+        );
     }
 
     if history.is_empty() {
         println!("(empty - no clipboard history yet)");
+    } else if entries.is_empty() {
+        println!("(no matching entries)");
     }
 
     Ok(())
@@ -292,8 +516,159 @@ fn cmd_export_theme(theme_name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Print history as `id<TAB>preview` lines for a dmenu-style launcher
+fn cmd_dmenu_list(preview_width: usize) -> Result<()> {
+    use std::io::Write;
+
+    let (data_dir, config_dir) = ensure_directories()?;
+
+    // Load config
+    let config_storage = TomlConfigStorage::new(config_dir.join("clipr.toml"));
+    let config = config_storage.load()?;
+
+    // Load history
+    let history_path = data_dir.join("history.bin");
+    let history_storage = BincodeHistoryStorage::new(history_path, config.general.max_history);
+    let history = history_storage.load()?;
+
+    let mut stdout = io::stdout().lock();
+    for entry in history.entries() {
+        writeln!(stdout, "{}", dmenu_line(entry, preview_width))?;
+    }
+    stdout.flush()?;
+
+    Ok(())
+}
+
+/// Output (or copy) the clip whose ID starts a line picked from `clipr dmenu-list`
+fn cmd_dmenu_decode(line: Option<String>, copy: bool) -> Result<()> {
+    let line = match line {
+        Some(line) => line,
+        None => {
+            let mut line = String::new();
+            io::stdin()
+                .read_line(&mut line)
+                .context("Failed to read from stdin")?;
+            line
+        }
+    };
+
+    // Nothing picked (launcher closed), nothing to do
+    if line.trim().is_empty() {
+        return Ok(());
+    }
+    let id = parse_dmenu_line(&line).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Input is not a `clipr dmenu-list` line: {}",
+            line.trim_end()
+        )
+    })?;
+
+    let (data_dir, config_dir) = ensure_directories()?;
+
+    // Load config
+    let config_storage = TomlConfigStorage::new(config_dir.join("clipr.toml"));
+    let config = config_storage.load()?;
+
+    // Load history
+    let history_path = data_dir.join("history.bin");
+    let history_storage = BincodeHistoryStorage::new(history_path, config.general.max_history);
+    let mut history = history_storage.load()?;
+    apply_history_settings(&mut history, &config);
+
+    let content = history
+        .get_entry(id)
+        .map(|entry| entry.content.clone())
+        .ok_or_else(|| anyhow::anyhow!("No clip with ID {}", id))?;
+
+    if copy {
+        let backend = create_backend()?;
+        match &content {
+            ClipContent::Text(text) => backend.write_text(text)?,
+            ClipContent::Image { data, mime_type } => {
+                if !backend.supports_images() {
+                    anyhow::bail!("Image clipboard not supported by backend");
+                }
+                backend.write_image(data, mime_type)?;
+            }
+            ClipContent::File { path, .. } => backend.write_text(&path.display().to_string())?,
+        }
+    } else {
+        write_stdout(&content)?;
+    }
+
+    // Count the copy for frecency ranking
+    history.record_use(id);
+    history_storage.save(&history)?;
+
+    Ok(())
+}
+
 /// Launch the TUI (default mode)
 fn cmd_tui() -> Result<()> {
+    // Create image protocol handler (if terminal supports it)
+    let (mut app, history_storage) = load_app(clipr::image::create_image_protocol())?;
+
+    let result = run_terminal(io::stdout(), &mut app);
+
+    // Save state on exit
+    if let Err(e) = &result {
+        eprintln!("Error running TUI: {}", e);
+    }
+
+    // Save history
+    history_storage.save(&app.history)?;
+
+    // Handle paste request - write to clipboard then spawn background process to paste
+    if let PasteRequest::PasteFromClipboard(content) = &app.paste_request {
+        paste_content(content, app.config.general.paste_delay_ms)?;
+    }
+
+    result
+}
+
+/// Fast picker for launcher hotkeys: search-first list without preview
+/// Prints the selected clip's content (or ID) to stdout, or pastes it
+fn cmd_pick(print_id: bool, paste: bool) -> Result<()> {
+    // The picker shows no images, so skip the slow terminal graphics query
+    let (mut app, history_storage) = load_app(ImageProtocol::without_query())?;
+    app.enter_pick_mode();
+
+    // Draw on the terminal itself so stdout only carries the selection
+    let tty = std::fs::OpenOptions::new()
+        .write(true)
+        .open("/dev/tty")
+        .context("Failed to open /dev/tty")?;
+    let result = run_terminal(tty, &mut app);
+
+    if let Err(e) = &result {
+        eprintln!("Error running picker: {}", e);
+    }
+    history_storage.save(&app.history)?;
+
+    let paste_delay_ms = app.config.general.paste_delay_ms;
+    if let PasteRequest::PasteFromClipboard(content) = &app.paste_request {
+        paste_content(content, paste_delay_ms)?;
+    } else if let Some((id, content)) = &app.picked {
+        if paste {
+            paste_content(content, paste_delay_ms)?;
+        } else if print_id {
+            println!("{}", id);
+        } else {
+            write_stdout(content)?;
+        }
+    } else if result.is_ok() {
+        // Cancelled: exit non-zero with nothing on stdout, so launcher scripts
+        // like `clipr pick | wl-copy` can tell
+        std::process::exit(1);
+    }
+
+    result
+}
+
+/// Load config, history and registers into a TUI app
+/// Also starts the TUI logger, which shows warnings as flash messages
+fn load_app(image_protocol: ImageProtocol) -> Result<(App, BincodeHistoryStorage)> {
     // Load state from storage
     let (data_dir, config_dir) = ensure_directories()?;
 
@@ -315,6 +690,7 @@ fn cmd_tui() -> Result<()> {
     let history_storage =
         BincodeHistoryStorage::new(history_path.clone(), config.general.max_history);
     let mut history = history_storage.load()?;
+    apply_history_settings(&mut history, &config);
 
     // Create registry and rebuild from loaded history to sync register assignments
     let mut registers = Registry::new();
@@ -330,11 +706,8 @@ fn cmd_tui() -> Result<()> {
     // Create clipboard backend
     let backend = create_backend()?;
 
-    // Create image protocol handler (if terminal supports it)
-    let image_protocol = clipr::image::create_image_protocol();
-
     // Create app
-    let mut app = App::new(
+    let app = App::new(
         history,
         registers,
         config,
@@ -343,55 +716,81 @@ fn cmd_tui() -> Result<()> {
         Some(flash_rx),
     )?;
 
+    Ok((app, history_storage))
+}
+
+/// Set up the terminal on `out`, run the TUI and restore the terminal
+fn run_terminal<W: io::Write>(mut out: W, app: &mut App) -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
+    execute!(
+        out,
+        EnterAlternateScreen,
+        EnableBracketedPaste,
+        EnableFocusChange
+    )?;
+    let mouse = app.config.general.mouse;
+    if mouse {
+        execute!(out, EnableMouseCapture)?;
+    }
+    let backend = CrosstermBackend::new(out);
     let mut terminal = Terminal::new(backend)?;
 
     // Main event loop
-    let result = run_tui(&mut terminal, &mut app);
+    let result = run_tui(&mut terminal, app);
 
     // Cleanup terminal
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
-
-    // Save state on exit
-    if let Err(e) = &result {
-        eprintln!("Error running TUI: {}", e);
+    if mouse {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
     }
+    execute!(
+        terminal.backend_mut(),
+        DisableFocusChange,
+        DisableBracketedPaste,
+        LeaveAlternateScreen
+    )?;
+    terminal.show_cursor()?;
 
-    // Save history
-    history_storage.save(&app.history)?;
+    result
+}
 
-    // Handle paste request - write to clipboard then spawn background process to paste
-    if let clipr::app::PasteRequest::PasteFromClipboard(content) = &app.paste_request {
-        let backend = create_backend()?;
-        match content {
-            ClipContent::Text(text) => {
-                backend.write_text(text)?;
-            }
-            ClipContent::Image { data, .. } => {
-                backend.write_image(data)?;
-            }
-            ClipContent::File { path, .. } => {
-                backend.write_text(&path.display().to_string())?;
-            }
+/// Write clip content to the clipboard and simulate Ctrl-V after a delay
+fn paste_content(content: &ClipContent, delay_ms: u64) -> Result<()> {
+    let backend = create_backend()?;
+    match content {
+        ClipContent::Text(text) => {
+            backend.write_text(text)?;
+        }
+        ClipContent::Image { data, mime_type } => {
+            backend.write_image(data, mime_type)?;
+        }
+        ClipContent::File { path, .. } => {
+            backend.write_text(&path.display().to_string())?;
         }
-
-        // Simulate Ctrl-V paste after delay
-        backend.paste_from_clipboard(app.config.general.paste_delay_ms)?;
     }
 
-    result
+    // Simulate Ctrl-V paste after delay
+    backend.paste_from_clipboard(delay_ms)
+}
+
+/// Write clip content to stdout as is (image bytes, file clips as their path)
+fn write_stdout(content: &ClipContent) -> Result<()> {
+    use std::io::Write;
+    let mut stdout = io::stdout().lock();
+    match content {
+        ClipContent::Text(text) => stdout.write_all(text.as_bytes())?,
+        ClipContent::Image { data, .. } => stdout.write_all(data)?,
+        ClipContent::File { path, .. } => write!(stdout, "{}", path.display())?,
+    }
+    stdout.flush()?;
+    Ok(())
 }
 
 /// Run the TUI event loop
 fn run_tui<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
     // Trigger initial image load
-    app.update_image_cache();
+    app.request_image_load();
 
     loop {
         // Check for completed image loads
@@ -400,6 +799,9 @@ fn run_tui<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut A
         // Check for theme file changes (development mode)
         app.check_theme_reload();
 
+        // Collect background search results
+        app.poll_search_results();
+
         // Poll and prune flash messages
         app.poll_flash_messages();
         app.prune_flash_messages();
@@ -408,10 +810,15 @@ fn run_tui<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut A
         terminal.draw(|f| app.draw(f))?;
 
         // Handle events with timeout for responsive UI (60fps)
-        if event::poll(Duration::from_millis(16))?
-            && let Event::Key(key) = event::read()?
-        {
-            app.handle_key(key)?;
+        if event::poll(Duration::from_millis(16))? {
+            match event::read()? {
+                Event::Key(key) => app.handle_key(key)?,
+                Event::Mouse(mouse) => app.handle_mouse(mouse)?,
+                Event::Resize(width, height) => app.handle_resize(width, height),
+                Event::Paste(text) => app.handle_paste(&text),
+                Event::FocusGained => app.handle_focus(true),
+                Event::FocusLost => app.handle_focus(false),
+            }
         }
 
         // Exit check
@@ -446,6 +853,7 @@ fn grab_register(is_permanent: bool, register: char, use_stdout: bool) -> Result
     let history_path = data_dir.join("history.bin");
     let history_storage = BincodeHistoryStorage::new(history_path, config.general.max_history);
     let mut history = history_storage.load()?;
+    apply_history_settings(&mut history, &config);
 
     // Create and rebuild registry from history to sync register assignments
     let mut registry = Registry::new();
@@ -472,33 +880,30 @@ fn grab_register(is_permanent: bool, register: char, use_stdout: bool) -> Result
         return Ok(());
     };
 
+    // Expand snippet template placeholders in permanent registers
+    let content = match &clip.content {
+        ClipContent::Text(text) if is_permanent && Template::has_placeholders(text) => {
+            ClipContent::Text(expand_template_cli(text)?)
+        }
+        content => content.clone(),
+    };
+
     if use_stdout {
         // Write to stdout instead of clipboard
-        match &clip.content {
-            ClipContent::Text(text) => {
-                print!("{}", text);
-            }
-            ClipContent::Image { data, .. } => {
-                use std::io::Write;
-                io::stdout().write_all(data)?;
-            }
-            ClipContent::File { path, .. } => {
-                print!("{}", path.display());
-            }
-        }
+        write_stdout(&content)?;
     } else {
         // Create clipboard backend
         let backend = create_backend()?;
 
         // Copy to clipboard based on content type
-        match &clip.content {
+        match &content {
             ClipContent::Text(text) => {
                 backend.write_text(text)?;
                 println!("Copied text from register '{}' to clipboard", register);
             }
-            ClipContent::Image { data, .. } => {
+            ClipContent::Image { data, mime_type } => {
                 if backend.supports_images() {
-                    backend.write_image(data)?;
+                    backend.write_image(data, mime_type)?;
                     println!("Copied image from register '{}' to clipboard", register);
                 } else {
                     eprintln!("Image clipboard not supported by backend");
@@ -517,9 +922,109 @@ fn grab_register(is_permanent: bool, register: char, use_stdout: bool) -> Result
         }
 
         // When run from terminal, add to history for future use
-        history.add_entry(clip.content.clone());
-        history_storage.save(&history)?;
+        history.add_entry(content);
+    }
+
+    // Count the grab for frecency ranking
+    history.record_use(clip_id);
+    history_storage.save(&history)?;
+
+    Ok(())
+}
+
+/// Copy a clip from history to clipboard or stdout, converting image clips if asked
+fn cmd_get(index: usize, convert: Option<ConvertOptions>, use_stdout: bool) -> Result<()> {
+    let (data_dir, config_dir) = ensure_directories()?;
+
+    // Load config
+    let config_storage = TomlConfigStorage::new(config_dir.join("clipr.toml"));
+    let config = config_storage.load()?;
+
+    // Load history
+    let history_path = data_dir.join("history.bin");
+    let history_storage = BincodeHistoryStorage::new(history_path, config.general.max_history);
+    let mut history = history_storage.load()?;
+    apply_history_settings(&mut history, &config);
+
+    let entry = index
+        .checked_sub(1)
+        .and_then(|i| history.entries().get(i))
+        .ok_or_else(|| anyhow::anyhow!("No clip at position {}", index))?;
+    let clip_id = entry.id;
+
+    let content = match convert {
+        Some(options) => {
+            let data = match &entry.content {
+                ClipContent::Image { data, .. } => data.clone(),
+                ClipContent::File { path, mime_type } if mime_type.starts_with("image/") => {
+                    std::fs::read(path).with_context(|| format!("Failed to read {:?}", path))?
+                }
+                _ => anyhow::bail!("Clip {} is not an image", index),
+            };
+            let data = clipr::image::convert::convert(&data, &options)?;
+            ClipContent::Image {
+                data,
+                mime_type: options.format.mime_type().to_string(),
+            }
+        }
+        None => entry.content.clone(),
+    };
+
+    if use_stdout {
+        write_stdout(&content)?;
+    } else {
+        let backend = create_backend()?;
+        match &content {
+            ClipContent::Text(text) => {
+                backend.write_text(text)?;
+                println!("Copied text to clipboard");
+            }
+            ClipContent::Image { data, mime_type } => {
+                if !backend.supports_images() {
+                    anyhow::bail!("Image clipboard not supported by backend");
+                }
+                backend.write_image(data, mime_type)?;
+                println!("Copied {} ({} bytes) to clipboard", mime_type, data.len());
+            }
+            ClipContent::File { path, .. } => {
+                backend.write_text(&path.display().to_string())?;
+                println!("Copied file path to clipboard: {}", path.display());
+            }
+        }
     }
 
+    // Count the copy for frecency ranking
+    history.record_use(clip_id);
+    history_storage.save(&history)?;
+
     Ok(())
 }
+
+/// Expand a snippet template for CLI use
+/// `{{input:...}}` values are prompted for on stderr and read from stdin
+fn expand_template_cli(text: &str) -> Result<String> {
+    use std::io::{BufRead, Write};
+
+    let template = Template::parse(text);
+    let mut context = TemplateContext::default();
+
+    if template.uses_clipboard() {
+        context.clipboard = create_backend().and_then(|b| b.read_text()).ok();
+    }
+
+    let stdin = io::stdin();
+    for label in template.input_labels() {
+        eprint!("{}: ", label);
+        io::stderr().flush()?;
+
+        let mut value = String::new();
+        stdin
+            .lock()
+            .read_line(&mut value)
+            .context("Failed to read template input")?;
+        let value = value.trim_end_matches(['\r', '\n']).to_string();
+        context.inputs.insert(label, value);
+    }
+
+    template.render(&context)
+}
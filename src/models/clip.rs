@@ -1,11 +1,13 @@
 use bincode::{Decode, Encode};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, hash_map::DefaultHasher};
+use std::collections::{HashMap, HashSet, hash_map::DefaultHasher};
 use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
+use super::phash;
+
 /// Content type for clipboard entries
 #[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
 pub enum ClipContent {
@@ -23,29 +25,7 @@ impl ClipContent {
         match self {
             ClipContent::Text(text) => {
                 let preview = text.lines().next().unwrap_or("");
-                let preview_width = preview.width();
-
-                if preview_width > max_len {
-                    // Need to truncate - reserve 3 chars for "..."
-                    let target_width = max_len.saturating_sub(3);
-
-                    // Find the character boundary that fits within target_width
-                    let mut current_width = 0;
-                    let mut byte_pos = 0;
-
-                    for (pos, ch) in preview.char_indices() {
-                        let ch_width = ch.width().unwrap_or(0);
-                        if current_width + ch_width > target_width {
-                            break;
-                        }
-                        current_width += ch_width;
-                        byte_pos = pos + ch.len_utf8();
-                    }
-
-                    format!("{}...", &preview[..byte_pos])
-                } else {
-                    preview.to_string()
-                }
+                truncate_to_width(preview, max_len, "...")
             }
             ClipContent::Image { mime_type, data } => {
                 format!("[Image: {} ({} bytes)]", mime_type, data.len())
@@ -77,6 +57,44 @@ impl ClipContent {
         matches!(self, ClipContent::File { .. })
     }
 
+    /// MIME type of the content (text is always text/plain)
+    pub fn mime_type(&self) -> &str {
+        match self {
+            ClipContent::Text(_) => "text/plain",
+            ClipContent::Image { mime_type, .. } => mime_type,
+            ClipContent::File { mime_type, .. } => mime_type,
+        }
+    }
+
+    /// Size of the content in bytes (file size on disk for file references, 0 if missing)
+    pub fn size_bytes(&self) -> u64 {
+        match self {
+            ClipContent::Text(text) => text.len() as u64,
+            ClipContent::Image { data, .. } => data.len() as u64,
+            ClipContent::File { path, .. } => std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        }
+    }
+
+    /// File extension to use when exporting this content
+    /// File references keep their original extension
+    pub fn file_extension(&self) -> String {
+        match self {
+            ClipContent::Text(_) => "txt".to_string(),
+            ClipContent::Image { mime_type, .. } => {
+                match mime_type.split_once('/').map(|(_, subtype)| subtype) {
+                    Some("jpeg") => "jpg".to_string(),
+                    Some("svg+xml") => "svg".to_string(),
+                    Some(subtype) if !subtype.is_empty() => subtype.to_string(),
+                    _ => "bin".to_string(),
+                }
+            }
+            ClipContent::File { path, .. } => path
+                .extension()
+                .map(|ext| ext.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "bin".to_string()),
+        }
+    }
+
     /// Get content hash for deduplication
     /// Note: File hash is based on path + mime_type, NOT file contents
     pub fn content_hash(&self) -> u64 {
@@ -94,6 +112,19 @@ impl ClipContent {
         }
         hasher.finish()
     }
+
+    /// Perceptual hash of image content (None for text, non-image files and undecodable data)
+    /// Unlike `content_hash`, re-encoded or rescaled copies of a picture hash alike
+    pub fn perceptual_hash(&self) -> Option<u64> {
+        match self {
+            ClipContent::Text(_) => None,
+            ClipContent::Image { data, .. } => phash::perceptual_hash(data),
+            ClipContent::File { path, mime_type } if mime_type.starts_with("image/") => {
+                phash::perceptual_hash(&std::fs::read(path).ok()?)
+            }
+            ClipContent::File { .. } => None,
+        }
+    }
 }
 
 /// A single clipboard entry with metadata
@@ -115,8 +146,17 @@ pub struct ClipEntry {
     pub temporary_registers: Vec<char>,
     /// Permanent registers assigned to this clip
     pub permanent_registers: Vec<char>,
+    /// Free-form tags (normalized: lowercase, no whitespace, no leading '#')
+    pub tags: Vec<String>,
+    /// Number of times this clip was selected, pasted or grabbed from a register
+    pub use_count: u32,
+    /// When this clip was last selected, pasted or grabbed
+    pub last_used: Option<SystemTime>,
     /// Content hash for deduplication
     pub content_hash: u64,
+    /// Perceptual hash of image content, for near-duplicate detection
+    /// (computed when image deduplication first needs it)
+    pub image_hash: Option<u64>,
 }
 
 impl ClipEntry {
@@ -133,7 +173,11 @@ impl ClipEntry {
             description: None,
             temporary_registers: Vec::new(),
             permanent_registers: Vec::new(),
+            tags: Vec::new(),
+            use_count: 0,
+            last_used: None,
             content_hash,
+            image_hash: None,
         }
     }
 
@@ -150,7 +194,11 @@ impl ClipEntry {
             description: None,
             temporary_registers: Vec::new(),
             permanent_registers: Vec::new(),
+            tags: Vec::new(),
+            use_count: 0,
+            last_used: None,
             content_hash,
+            image_hash: None,
         }
     }
 
@@ -167,7 +215,11 @@ impl ClipEntry {
             description: None,
             temporary_registers: Vec::new(),
             permanent_registers: Vec::new(),
+            tags: Vec::new(),
+            use_count: 0,
+            last_used: None,
             content_hash,
+            image_hash: None,
         }
     }
 
@@ -188,7 +240,11 @@ impl ClipEntry {
             description,
             temporary_registers: Vec::new(),
             permanent_registers: Vec::new(),
+            tags: Vec::new(),
+            use_count: 0,
+            last_used: None,
             content_hash,
+            image_hash: None,
         }
     }
 
@@ -212,6 +268,35 @@ impl ClipEntry {
         self.timestamp = SystemTime::now();
     }
 
+    /// Record that this clip was selected, pasted or grabbed
+    pub fn record_use(&mut self) {
+        self.use_count = self.use_count.saturating_add(1);
+        self.last_used = Some(SystemTime::now());
+    }
+
+    /// Frecency score: use count weighted by how recently the clip was used
+    /// Clips that were never used rank by how recently they were copied
+    pub fn frecency(&self, now: SystemTime) -> f64 {
+        let last_seen = self
+            .last_used
+            .map_or(self.timestamp, |t| t.max(self.timestamp));
+        let age_hours = now
+            .duration_since(last_seen)
+            .map(|d| d.as_secs() / 3600)
+            .unwrap_or(0);
+
+        let weight = match age_hours {
+            0..4 => 100.0,
+            4..24 => 70.0,
+            24..168 => 50.0,
+            168..720 => 30.0,
+            720..2160 => 10.0,
+            _ => 5.0,
+        };
+
+        weight * (self.use_count as f64 + 1.0)
+    }
+
     /// Check if file exists (for File variant)
     pub fn file_exists(&self) -> bool {
         match &self.content {
@@ -233,6 +318,12 @@ impl ClipEntry {
         self.pinned || !self.temporary_registers.is_empty() || !self.permanent_registers.is_empty()
     }
 
+    /// Check if this entry should survive rotation and clear-all
+    /// Tagged entries are only protected when `protect_tagged` is enabled
+    pub fn is_protected(&self, protect_tagged: bool) -> bool {
+        self.should_keep() || (protect_tagged && !self.tags.is_empty())
+    }
+
     /// Check if this entry can be deleted from TUI
     /// (Permanent register clips cannot be deleted)
     pub fn can_delete(&self) -> bool {
@@ -262,34 +353,248 @@ impl ClipEntry {
     pub fn remove_permanent_register(&mut self, key: char) {
         self.permanent_registers.retain(|&k| k != key);
     }
+
+    /// Check if this clip has the given tag (tag is normalized before comparing)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        normalize_tag(tag).is_some_and(|tag| self.tags.contains(&tag))
+    }
+
+    /// Add a tag to this clip
+    /// Returns false if the tag is invalid or already present
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        match normalize_tag(tag) {
+            Some(tag) if !self.tags.contains(&tag) => {
+                self.tags.push(tag);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Remove a tag from this clip
+    /// Returns true if the tag was present
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let Some(tag) = normalize_tag(tag) else {
+            return false;
+        };
+        let before = self.tags.len();
+        self.tags.retain(|t| t != &tag);
+        self.tags.len() != before
+    }
+}
+
+/// Truncate `text` to at most `max_width` columns, ending it with `ellipsis` if cut
+fn truncate_to_width(text: &str, max_width: usize, ellipsis: &str) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+
+    // Find the character boundary that fits next to the ellipsis
+    let target_width = max_width.saturating_sub(ellipsis.width());
+    let mut current_width = 0;
+    let mut byte_pos = 0;
+
+    for (pos, ch) in text.char_indices() {
+        let ch_width = ch.width().unwrap_or(0);
+        if current_width + ch_width > target_width {
+            break;
+        }
+        current_width += ch_width;
+        byte_pos = pos + ch.len_utf8();
+    }
+
+    format!("{}{}", &text[..byte_pos], ellipsis)
+}
+
+/// Human-readable byte size like "512 B" or "12 KiB" (rounded down)
+fn size_label(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes;
+    let mut unit = 0;
+    while size >= 1024 && unit < UNITS.len() - 1 {
+        size /= 1024;
+        unit += 1;
+    }
+    format!("{} {}", size, UNITS[unit])
+}
+
+/// Format an entry as an `id<TAB>preview` line for dmenu-style launchers
+/// Like cliphist, whitespace runs (including newlines and tabs) collapse to one
+/// space and images show as `[[ binary data <size> <ext> ]]`
+pub fn dmenu_line(entry: &ClipEntry, max_width: usize) -> String {
+    let preview = match &entry.content {
+        ClipContent::Text(text) => text.split_whitespace().collect::<Vec<_>>().join(" "),
+        ClipContent::Image { .. } => format!(
+            "[[ binary data {} {} ]]",
+            size_label(entry.content.size_bytes()),
+            entry.content.file_extension()
+        ),
+        ClipContent::File { path, .. } => {
+            let path = path.display().to_string();
+            format!(
+                "[[ file {} ]]",
+                path.split_whitespace().collect::<Vec<_>>().join(" ")
+            )
+        }
+    };
+    format!(
+        "{}\t{}",
+        entry.id,
+        truncate_to_width(&preview, max_width, "…")
+    )
+}
+
+/// Parse the clip ID from a line printed by `dmenu_line`
+/// Accepts a bare ID too, so launchers that return only the first column work
+pub fn parse_dmenu_line(line: &str) -> Option<u64> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let id = line.split_once('\t').map_or(line, |(id, _)| id);
+    id.trim().parse().ok()
+}
+
+/// Normalize a user-supplied tag
+/// Strips a leading '#', trims and lowercases. Returns None for empty tags
+/// or tags containing whitespace.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix('#').unwrap_or(tag);
+    if tag.is_empty() || tag.chars().any(char::is_whitespace) {
+        return None;
+    }
+    Some(tag.to_lowercase())
+}
+
+/// Default maximum number of clips kept in the trash
+pub const DEFAULT_MAX_TRASH: usize = 200;
+
+/// Default cap on the text and image bytes kept in the trash
+/// Trashed clips are saved with the history file, which is rewritten on every copy
+pub const DEFAULT_MAX_TRASH_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Default number of days deleted clips stay in the trash
+pub const DEFAULT_TRASH_RETENTION_DAYS: u64 = 30;
+
+/// A clip that was deleted or rotated out of history
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct TrashedClip {
+    /// The entry as it was when removed
+    pub entry: ClipEntry,
+    /// When the entry was moved to the trash
+    pub deleted_at: SystemTime,
 }
 
 /// Clipboard history manager
 /// Entries are kept in timestamp order (most recent first)
-#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClipboardHistory {
     /// All clipboard entries (sorted by timestamp, most recent first)
     pub entries: Vec<ClipEntry>,
     /// Maximum number of entries to keep (excludes pinned and registered)
     pub max_entries: usize,
+    /// Whether tagged entries are exempt from rotation and clear-all
+    /// (set from config on load, not persisted)
+    #[serde(skip)]
+    pub protect_tagged: bool,
+    /// Deleted and rotated-out entries (most recently deleted first)
+    pub trash: Vec<TrashedClip>,
+    /// Maximum number of trashed entries to keep (0 disables the trash)
+    /// (set from config on load, like `protect_tagged`)
+    #[serde(skip)]
+    pub max_trash: usize,
+    /// Maximum text and image bytes kept in the trash (file clips count as 0)
+    /// (set from config on load, like `protect_tagged`)
+    #[serde(skip)]
+    pub max_trash_bytes: u64,
+    /// Days a trashed entry is kept before being purged
+    /// (set from config on load, like `protect_tagged`)
+    #[serde(skip)]
+    pub trash_retention_days: u64,
+    /// Perceptual hash bits that may differ for a new image to count as a duplicate
+    /// of an existing one (None disables near-duplicate detection; set from config on load)
+    #[serde(skip)]
+    pub image_dedup_threshold: Option<u32>,
+    /// Image entries whose perceptual hash couldn't be computed, so undecodable data
+    /// isn't decoded again on every lookup (not persisted)
+    #[serde(skip)]
+    unhashable_images: HashSet<u64>,
     /// Next ID to assign (monotonic counter)
     next_id: u64,
+    /// Bumped by every method that may change entries or the trash (not persisted)
+    #[serde(skip)]
+    revision: u64,
     /// HashMap for fast duplicate detection: content_hash -> entry_id
     #[serde(skip)]
     hash_to_id: HashMap<u64, u64>,
 }
 
+// Persisted by hand so settings that come from config and the hash index (rebuilt
+// on load) stay out of the history file
+impl Encode for ClipboardHistory {
+    fn encode<E: bincode::enc::Encoder>(
+        &self,
+        encoder: &mut E,
+    ) -> Result<(), bincode::error::EncodeError> {
+        self.entries.encode(encoder)?;
+        self.max_entries.encode(encoder)?;
+        self.trash.encode(encoder)?;
+        self.next_id.encode(encoder)
+    }
+}
+
+impl<Context> Decode<Context> for ClipboardHistory {
+    fn decode<D: bincode::de::Decoder<Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, bincode::error::DecodeError> {
+        let entries = Vec::decode(decoder)?;
+        let max_entries = usize::decode(decoder)?;
+        let trash = Vec::decode(decoder)?;
+        let next_id = u64::decode(decoder)?;
+
+        let mut history = ClipboardHistory::from_parts(entries, max_entries, next_id);
+        history.trash = trash;
+        Ok(history)
+    }
+}
+
 impl ClipboardHistory {
     /// Create a new clipboard history with specified max entries
     pub fn new(max_entries: usize) -> Self {
         ClipboardHistory {
             entries: Vec::new(),
             max_entries,
+            protect_tagged: false,
+            trash: Vec::new(),
+            max_trash: DEFAULT_MAX_TRASH,
+            max_trash_bytes: DEFAULT_MAX_TRASH_BYTES,
+            trash_retention_days: DEFAULT_TRASH_RETENTION_DAYS,
+            image_dedup_threshold: None,
+            unhashable_images: HashSet::new(),
             next_id: 1,
+            revision: 0,
             hash_to_id: HashMap::new(),
         }
     }
 
+    /// Reassemble a history from its persisted parts (used by format migrations)
+    pub fn from_parts(entries: Vec<ClipEntry>, max_entries: usize, next_id: u64) -> Self {
+        let mut history = ClipboardHistory {
+            entries,
+            max_entries,
+            protect_tagged: false,
+            trash: Vec::new(),
+            max_trash: DEFAULT_MAX_TRASH,
+            max_trash_bytes: DEFAULT_MAX_TRASH_BYTES,
+            trash_retention_days: DEFAULT_TRASH_RETENTION_DAYS,
+            image_dedup_threshold: None,
+            unhashable_images: HashSet::new(),
+            next_id,
+            revision: 0,
+            hash_to_id: HashMap::new(),
+        };
+        history.rebuild_hash_map();
+        history
+    }
+
     /// Rebuild the hash_to_id map (called after deserialization)
     pub fn rebuild_hash_map(&mut self) {
         self.hash_to_id.clear();
@@ -302,6 +607,7 @@ impl ClipboardHistory {
     /// If content is duplicate, updates timestamp and moves to front
     /// Returns the ID of the entry (existing or new)
     pub fn add_entry(&mut self, content: ClipContent) -> u64 {
+        self.revision += 1;
         let content_hash = content.content_hash();
 
         // Check if this content already exists
@@ -310,14 +616,20 @@ impl ClipboardHistory {
                 "Duplicate detected, bumping timestamp for entry {}",
                 existing_id
             );
+            self.bump_to_front(existing_id);
+            return existing_id;
+        }
 
-            // Find the existing entry, update timestamp, and move to front
-            if let Some(pos) = self.entries.iter().position(|e| e.id == existing_id) {
-                let mut entry = self.entries.remove(pos);
-                entry.bump_timestamp();
-                self.entries.insert(0, entry);
-            }
-
+        // Near-duplicate images (re-encoded, rescaled or copied from another app)
+        let image_hash = self
+            .image_dedup_threshold
+            .and_then(|_| content.perceptual_hash());
+        if let Some(existing_id) = image_hash.and_then(|hash| self.find_similar_image(hash)) {
+            log::debug!(
+                "Similar image detected, bumping timestamp for entry {}",
+                existing_id
+            );
+            self.bump_to_front(existing_id);
             return existing_id;
         }
 
@@ -325,11 +637,18 @@ impl ClipboardHistory {
         let id = self.next_id;
         self.next_id += 1;
 
-        let entry = match content {
+        let mut entry = match content {
             ClipContent::Text(text) => ClipEntry::new_text(id, text),
             ClipContent::Image { data, mime_type } => ClipEntry::new_image(id, data, mime_type),
             ClipContent::File { path, mime_type } => ClipEntry::new_file(id, path, mime_type),
         };
+        entry.image_hash = image_hash;
+        if self.image_dedup_threshold.is_some()
+            && image_hash.is_none()
+            && entry.content.mime_type().starts_with("image/")
+        {
+            self.unhashable_images.insert(id);
+        }
 
         // Add to hash map
         self.hash_to_id.insert(content_hash, id);
@@ -343,6 +662,44 @@ impl ClipboardHistory {
         id
     }
 
+    /// Update an entry's timestamp and move it to the front
+    fn bump_to_front(&mut self, id: u64) {
+        if let Some(pos) = self.entries.iter().position(|e| e.id == id) {
+            let mut entry = self.entries.remove(pos);
+            entry.bump_timestamp();
+            self.entries.insert(0, entry);
+        }
+    }
+
+    /// Find the image entry closest to a perceptual hash, within the dedup threshold
+    /// Hashes of older entries are computed on the first lookup and kept with the entry
+    /// (failures are remembered for the session)
+    fn find_similar_image(&mut self, hash: u64) -> Option<u64> {
+        let threshold = self.image_dedup_threshold?;
+        let mut closest = None;
+        for entry in &mut self.entries {
+            if entry.image_hash.is_none()
+                && entry.content.mime_type().starts_with("image/")
+                && !self.unhashable_images.contains(&entry.id)
+            {
+                entry.image_hash = entry.content.perceptual_hash();
+                if entry.image_hash.is_none() {
+                    self.unhashable_images.insert(entry.id);
+                }
+            }
+            let Some(distance) = entry
+                .image_hash
+                .map(|other| phash::hash_distance(hash, other))
+            else {
+                continue;
+            };
+            if distance <= threshold && closest.is_none_or(|(_, best)| distance < best) {
+                closest = Some((entry.id, distance));
+            }
+        }
+        closest.map(|(id, _)| id)
+    }
+
     /// Add a new entry with metadata (for permanent registers)
     pub fn add_entry_with_metadata(
         &mut self,
@@ -350,6 +707,7 @@ impl ClipboardHistory {
         name: Option<String>,
         description: Option<String>,
     ) -> u64 {
+        self.revision += 1;
         let content_hash = content.content_hash();
 
         // Check if this content already exists
@@ -396,6 +754,7 @@ impl ClipboardHistory {
 
     /// Remove an entry by ID
     pub fn remove_entry(&mut self, id: u64) -> bool {
+        self.revision += 1;
         if let Some(pos) = self.entries.iter().position(|e| e.id == id) {
             let entry = self.entries.remove(pos);
             self.hash_to_id.remove(&entry.content_hash);
@@ -405,6 +764,103 @@ impl ClipboardHistory {
         }
     }
 
+    /// Move an entry to the trash
+    pub fn trash_entry(&mut self, id: u64) -> bool {
+        self.revision += 1;
+        let Some(pos) = self.entries.iter().position(|e| e.id == id) else {
+            return false;
+        };
+        let entry = self.entries.remove(pos);
+        self.hash_to_id.remove(&entry.content_hash);
+        self.push_trash(vec![entry]);
+        true
+    }
+
+    /// Add removed entries to the front of the trash, dropping the oldest beyond the limits
+    fn push_trash(&mut self, entries: Vec<ClipEntry>) {
+        let deleted_at = SystemTime::now();
+        let trashed: Vec<TrashedClip> = entries
+            .into_iter()
+            .map(|entry| TrashedClip { entry, deleted_at })
+            .collect();
+        self.trash.splice(0..0, trashed);
+        self.truncate_trash();
+    }
+
+    /// Drop the oldest trashed entries beyond `max_trash` or `max_trash_bytes`
+    fn truncate_trash(&mut self) {
+        self.trash.truncate(self.max_trash);
+
+        let mut total: u64 = 0;
+        let keep = self
+            .trash
+            .iter()
+            .take_while(|t| {
+                // File clips only reference a file, so they don't grow the history file
+                total += match &t.entry.content {
+                    ClipContent::File { .. } => 0,
+                    content => content.size_bytes(),
+                };
+                total <= self.max_trash_bytes
+            })
+            .count();
+        self.trash.truncate(keep);
+    }
+
+    /// Get a trashed entry by ID
+    pub fn get_trashed(&self, id: u64) -> Option<&ClipEntry> {
+        self.trash.iter().map(|t| &t.entry).find(|e| e.id == id)
+    }
+
+    /// Permanently remove an entry from the trash
+    pub fn remove_from_trash(&mut self, id: u64) -> Option<ClipEntry> {
+        self.revision += 1;
+        let pos = self.trash.iter().position(|t| t.entry.id == id)?;
+        Some(self.trash.remove(pos).entry)
+    }
+
+    /// Move an entry from the trash back into history, in timestamp order
+    /// Returns the ID of the restored entry (an existing one if the content is already in history)
+    pub fn restore_from_trash(&mut self, id: u64) -> anyhow::Result<u64> {
+        let entry = self
+            .remove_from_trash(id)
+            .ok_or_else(|| anyhow::anyhow!("Clip {} not found in trash", id))?;
+
+        if let Some(existing_id) = self.find_by_hash(entry.content_hash) {
+            return Ok(existing_id);
+        }
+
+        let pos = self
+            .entries
+            .iter()
+            .position(|e| e.timestamp < entry.timestamp)
+            .unwrap_or(self.entries.len());
+        self.insert_entry(pos, entry);
+        Ok(id)
+    }
+
+    /// Drop trashed entries older than the retention period, or beyond the trash limits
+    pub fn purge_trash(&mut self) {
+        self.revision += 1;
+        let retention = Duration::from_secs(self.trash_retention_days * 24 * 60 * 60);
+        let now = SystemTime::now();
+        self.trash.retain(|t| {
+            now.duration_since(t.deleted_at)
+                .map(|age| age <= retention)
+                .unwrap_or(true)
+        });
+        self.truncate_trash();
+    }
+
+    /// Insert an existing entry at a position (clamped to the end), e.g. when undoing a delete
+    pub fn insert_entry(&mut self, index: usize, entry: ClipEntry) {
+        self.revision += 1;
+        self.remove_from_trash(entry.id);
+        self.hash_to_id.insert(entry.content_hash, entry.id);
+        let index = index.min(self.entries.len());
+        self.entries.insert(index, entry);
+    }
+
     /// Get all pinned entries
     pub fn get_pinned(&self) -> Vec<&ClipEntry> {
         self.entries.iter().filter(|e| e.pinned).collect()
@@ -417,9 +873,22 @@ impl ClipboardHistory {
 
     /// Get mutable entry by ID
     pub fn get_entry_mut(&mut self, id: u64) -> Option<&mut ClipEntry> {
+        self.revision += 1;
         self.entries.iter_mut().find(|e| e.id == id)
     }
 
+    /// Record a use of the entry (for frecency ranking)
+    /// Returns false if the entry doesn't exist
+    pub fn record_use(&mut self, id: u64) -> bool {
+        match self.get_entry_mut(id) {
+            Some(entry) => {
+                entry.record_use();
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Toggle pin status of entry by ID
     pub fn toggle_pin(&mut self, id: u64) -> anyhow::Result<()> {
         let entry = self
@@ -430,54 +899,64 @@ impl ClipboardHistory {
     }
 
     /// Clear all non-pinned, non-registered entries
+    /// Tagged entries are also kept when `protect_tagged` is enabled
+    /// Cleared entries are moved to the trash
     pub fn clear_unpinned(&mut self) {
-        self.entries.retain(|e| {
-            if e.should_keep() {
-                true
-            } else {
-                self.hash_to_id.remove(&e.content_hash);
-                false
-            }
-        });
+        self.revision += 1;
+        let protect_tagged = self.protect_tagged;
+        let (kept, removed): (Vec<ClipEntry>, Vec<ClipEntry>) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|e| e.is_protected(protect_tagged));
+
+        self.entries = kept;
+        for entry in &removed {
+            self.hash_to_id.remove(&entry.content_hash);
+        }
+        self.push_trash(removed);
+    }
+
+    /// IDs of the entries that adding one new clip would rotate into the trash
+    /// (for undo snapshots taken before the clip is added)
+    pub fn rotation_candidates(&self) -> Vec<u64> {
+        self.entries
+            .iter()
+            .filter(|e| !e.is_protected(self.protect_tagged))
+            .skip(self.max_entries.saturating_sub(1))
+            .map(|e| e.id)
+            .collect()
     }
 
     /// Rotate history to enforce max_entries limit
     /// Pinned entries and entries with registers are exempt from rotation
+    /// (and tagged entries when `protect_tagged` is enabled).
+    /// The oldest unprotected entries are moved to the trash first.
     fn rotate_history(&mut self) {
-        let protected_count = self.entries.iter().filter(|e| e.should_keep()).count();
-        let unprotected_count = self.entries.len() - protected_count;
-
-        if unprotected_count > self.max_entries {
-            let to_remove = unprotected_count - self.max_entries;
-
-            self.entries.retain(|e| {
-                if e.should_keep() {
-                    true
-                } else {
-                    static mut REMOVED: usize = 0;
-                    unsafe {
-                        if REMOVED < to_remove {
-                            REMOVED += 1;
-                            self.hash_to_id.remove(&e.content_hash);
-                            false
-                        } else {
-                            true
-                        }
-                    }
+        let protect_tagged = self.protect_tagged;
+        let max_entries = self.max_entries;
+
+        // Entries are most recent first, so keep the first max_entries unprotected
+        let mut unprotected_seen = 0;
+        let (kept, removed): (Vec<ClipEntry>, Vec<ClipEntry>) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|e| {
+                if e.is_protected(protect_tagged) {
+                    return true;
                 }
+                unprotected_seen += 1;
+                unprotected_seen <= max_entries
             });
 
-            // Reset counter (unsafe but constrained to this function)
-            unsafe {
-                static mut REMOVED: usize = 0;
-                REMOVED = 0;
-            }
+        self.entries = kept;
+        for entry in &removed {
+            self.hash_to_id.remove(&entry.content_hash);
         }
+        self.push_trash(removed);
     }
 
     /// Sort entries by timestamp (most recent first)
     pub fn sort_by_timestamp(&mut self) {
-        self.entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        self.revision += 1;
+        self.entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
     }
 
     /// Get next available ID
@@ -485,6 +964,12 @@ impl ClipboardHistory {
         self.next_id
     }
 
+    /// Counter that changes whenever entries or the trash may have changed
+    /// (through methods; direct writes to `entries` or `trash` don't count)
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
     /// Get the number of entries in the history
     pub fn len(&self) -> usize {
         self.entries.len()
@@ -500,6 +985,18 @@ impl ClipboardHistory {
         &self.entries
     }
 
+    /// Get all distinct tags across entries (sorted)
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .entries
+            .iter()
+            .flat_map(|e| e.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
     /// Find an entry by content hash
     pub fn find_by_hash(&self, content_hash: u64) -> Option<u64> {
         self.hash_to_id.get(&content_hash).copied()
@@ -529,6 +1026,38 @@ mod tests {
         assert!(file.preview(50).contains("test.png"));
     }
 
+    #[test]
+    fn test_dmenu_line() {
+        let text = ClipEntry::new_text(7, "first line\n\tsecond  line\r\n".to_string());
+        assert_eq!(dmenu_line(&text, 100), "7\tfirst line second line");
+        assert_eq!(dmenu_line(&text, 10), "7\tfirst lin…");
+
+        let image = ClipEntry::new_image(8, vec![0; 3 * 1024 + 1], "image/jpeg".to_string());
+        assert_eq!(dmenu_line(&image, 100), "8\t[[ binary data 3 KiB jpg ]]");
+
+        assert_eq!(parse_dmenu_line(&dmenu_line(&text, 100)), Some(7));
+        assert_eq!(parse_dmenu_line("42\n"), Some(42));
+        assert_eq!(parse_dmenu_line("\tno id"), None);
+        assert_eq!(parse_dmenu_line("abc\tpreview"), None);
+    }
+
+    #[test]
+    fn test_file_extension() {
+        assert_eq!(ClipContent::Text("hi".to_string()).file_extension(), "txt");
+
+        let jpeg = ClipContent::Image {
+            data: vec![],
+            mime_type: "image/jpeg".to_string(),
+        };
+        assert_eq!(jpeg.file_extension(), "jpg");
+
+        let file = ClipContent::File {
+            path: PathBuf::from("/tmp/report.pdf"),
+            mime_type: "application/pdf".to_string(),
+        };
+        assert_eq!(file.file_extension(), "pdf");
+    }
+
     #[test]
     fn test_file_hash_stable() {
         let file1 = ClipContent::File {
@@ -588,6 +1117,66 @@ mod tests {
         assert_eq!(history.entries.len(), 2);
     }
 
+    #[test]
+    fn test_similar_image_bumps_existing() {
+        use image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+        use std::io::Cursor;
+
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(32, 32, |x, y| {
+            Rgb([(x * 8) as u8, (y * 8) as u8, 128])
+        }));
+        let encode = |img: &DynamicImage, format: ImageFormat, mime: &str| {
+            let mut out = Cursor::new(Vec::new());
+            img.write_to(&mut out, format).unwrap();
+            ClipContent::Image {
+                data: out.into_inner(),
+                mime_type: mime.to_string(),
+            }
+        };
+        let png = encode(&img, ImageFormat::Png, "image/png");
+        let bmp = encode(&img, ImageFormat::Bmp, "image/bmp");
+        let other = encode(&img.fliph(), ImageFormat::Png, "image/png");
+
+        // Byte-level dedup only without a threshold
+        let mut history = ClipboardHistory::new(10);
+        history.add_entry(png.clone());
+        history.add_entry(bmp.clone());
+        assert_eq!(history.entries.len(), 2);
+
+        let mut history = ClipboardHistory::new(10);
+        history.image_dedup_threshold = Some(4);
+        let id = history.add_entry(png);
+        history.add_entry(ClipContent::Text("between".to_string()));
+        assert_eq!(history.add_entry(bmp), id);
+        assert_eq!(history.entries[0].id, id);
+        assert_eq!(history.entries.len(), 2);
+
+        assert_ne!(history.add_entry(other), id);
+        assert_eq!(history.entries.len(), 3);
+    }
+
+    #[test]
+    fn test_undecodable_image_hashed_once() {
+        let broken = |byte: u8| ClipContent::Image {
+            data: vec![byte; 16],
+            mime_type: "image/png".to_string(),
+        };
+
+        // Added before dedup was enabled, so hashed on the first lookup
+        let mut history = ClipboardHistory::new(10);
+        let old = history.add_entry(broken(1));
+        history.image_dedup_threshold = Some(4);
+        assert!(history.find_similar_image(0).is_none());
+        assert!(history.unhashable_images.contains(&old));
+
+        // Added with dedup enabled: the failed hash is remembered straight away
+        let new = history.add_entry(broken(2));
+        assert_ne!(new, old);
+        assert!(history.unhashable_images.contains(&new));
+        assert!(history.get_entry(new).unwrap().image_hash.is_none());
+        assert!(history.find_similar_image(0).is_none());
+    }
+
     #[test]
     fn test_registered_entries_exempt_from_rotation() {
         let mut history = ClipboardHistory::new(2);
@@ -616,5 +1205,139 @@ mod tests {
         // Registered entries should still be present
         assert!(history.get_entry(id1).is_some());
         assert!(history.get_entry(id2).is_some());
+
+        // The oldest unregistered entry should have been rotated out
+        let texts: Vec<String> = history.entries.iter().map(|e| e.preview(50)).collect();
+        assert!(!texts.contains(&"Entry 3".to_string()));
+        assert!(texts.contains(&"Entry 5".to_string()));
+    }
+
+    #[test]
+    fn test_clip_entry_tags() {
+        let mut entry = ClipEntry::new_text(1, "SELECT 1".to_string());
+
+        assert!(entry.add_tag("#SQL"));
+        assert!(!entry.add_tag("sql"));
+        assert!(!entry.add_tag("two words"));
+        assert!(!entry.add_tag("#"));
+        assert_eq!(entry.tags, vec!["sql".to_string()]);
+        assert!(entry.has_tag("Sql"));
+
+        assert!(entry.remove_tag("#sql"));
+        assert!(!entry.remove_tag("sql"));
+        assert!(entry.tags.is_empty());
+    }
+
+    #[test]
+    fn test_tagged_entries_optionally_protected() {
+        let mut history = ClipboardHistory::new(1);
+
+        let id1 = history.add_entry(ClipContent::Text("tagged".to_string()));
+        history.get_entry_mut(id1).unwrap().add_tag("keep");
+        history.add_entry(ClipContent::Text("other".to_string()));
+
+        // Without protection, the tagged entry rotates out like any other
+        assert!(history.get_entry(id1).is_none());
+
+        let mut history = ClipboardHistory::new(1);
+        history.protect_tagged = true;
+
+        let id1 = history.add_entry(ClipContent::Text("tagged".to_string()));
+        history.get_entry_mut(id1).unwrap().add_tag("keep");
+        history.add_entry(ClipContent::Text("other".to_string()));
+        history.add_entry(ClipContent::Text("newest".to_string()));
+
+        assert!(history.get_entry(id1).is_some());
+        assert_eq!(history.len(), 2);
+
+        history.clear_unpinned();
+        assert_eq!(history.len(), 1);
+        assert!(history.get_entry(id1).is_some());
+    }
+
+    #[test]
+    fn test_trash_and_restore() {
+        let mut history = ClipboardHistory::new(2);
+        let id1 = history.add_entry(ClipContent::Text("first".to_string()));
+        let id2 = history.add_entry(ClipContent::Text("second".to_string()));
+        history.add_entry(ClipContent::Text("third".to_string()));
+
+        // Rotated-out entry goes to the trash
+        assert!(history.get_entry(id1).is_none());
+        assert!(history.get_trashed(id1).is_some());
+
+        assert!(history.trash_entry(id2));
+        assert_eq!(history.trash.len(), 2);
+        assert_eq!(history.trash[0].entry.id, id2);
+
+        // Restored entries go back in timestamp order and leave the trash
+        assert_eq!(history.restore_from_trash(id2).unwrap(), id2);
+        assert_eq!(history.entries()[1].id, id2);
+        assert!(history.get_trashed(id2).is_none());
+        assert!(
+            history
+                .find_by_hash(history.get_entry(id2).unwrap().content_hash)
+                .is_some()
+        );
+        assert!(history.restore_from_trash(id2).is_err());
+    }
+
+    #[test]
+    fn test_trash_bounded_and_purged() {
+        let mut history = ClipboardHistory::new(10);
+        history.max_trash = 2;
+        for text in ["a", "b", "c"] {
+            let id = history.add_entry(ClipContent::Text(text.to_string()));
+            history.trash_entry(id);
+        }
+        assert_eq!(history.trash.len(), 2);
+        assert_eq!(history.trash[0].entry.preview(10), "c");
+
+        history.trash[1].deleted_at = SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60);
+        history.trash_retention_days = 1;
+        history.purge_trash();
+        assert_eq!(history.trash.len(), 1);
+    }
+
+    #[test]
+    fn test_trash_bounded_by_bytes() {
+        let mut history = ClipboardHistory::new(10);
+        history.max_trash_bytes = 250;
+        for byte in 1..=3u8 {
+            let id = history.add_entry(ClipContent::Image {
+                data: vec![byte; 100],
+                mime_type: "image/png".to_string(),
+            });
+            history.trash_entry(id);
+        }
+        let id = history.add_entry(ClipContent::File {
+            path: PathBuf::from("/tmp/large.png"),
+            mime_type: "image/png".to_string(),
+        });
+        history.trash_entry(id);
+
+        // The file clip and the two newest images fit, the oldest image is dropped
+        assert_eq!(history.trash.len(), 3);
+        assert!(history.trash[0].entry.content.is_file());
+    }
+
+    #[test]
+    fn test_frecency() {
+        let now = SystemTime::now();
+        let mut fresh = ClipEntry::new_text(1, "fresh".to_string());
+        let mut snippet = ClipEntry::new_text(2, "snippet".to_string());
+        snippet.timestamp = now - Duration::from_secs(30 * 24 * 60 * 60);
+        assert!(fresh.frecency(now) > snippet.frecency(now));
+
+        // Frequent use from a few days ago beats a single fresh copy
+        for _ in 0..5 {
+            snippet.record_use();
+        }
+        snippet.last_used = Some(now - Duration::from_secs(3 * 24 * 60 * 60));
+        assert_eq!(snippet.use_count, 5);
+        assert!(snippet.frecency(now) > fresh.frecency(now));
+
+        fresh.record_use();
+        assert!(fresh.last_used.is_some());
     }
 }
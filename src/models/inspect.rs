@@ -0,0 +1,210 @@
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Bytes shown in the hex view; larger data is truncated
+pub const MAX_HEX_BYTES: usize = 64 * 1024;
+
+/// Bytes per hex dump row
+pub const HEX_ROW_BYTES: usize = 16;
+
+/// Leading bytes of a file, for the hex view
+#[derive(Debug, Clone)]
+pub struct FileHead {
+    pub data: Vec<u8>,
+    /// Total size of the file in bytes
+    pub size: u64,
+}
+
+impl FileHead {
+    /// Read up to MAX_HEX_BYTES from the start of a file
+    pub fn read(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+        let size = file.metadata()?.len();
+        let mut data = Vec::with_capacity((size as usize).min(MAX_HEX_BYTES));
+        file.take(MAX_HEX_BYTES as u64)
+            .read_to_end(&mut data)
+            .with_context(|| format!("Failed to read {:?}", path))?;
+        Ok(Self { data, size })
+    }
+}
+
+/// Check whether a MIME type is opaque binary data (not text, image or structured text)
+pub fn is_binary_mime(mime_type: &str) -> bool {
+    let mime_type = mime_type.to_lowercase();
+    !(mime_type.starts_with("text/")
+        || mime_type.starts_with("image/")
+        || mime_type.ends_with("json")
+        || mime_type.ends_with("xml"))
+}
+
+/// Format one hex dump row as offset, hex bytes (two groups of 8) and ASCII
+/// e.g. `00000010`, `48 65 6c 6c 6f 0a 00 ...`, `Hello...`
+pub fn hex_row(offset: usize, chunk: &[u8]) -> (String, String, String) {
+    let mut hex = String::with_capacity(HEX_ROW_BYTES * 3 + 1);
+    for i in 0..HEX_ROW_BYTES {
+        if i == HEX_ROW_BYTES / 2 {
+            hex.push(' ');
+        }
+        match chunk.get(i) {
+            Some(byte) => hex.push_str(&format!("{:02x} ", byte)),
+            None => hex.push_str("   "),
+        }
+    }
+    hex.pop();
+
+    let ascii = chunk
+        .iter()
+        .map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        })
+        .collect();
+
+    (format!("{:08x}", offset), hex, ascii)
+}
+
+/// How an invisible character is shown when invisibles are visible
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Invisible {
+    /// Harmless whitespace (tabs, carriage returns at line ends)
+    Whitespace(&'static str),
+    /// Characters that change what a paste does (zero-width, bidi, control, NBSP)
+    Hazard(&'static str),
+}
+
+/// Visible stand-in for an invisible or confusable character
+pub fn invisible(c: char) -> Option<Invisible> {
+    const CONTROL_PICTURES: &[&str] = &[
+        "␀", "␁", "␂", "␃", "␄", "␅", "␆", "␇", "␈", "␉", "␊", "␋", "␌", "␍", "␎", "␏", "␐", "␑",
+        "␒", "␓", "␔", "␕", "␖", "␗", "␘", "␙", "␚", "␛", "␜", "␝", "␞", "␟",
+    ];
+
+    let shown = match c {
+        '\t' => Invisible::Whitespace("→   "),
+        '\r' => Invisible::Whitespace("␍"),
+        '\u{00a0}' => Invisible::Hazard("⍽"),
+        '\u{200b}' => Invisible::Hazard("<ZWSP>"),
+        '\u{200c}' => Invisible::Hazard("<ZWNJ>"),
+        '\u{200d}' => Invisible::Hazard("<ZWJ>"),
+        '\u{2060}' => Invisible::Hazard("<WJ>"),
+        '\u{feff}' => Invisible::Hazard("<BOM>"),
+        '\u{00ad}' => Invisible::Hazard("<SHY>"),
+        '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}' => Invisible::Hazard("<BIDI>"),
+        '\u{7f}' => Invisible::Hazard("␡"),
+        c if (c as u32) < 0x20 && c != '\n' => Invisible::Hazard(CONTROL_PICTURES[c as usize]),
+        _ => return None,
+    };
+    Some(shown)
+}
+
+/// Invisible characters found in a text clip, summarized in the preview metadata
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Invisibles {
+    pub crlf: bool,
+    pub tabs: bool,
+    pub trailing_whitespace: bool,
+    pub zero_width: bool,
+    pub bidi: bool,
+    pub control: bool,
+    pub nbsp: bool,
+}
+
+impl Invisibles {
+    /// Scan text for invisible characters
+    pub fn scan(text: &str) -> Self {
+        let mut found = Invisibles::default();
+
+        for line in text.split('\n') {
+            found.crlf |= line.ends_with('\r');
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            found.trailing_whitespace |= line.ends_with([' ', '\t']);
+
+            for c in line.chars() {
+                match c {
+                    '\t' => found.tabs = true,
+                    '\u{00a0}' => found.nbsp = true,
+                    '\u{200b}'..='\u{200d}' | '\u{2060}' | '\u{feff}' | '\u{00ad}' => {
+                        found.zero_width = true
+                    }
+                    '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}' => found.bidi = true,
+                    c if c.is_control() => found.control = true,
+                    _ => {}
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Short descriptions of what was found, most dangerous first
+    pub fn summary(&self) -> Vec<&'static str> {
+        [
+            (self.bidi, "bidi controls"),
+            (self.control, "control chars"),
+            (self.zero_width, "zero-width"),
+            (self.nbsp, "nbsp"),
+            (self.crlf, "CRLF"),
+            (self.trailing_whitespace, "trailing space"),
+            (self.tabs, "tabs"),
+        ]
+        .into_iter()
+        .filter_map(|(found, label)| found.then_some(label))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_row() {
+        let (offset, hex, ascii) = hex_row(32, b"Hello\n\x00world!!");
+        assert_eq!(offset, "00000020");
+        // Short rows are padded so the ASCII column lines up
+        assert_eq!(hex.trim_end(), "48 65 6c 6c 6f 0a 00 77  6f 72 6c 64 21 21");
+        assert_eq!(hex.len(), HEX_ROW_BYTES * 3);
+        assert_eq!(ascii, "Hello..world!!");
+
+        let (_, hex, _) = hex_row(0, &[0xff]);
+        assert_eq!(hex.len(), HEX_ROW_BYTES * 3);
+        assert!(hex.starts_with("ff "));
+    }
+
+    #[test]
+    fn test_invisibles() {
+        assert_eq!(Invisibles::scan("plain text\nok"), Invisibles::default());
+        assert!(Invisibles::scan("plain text\nok").summary().is_empty());
+
+        let found = Invisibles::scan("echo\u{200b} hi \r\n\tx\u{1b}[0m");
+        assert_eq!(
+            found.summary(),
+            vec![
+                "control chars",
+                "zero-width",
+                "CRLF",
+                "trailing space",
+                "tabs"
+            ]
+        );
+        assert!(Invisibles::scan("x  \ny").trailing_whitespace);
+
+        assert_eq!(invisible('\u{1b}'), Some(Invisible::Hazard("␛")));
+        assert_eq!(invisible('\r'), Some(Invisible::Whitespace("␍")));
+        assert_eq!(invisible('a'), None);
+    }
+
+    #[test]
+    fn test_binary_mime() {
+        assert!(is_binary_mime("application/octet-stream"));
+        assert!(is_binary_mime("application/zip"));
+        assert!(!is_binary_mime("text/plain"));
+        assert!(!is_binary_mime("application/json"));
+        assert!(!is_binary_mime("image/png"));
+    }
+}
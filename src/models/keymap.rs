@@ -0,0 +1,832 @@
+use anyhow::Result;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::storage::config::KeyBindingValue;
+
+/// A key with modifiers, like `Ctrl-d`, `Enter` or `J`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// Build a chord, normalised the way key events are matched
+    /// Characters carry Shift themselves (`J`, `?`), Ctrl letters are lowercase and
+    /// Shift-Tab is `BackTab` (as terminals report it, with or without Shift)
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Char(c) => {
+                let shift = modifiers.contains(KeyModifiers::SHIFT);
+                modifiers.remove(KeyModifiers::SHIFT);
+                if modifiers.contains(KeyModifiers::CONTROL) {
+                    KeyCode::Char(c.to_ascii_lowercase())
+                } else if shift {
+                    KeyCode::Char(c.to_ascii_uppercase())
+                } else {
+                    KeyCode::Char(c)
+                }
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Parse a chord like `Ctrl-d`, `Alt-t`, `Shift-Tab`, `PgDn`, `Space` or `?`
+    pub fn parse(chord: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = chord;
+        // Stop before a trailing `-` so `-` and `Ctrl--` name the minus key
+        while let Some((prefix, key)) = rest.split_once('-')
+            && !key.is_empty()
+        {
+            modifiers |= match prefix.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => break,
+            };
+            rest = key;
+        }
+
+        let code = match rest {
+            "↑" => KeyCode::Up,
+            "↓" => KeyCode::Down,
+            "←" => KeyCode::Left,
+            "→" => KeyCode::Right,
+            _ => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => named_key(rest)
+                        .ok_or_else(|| anyhow::anyhow!("Unknown key '{}' in '{}'", rest, chord))?,
+                }
+            }
+        };
+        // Shift only changes the case of letters; `Shift-/` would silently bind `/`
+        if let KeyCode::Char(c) = code
+            && modifiers.contains(KeyModifiers::SHIFT)
+            && !c.is_ascii_alphabetic()
+        {
+            anyhow::bail!(
+                "Shift can't be combined with '{}' in '{}', use the shifted character instead (e.g. '?' rather than 'Shift-/')",
+                c,
+                chord
+            );
+        }
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+/// Labels for a run of keys, as shown in help and hints
+/// A character key with the same modifiers as the key before drops them (`Ctrl-u/d`)
+pub fn key_labels(chords: &[KeyChord]) -> Vec<String> {
+    let mut previous: Option<KeyChord> = None;
+    chords
+        .iter()
+        .map(|chord| {
+            let shares_modifiers = previous.is_some_and(|previous| {
+                !chord.modifiers.is_empty() && previous.modifiers == chord.modifiers
+            });
+            previous = Some(*chord);
+            match chord.code {
+                KeyCode::Char(c) if shares_modifiers && c != ' ' => c.to_string(),
+                _ => chord.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Key for a name like `Enter`, `PgDn` or `F5` (case-insensitive)
+fn named_key(name: &str) -> Option<KeyCode> {
+    let name = name.to_lowercase();
+    let code = match name.as_str() {
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "space" | "spc" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "del" | "delete" => KeyCode::Delete,
+        "ins" | "insert" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pgup" | "pageup" => KeyCode::PageUp,
+        "pgdn" | "pagedown" => KeyCode::PageDown,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        _ => {
+            let n = name.strip_prefix('f')?.parse().ok()?;
+            if !(1..=24).contains(&n) {
+                return None;
+            }
+            KeyCode::F(n)
+        }
+    };
+    Some(code)
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(key: KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+impl From<KeyChord> for KeyEvent {
+    fn from(chord: KeyChord) -> Self {
+        KeyEvent::new(chord.code, chord.modifiers)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("Shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::BackTab => f.write_str("Shift-Tab"),
+            KeyCode::Backspace => f.write_str("Backspace"),
+            KeyCode::Delete => f.write_str("Del"),
+            KeyCode::Insert => f.write_str("Ins"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Modes with configurable keys (`[keys.<name>]` in the config)
+/// Text prompts (search, tags, convert, templates) only bind their non-editing keys;
+/// keys without an action are typed into the prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyScope {
+    Normal,
+    Preview,
+    Visual,
+    Trash,
+    Help,
+    ThemePicker,
+    Search,
+    RegisterAssign,
+    Confirm,
+    TagEdit,
+    Convert,
+    TemplateInput,
+}
+
+impl KeyScope {
+    pub const ALL: [KeyScope; 12] = [
+        KeyScope::Normal,
+        KeyScope::Preview,
+        KeyScope::Visual,
+        KeyScope::Trash,
+        KeyScope::Help,
+        KeyScope::ThemePicker,
+        KeyScope::Search,
+        KeyScope::RegisterAssign,
+        KeyScope::Confirm,
+        KeyScope::TagEdit,
+        KeyScope::Convert,
+        KeyScope::TemplateInput,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyScope::Normal => "normal",
+            KeyScope::Preview => "preview",
+            KeyScope::Visual => "visual",
+            KeyScope::Trash => "trash",
+            KeyScope::Help => "help",
+            KeyScope::ThemePicker => "theme-picker",
+            KeyScope::Search => "search",
+            KeyScope::RegisterAssign => "register",
+            KeyScope::Confirm => "confirm",
+            KeyScope::TagEdit => "tags",
+            KeyScope::Convert => "convert",
+            KeyScope::TemplateInput => "template",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|scope| scope.name() == name)
+    }
+
+    /// Actions available in this mode and their default keys, in help order
+    fn defaults(self) -> &'static [(Action, &'static [&'static str])] {
+        use Action::*;
+        match self {
+            KeyScope::Normal => &[
+                (Up, &["k", "↑"]),
+                (Down, &["j", "↓"]),
+                (Top, &["Home"]),
+                (Bottom, &["End"]),
+                (PageUp, &["PgUp"]),
+                (PageDown, &["PgDn"]),
+                (HalfPageUp, &["Ctrl-u"]),
+                (HalfPageDown, &["Ctrl-d"]),
+                (Copy, &["Enter"]),
+                (Paste, &["Ctrl-Space"]),
+                (ToggleMark, &["Space"]),
+                (Visual, &["V"]),
+                (Join, &["J"]),
+                (Export, &["E"]),
+                (CopyPretty, &["y"]),
+                (CopyMinified, &["Y"]),
+                (Convert, &["C"]),
+                (AssignRegister, &["m"]),
+                (TogglePin, &["p"]),
+                (PinnedFilter, &["P"]),
+                (ClearMessages, &["c"]),
+                (TestMessages, &["L"]),
+                (Search, &["/"]),
+                (TagSearch, &["#"]),
+                (EditTags, &["t"]),
+                (Help, &["?"]),
+                (TemporaryFilter, &["'"]),
+                (PermanentFilter, &["\""]),
+                (ToggleView, &["v"]),
+                (CycleSort, &["o"]),
+                (ToggleGroups, &["O"]),
+                (FocusPreview, &["Tab"]),
+                (ThemePicker, &["T"]),
+                (CycleTheme, &["Ctrl-t"]),
+                (SaveTheme, &["Alt-t"]),
+                (ReloadTheme, &["Ctrl-l"]),
+                (Delete, &["d"]),
+                (ClearAll, &["D"]),
+                (Trash, &["X"]),
+                (Undo, &["u"]),
+                (Redo, &["Ctrl-r"]),
+                (Quit, &["q"]),
+                (Back, &["Esc"]),
+            ],
+            KeyScope::Preview => &[
+                (Down, &["j", "↓"]),
+                (Up, &["k", "↑"]),
+                (HalfPageUp, &["Ctrl-u"]),
+                (HalfPageDown, &["Ctrl-d"]),
+                (PageUp, &["PgUp"]),
+                (PageDown, &["PgDn"]),
+                (Top, &["g", "Home"]),
+                (Bottom, &["G", "End"]),
+                (Left, &["h", "←"]),
+                (Right, &["l", "→"]),
+                (LineNumbers, &["n"]),
+                (Wrap, &["w"]),
+                (Format, &["f"]),
+                (Fold, &["z"]),
+                (Unfold, &["Z"]),
+                (CopyPretty, &["y"]),
+                (CopyMinified, &["Y"]),
+                (Hex, &["x"]),
+                (Invisibles, &["i"]),
+                (ZoomIn, &["+", "="]),
+                (ZoomOut, &["-"]),
+                (ZoomFit, &["0"]),
+                (ZoomActual, &["1"]),
+                (Help, &["?"]),
+                (Back, &["Tab", "Esc", "q"]),
+            ],
+            KeyScope::Visual => &[
+                (Down, &["j", "↓"]),
+                (Up, &["k", "↑"]),
+                (HalfPageUp, &["Ctrl-u"]),
+                (HalfPageDown, &["Ctrl-d"]),
+                (Top, &["Home"]),
+                (Bottom, &["End"]),
+                (PageUp, &["PgUp"]),
+                (PageDown, &["PgDn"]),
+                (ToggleMark, &["Space"]),
+                (TogglePin, &["p"]),
+                (EditTags, &["t"]),
+                (Delete, &["d"]),
+                (Join, &["J"]),
+                (Export, &["E"]),
+                (Back, &["V", "Esc"]),
+            ],
+            KeyScope::Trash => &[
+                (Down, &["j", "↓"]),
+                (Up, &["k", "↑"]),
+                (Top, &["Home"]),
+                (Bottom, &["End"]),
+                (PageUp, &["PgUp"]),
+                (PageDown, &["PgDn"]),
+                (Restore, &["Enter", "r"]),
+                (Delete, &["d"]),
+                (Back, &["X", "q", "Esc"]),
+            ],
+            KeyScope::Help => &[
+                (Down, &["j", "↓"]),
+                (Up, &["k", "↑"]),
+                (PageDown, &["PgDn"]),
+                (PageUp, &["PgUp"]),
+                (HalfPageDown, &["Ctrl-d"]),
+                (HalfPageUp, &["Ctrl-u"]),
+                (Top, &["Home"]),
+                (Bottom, &["End"]),
+                (Back, &["?", "Esc"]),
+            ],
+            KeyScope::ThemePicker => &[
+                (Down, &["j", "↓"]),
+                (Up, &["k", "↑"]),
+                (Top, &["g", "Home"]),
+                (Bottom, &["G", "End"]),
+                (Select, &["Enter"]),
+                (Back, &["Esc"]),
+            ],
+            KeyScope::Search => &[
+                (Up, &["↑"]),
+                (Down, &["↓"]),
+                (Copy, &["Enter"]),
+                (Paste, &["Ctrl-Space"]),
+                (MatchMode, &["Ctrl-r"]),
+                (CaseSensitive, &["Ctrl-s"]),
+                (Back, &["Esc"]),
+            ],
+            // The register itself is the next letter or digit typed
+            KeyScope::RegisterAssign => &[(Back, &["Esc"])],
+            KeyScope::Confirm => &[(Select, &["y", "Y"]), (Back, &["n", "N", "Esc"])],
+            KeyScope::TagEdit | KeyScope::Convert | KeyScope::TemplateInput => {
+                &[(Select, &["Enter"]), (Back, &["Esc"])]
+            }
+        }
+    }
+}
+
+/// Something a key can be bound to (`<action> = "<keys>"` in the config)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    PageUp,
+    PageDown,
+    HalfPageUp,
+    HalfPageDown,
+    Copy,
+    Paste,
+    Select,
+    MatchMode,
+    CaseSensitive,
+    ToggleMark,
+    Visual,
+    Join,
+    Export,
+    CopyPretty,
+    CopyMinified,
+    Convert,
+    AssignRegister,
+    TogglePin,
+    PinnedFilter,
+    ClearMessages,
+    TestMessages,
+    Search,
+    TagSearch,
+    EditTags,
+    Help,
+    TemporaryFilter,
+    PermanentFilter,
+    ToggleView,
+    CycleSort,
+    ToggleGroups,
+    FocusPreview,
+    ThemePicker,
+    CycleTheme,
+    SaveTheme,
+    ReloadTheme,
+    Delete,
+    ClearAll,
+    Trash,
+    Restore,
+    Undo,
+    Redo,
+    Quit,
+    Back,
+    LineNumbers,
+    Wrap,
+    Format,
+    Fold,
+    Unfold,
+    Hex,
+    Invisibles,
+    ZoomIn,
+    ZoomOut,
+    ZoomFit,
+    ZoomActual,
+}
+
+impl Action {
+    /// Name used in the config
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::Top => "top",
+            Action::Bottom => "bottom",
+            Action::PageUp => "page-up",
+            Action::PageDown => "page-down",
+            Action::HalfPageUp => "half-page-up",
+            Action::HalfPageDown => "half-page-down",
+            Action::Copy => "copy",
+            Action::Paste => "paste",
+            Action::Select => "select",
+            Action::MatchMode => "match-mode",
+            Action::CaseSensitive => "case-sensitive",
+            Action::ToggleMark => "mark",
+            Action::Visual => "visual",
+            Action::Join => "join",
+            Action::Export => "export",
+            Action::CopyPretty => "copy-pretty",
+            Action::CopyMinified => "copy-minified",
+            Action::Convert => "convert",
+            Action::AssignRegister => "assign-register",
+            Action::TogglePin => "pin",
+            Action::PinnedFilter => "pinned-filter",
+            Action::ClearMessages => "clear-messages",
+            Action::TestMessages => "test-messages",
+            Action::Search => "search",
+            Action::TagSearch => "tag-search",
+            Action::EditTags => "tags",
+            Action::Help => "help",
+            Action::TemporaryFilter => "temporary-filter",
+            Action::PermanentFilter => "permanent-filter",
+            Action::ToggleView => "view",
+            Action::CycleSort => "sort",
+            Action::ToggleGroups => "groups",
+            Action::FocusPreview => "focus-preview",
+            Action::ThemePicker => "theme-picker",
+            Action::CycleTheme => "next-theme",
+            Action::SaveTheme => "save-theme",
+            Action::ReloadTheme => "reload-theme",
+            Action::Delete => "delete",
+            Action::ClearAll => "clear-all",
+            Action::Trash => "trash",
+            Action::Restore => "restore",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::Quit => "quit",
+            Action::Back => "back",
+            Action::LineNumbers => "line-numbers",
+            Action::Wrap => "wrap",
+            Action::Format => "format",
+            Action::Fold => "fold",
+            Action::Unfold => "unfold",
+            Action::Hex => "hex",
+            Action::Invisibles => "invisibles",
+            Action::ZoomIn => "zoom-in",
+            Action::ZoomOut => "zoom-out",
+            Action::ZoomFit => "zoom-fit",
+            Action::ZoomActual => "zoom-actual",
+        }
+    }
+}
+
+/// Keys bound to actions in each mode: the defaults with `[keys]` from the config applied
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    /// Keys of each action, in the order they are shown
+    keys: HashMap<(KeyScope, Action), Vec<KeyChord>>,
+    actions: HashMap<(KeyScope, KeyChord), Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&HashMap::new()).0
+    }
+}
+
+impl Keymap {
+    /// Apply the `[keys]` config to the defaults
+    /// A configured action replaces all of its default keys (`[]` unbinds it).
+    /// Also returns the problems found: unknown modes, actions or keys, and keys bound
+    /// to two actions in one mode (a configured action wins over a default one)
+    pub fn new(config: &HashMap<String, HashMap<String, KeyBindingValue>>) -> (Self, Vec<String>) {
+        let mut problems = Vec::new();
+        let mut keys = HashMap::new();
+        for scope in KeyScope::ALL {
+            for (action, defaults) in scope.defaults() {
+                let chords = defaults
+                    .iter()
+                    .map(|key| KeyChord::parse(key).expect("default keys parse"))
+                    .collect();
+                keys.insert((scope, *action), chords);
+            }
+        }
+
+        // Sorted so problems are reported in a stable order
+        let mut configured = HashSet::new();
+        let mut sections: Vec<_> = config.iter().collect();
+        sections.sort_by_key(|(name, _)| *name);
+        for (scope_name, bindings) in sections {
+            let Some(scope) = KeyScope::from_name(scope_name) else {
+                let names: Vec<_> = KeyScope::ALL.iter().map(|scope| scope.name()).collect();
+                problems.push(format!(
+                    "[keys.{}]: unknown mode (use {})",
+                    scope_name,
+                    names.join(", ")
+                ));
+                continue;
+            };
+            let mut bindings: Vec<_> = bindings.iter().collect();
+            bindings.sort_by_key(|(name, _)| *name);
+            for (action_name, value) in bindings {
+                let Some(action) = scope
+                    .defaults()
+                    .iter()
+                    .map(|(action, _)| *action)
+                    .find(|action| action.name() == action_name)
+                else {
+                    problems.push(format!(
+                        "[keys.{}] {}: unknown action",
+                        scope_name, action_name
+                    ));
+                    continue;
+                };
+                let mut chords = Vec::new();
+                for key in value.keys() {
+                    match KeyChord::parse(key) {
+                        Ok(chord) if !chords.contains(&chord) => chords.push(chord),
+                        Ok(_) => {}
+                        Err(e) => {
+                            problems.push(format!("[keys.{}] {}: {}", scope_name, action_name, e))
+                        }
+                    }
+                }
+                keys.insert((scope, action), chords);
+                configured.insert((scope, action));
+            }
+        }
+
+        // Configured actions claim their keys first so defaults can't shadow them
+        let mut actions = HashMap::new();
+        let mut shadowed = Vec::new();
+        for scope in KeyScope::ALL {
+            let (mine, defaults): (Vec<_>, Vec<_>) = scope
+                .defaults()
+                .iter()
+                .map(|(action, _)| *action)
+                .partition(|action| configured.contains(&(scope, *action)));
+            for action in mine.into_iter().chain(defaults) {
+                for chord in &keys[&(scope, action)] {
+                    match actions.entry((scope, *chord)) {
+                        Entry::Vacant(entry) => {
+                            entry.insert(action);
+                        }
+                        Entry::Occupied(entry) => {
+                            problems.push(format!(
+                                "[keys.{}] {} is bound to both {} and {} (using {})",
+                                scope.name(),
+                                chord,
+                                entry.get().name(),
+                                action.name(),
+                                entry.get().name()
+                            ));
+                            shadowed.push((scope, action, *chord));
+                        }
+                    }
+                }
+            }
+        }
+        // Keys that lost a conflict aren't shown for the action they don't trigger
+        for (scope, action, chord) in shadowed {
+            if let Some(chords) = keys.get_mut(&(scope, action)) {
+                chords.retain(|c| *c != chord);
+            }
+        }
+
+        (Self { keys, actions }, problems)
+    }
+
+    /// Action a key triggers in a mode
+    pub fn action(&self, scope: KeyScope, key: KeyEvent) -> Option<Action> {
+        self.actions.get(&(scope, KeyChord::from(key))).copied()
+    }
+
+    /// Keys bound to an action in a mode (empty if unbound)
+    pub fn keys(&self, scope: KeyScope, action: Action) -> &[KeyChord] {
+        self.keys
+            .get(&(scope, action))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_chords() {
+        let ctrl_d = KeyChord::parse("Ctrl-D").unwrap();
+        assert_eq!(ctrl_d, KeyChord::parse("ctrl-d").unwrap());
+        assert_eq!(ctrl_d.to_string(), "Ctrl-d");
+        assert_eq!(
+            KeyChord::parse("Shift-j").unwrap(),
+            KeyChord::parse("J").unwrap()
+        );
+        assert_eq!(KeyChord::parse("-").unwrap().code, KeyCode::Char('-'));
+        assert_eq!(
+            KeyChord::parse("Ctrl--").unwrap().to_string(),
+            "Ctrl--".to_string()
+        );
+        assert_eq!(KeyChord::parse("pgdn").unwrap().to_string(), "PgDn");
+        assert_eq!(KeyChord::parse("Down").unwrap().to_string(), "↓");
+        assert_eq!(KeyChord::parse("F5").unwrap().code, KeyCode::F(5));
+        let half_page = [ctrl_d, KeyChord::parse("Ctrl-u").unwrap()];
+        assert_eq!(key_labels(&half_page), vec!["Ctrl-d", "u"]);
+        assert!(KeyChord::parse("Hyper-x").is_err());
+        assert!(KeyChord::parse("F99").is_err());
+        let error = KeyChord::parse("Shift-/").unwrap_err().to_string();
+        assert!(error.contains("use the shifted character"));
+        assert!(KeyChord::parse("Ctrl-Shift-1").is_err());
+        assert!(KeyChord::parse("Shift-Space").is_err());
+
+        // Events from the terminal match regardless of how Shift is reported
+        assert_eq!(
+            KeyChord::from(key(KeyCode::Char('J'), KeyModifiers::SHIFT)),
+            KeyChord::parse("J").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_shift_tab_matches_backtab() {
+        let shift_tab = KeyChord::parse("Shift-Tab").unwrap();
+        assert_eq!(shift_tab, KeyChord::parse("BackTab").unwrap());
+        assert_eq!(shift_tab.to_string(), "Shift-Tab");
+
+        let config: HashMap<String, HashMap<String, KeyBindingValue>> =
+            toml::from_str("[preview]\nback = \"Shift-Tab\"").unwrap();
+        let (keymap, problems) = Keymap::new(&config);
+        assert!(problems.is_empty(), "{:?}", problems);
+        for modifiers in [KeyModifiers::SHIFT, KeyModifiers::NONE] {
+            assert_eq!(
+                keymap.action(KeyScope::Preview, key(KeyCode::BackTab, modifiers)),
+                Some(Action::Back)
+            );
+        }
+        assert_eq!(
+            keymap.action(KeyScope::Preview, key(KeyCode::Tab, KeyModifiers::NONE)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_prompt_scopes() {
+        let config: HashMap<String, HashMap<String, KeyBindingValue>> =
+            toml::from_str("[search]\nmatch-mode = \"Alt-m\"\n\n[confirm]\nselect = \"Enter\"")
+                .unwrap();
+        let (keymap, problems) = Keymap::new(&config);
+        assert!(problems.is_empty(), "{:?}", problems);
+
+        assert_eq!(
+            keymap.action(KeyScope::Search, key(KeyCode::Char('m'), KeyModifiers::ALT)),
+            Some(Action::MatchMode)
+        );
+        assert_eq!(
+            keymap.action(
+                KeyScope::Search,
+                key(KeyCode::Char('r'), KeyModifiers::CONTROL)
+            ),
+            None
+        );
+        assert_eq!(
+            keymap.action(
+                KeyScope::Search,
+                key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+            ),
+            Some(Action::CaseSensitive)
+        );
+        // Typed characters are left to the search input
+        assert_eq!(
+            keymap.action(
+                KeyScope::Search,
+                key(KeyCode::Char('j'), KeyModifiers::NONE)
+            ),
+            None
+        );
+        assert_eq!(
+            keymap.action(KeyScope::Confirm, key(KeyCode::Enter, KeyModifiers::NONE)),
+            Some(Action::Select)
+        );
+        assert_eq!(
+            keymap.action(
+                KeyScope::Confirm,
+                key(KeyCode::Char('y'), KeyModifiers::NONE)
+            ),
+            None
+        );
+        assert_eq!(
+            keymap.action(
+                KeyScope::Confirm,
+                key(KeyCode::Char('n'), KeyModifiers::NONE)
+            ),
+            Some(Action::Back)
+        );
+    }
+
+    #[test]
+    fn test_keymap_config() {
+        let config: HashMap<String, HashMap<String, KeyBindingValue>> = toml::from_str(
+            r#"
+            [normal]
+            copy = ["Enter", "d"]
+            quit = []
+            bogus = "x"
+
+            [preview]
+            wrap = "Ctrl-w"
+
+            [nowhere]
+            up = "k"
+            "#,
+        )
+        .unwrap();
+        let (keymap, problems) = Keymap::new(&config);
+
+        let d = key(KeyCode::Char('d'), KeyModifiers::NONE);
+        assert_eq!(keymap.action(KeyScope::Normal, d), Some(Action::Copy));
+        assert!(keymap.keys(KeyScope::Normal, Action::Delete).is_empty());
+        assert_eq!(
+            keymap.action(
+                KeyScope::Normal,
+                key(KeyCode::Char('q'), KeyModifiers::NONE)
+            ),
+            None
+        );
+        assert_eq!(
+            keymap.action(
+                KeyScope::Preview,
+                key(KeyCode::Char('w'), KeyModifiers::CONTROL)
+            ),
+            Some(Action::Wrap)
+        );
+        assert_eq!(
+            keymap.action(
+                KeyScope::Preview,
+                key(KeyCode::Char('w'), KeyModifiers::NONE)
+            ),
+            None
+        );
+
+        assert_eq!(
+            problems,
+            vec![
+                "[keys.normal] bogus: unknown action",
+                "[keys.nowhere]: unknown mode (use normal, preview, visual, trash, help, theme-picker, search, register, confirm, tags, convert, template)",
+                "[keys.normal] d is bound to both copy and delete (using copy)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_keymap_has_no_conflicts() {
+        let (keymap, problems) = Keymap::new(&HashMap::new());
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(
+            keymap.action(KeyScope::Normal, key(KeyCode::Enter, KeyModifiers::NONE)),
+            Some(Action::Copy)
+        );
+    }
+}
@@ -1,7 +1,24 @@
 pub mod clip;
+pub mod inspect;
+pub mod keymap;
+pub mod phash;
+pub mod query;
 pub mod registry;
 pub mod search_index;
+pub mod structured;
+pub mod syntax;
+pub mod template;
+pub mod undo;
 
-pub use clip::{ClipContent, ClipEntry, ClipboardHistory};
+pub use clip::{
+    ClipContent, ClipEntry, ClipboardHistory, dmenu_line, normalize_tag, parse_dmenu_line,
+};
+pub use inspect::{FileHead, Invisible, Invisibles};
+pub use keymap::{Action, KeyChord, KeyScope, Keymap, key_labels};
+pub use query::SearchQuery;
 pub use registry::{Registry, is_valid_register_key};
-pub use search_index::{SearchIndex, SearchMode};
+pub use search_index::{Highlighter, MatchMode, SearchIndex, SearchMode};
+pub use structured::{FormatError, StructuredFormat};
+pub use syntax::{Language, Token, TokenKind, Tokenizer};
+pub use template::{Template, TemplateContext};
+pub use undo::{HistorySnapshot, UndoStack};
@@ -0,0 +1,56 @@
+use image::DynamicImage;
+use image::imageops::FilterType;
+
+/// 64-bit difference hash of an image
+/// Each bit says whether a pixel of a 9x8 grayscale thumbnail is brighter than its right
+/// neighbour, so re-encoding, rescaling or slight colour changes flip few bits
+pub fn dhash(img: &DynamicImage) -> u64 {
+    let small = img.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let brighter = small.get_pixel(x, y).0[0] > small.get_pixel(x + 1, y).0[0];
+            hash = (hash << 1) | brighter as u64;
+        }
+    }
+    hash
+}
+
+/// Decode image bytes and hash them (None if they aren't a decodable image)
+pub fn perceptual_hash(data: &[u8]) -> Option<u64> {
+    image::load_from_memory(data).ok().map(|img| dhash(&img))
+}
+
+/// Number of bits that differ between two hashes (0 is the same picture)
+pub fn hash_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, Rgb, RgbImage};
+    use std::io::Cursor;
+
+    fn encode(img: &DynamicImage, format: ImageFormat) -> Vec<u8> {
+        let mut out = Cursor::new(Vec::new());
+        img.write_to(&mut out, format).unwrap();
+        out.into_inner()
+    }
+
+    #[test]
+    fn test_dhash_survives_reencoding() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(64, 48, |x, y| {
+            Rgb([(x * 4) as u8, (y * 5) as u8, ((x * y) % 256) as u8])
+        }));
+        let png = perceptual_hash(&encode(&img, ImageFormat::Png)).unwrap();
+        let bmp = perceptual_hash(&encode(&img, ImageFormat::Bmp)).unwrap();
+        let scaled = dhash(&img.resize_exact(128, 96, FilterType::Triangle));
+        assert_eq!(png, bmp);
+        assert!(hash_distance(png, scaled) <= 4);
+
+        let flipped = dhash(&img.fliph());
+        assert!(hash_distance(png, flipped) > 16);
+        assert_eq!(perceptual_hash(b"not an image"), None);
+    }
+}
@@ -0,0 +1,309 @@
+use chrono::{Local, NaiveDate};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+use super::clip::{ClipContent, ClipEntry, normalize_tag};
+
+/// Kind of clip content, for `type:` filters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentKind {
+    Text,
+    Image,
+    File,
+}
+
+/// How a `size:` filter compares against the clip size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeOp {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+/// A single field filter parsed from the search query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    /// `type:text|image|file`
+    Type(ContentKind),
+    /// `pinned:yes|no`
+    Pinned(bool),
+    /// `reg:a` - has temporary or permanent register
+    Register(char),
+    /// `name:foo` - name contains text (case-insensitive)
+    Name(String),
+    /// `after:2026-01-01` or `after:3d` - last seen at or after this time
+    After(SystemTime),
+    /// `before:2026-01-01` or `before:3d` - last seen before this time
+    Before(SystemTime),
+    /// `size:>1k` - content size comparison (no operator means at least)
+    Size(SizeOp, u64),
+    /// `mime:text/html` - MIME type prefix (case-insensitive)
+    Mime(String),
+    /// `#tag` - has tag
+    Tag(String),
+}
+
+/// A filter, optionally negated with a leading `!`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    pub filter: Filter,
+    pub negated: bool,
+}
+
+/// Search query split into field filters and the fuzzy remainder
+/// e.g. `type:text !pinned:yes #sql select` -> 3 conditions + fuzzy "select"
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// Field filters a clip must all satisfy
+    pub conditions: Vec<Condition>,
+    /// Remaining text for fuzzy matching
+    pub fuzzy: String,
+    /// Tokens that looked like filters but had invalid values (e.g. `after:soon`)
+    /// These are ignored, so a half-typed filter doesn't empty the list
+    pub invalid: Vec<String>,
+    /// Sizes of file clips by clip ID, so `size:` reads each file's metadata
+    /// once per query instead of on every list refresh
+    file_sizes: RefCell<HashMap<u64, u64>>,
+}
+
+impl SearchQuery {
+    /// Parse a query string
+    /// Unknown `field:value` tokens are kept in the fuzzy remainder (e.g. URLs)
+    pub fn parse(query: &str) -> Self {
+        let mut parsed = SearchQuery::default();
+        let mut fuzzy = Vec::new();
+
+        for token in query.split_whitespace() {
+            let (negated, body) = match token.strip_prefix('!') {
+                Some(body) => (true, body),
+                None => (false, token),
+            };
+
+            match Filter::parse(body) {
+                Some(Ok(filter)) => parsed.conditions.push(Condition { filter, negated }),
+                Some(Err(())) => parsed.invalid.push(token.to_string()),
+                None => fuzzy.push(token),
+            }
+        }
+
+        parsed.fuzzy = fuzzy.join(" ");
+        parsed
+    }
+
+    /// Check whether a clip satisfies all field filters (ignores the fuzzy part)
+    pub fn matches(&self, entry: &ClipEntry) -> bool {
+        self.conditions
+            .iter()
+            .all(|c| c.filter.matches(entry, || self.size_of(entry)) != c.negated)
+    }
+
+    /// Content size of a clip, with file sizes cached for the life of the query
+    fn size_of(&self, entry: &ClipEntry) -> u64 {
+        if !entry.content.is_file() {
+            return entry.content.size_bytes();
+        }
+        *self
+            .file_sizes
+            .borrow_mut()
+            .entry(entry.id)
+            .or_insert_with(|| entry.content.size_bytes())
+    }
+
+    /// Check whether the query has any field filters
+    pub fn has_filters(&self) -> bool {
+        !self.conditions.is_empty()
+    }
+}
+
+impl Filter {
+    /// Parse a filter token (without `!`)
+    /// Returns None if the token isn't a filter, Some(Err) if its value is invalid
+    fn parse(token: &str) -> Option<Result<Self, ()>> {
+        if token.starts_with('#') {
+            return normalize_tag(token).map(|tag| Ok(Filter::Tag(tag)));
+        }
+
+        let (field, value) = token.split_once(':')?;
+        let value_lower = value.to_lowercase();
+
+        let filter = match field.to_lowercase().as_str() {
+            "type" => match value_lower.as_str() {
+                "text" => Some(Filter::Type(ContentKind::Text)),
+                "image" | "img" => Some(Filter::Type(ContentKind::Image)),
+                "file" => Some(Filter::Type(ContentKind::File)),
+                _ => None,
+            },
+            "pinned" => parse_bool(&value_lower).map(Filter::Pinned),
+            "reg" => {
+                let mut chars = value.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(key), None) => Some(Filter::Register(key)),
+                    _ => None,
+                }
+            }
+            "name" if !value.is_empty() => Some(Filter::Name(value_lower)),
+            "after" => parse_time(&value_lower).map(Filter::After),
+            "before" => parse_time(&value_lower).map(Filter::Before),
+            "size" => parse_size(&value_lower).map(|(op, size)| Filter::Size(op, size)),
+            "mime" if !value.is_empty() => Some(Filter::Mime(value_lower)),
+            "name" | "mime" => None,
+            _ => return None,
+        };
+
+        Some(filter.ok_or(()))
+    }
+
+    /// Check whether a clip satisfies this filter
+    /// `content_size` gives the clip's size, only called by `size:` filters
+    fn matches(&self, entry: &ClipEntry, content_size: impl FnOnce() -> u64) -> bool {
+        match self {
+            Filter::Type(kind) => matches!(
+                (kind, &entry.content),
+                (ContentKind::Text, ClipContent::Text(_))
+                    | (ContentKind::Image, ClipContent::Image { .. })
+                    | (ContentKind::File, ClipContent::File { .. })
+            ),
+            Filter::Pinned(pinned) => entry.pinned == *pinned,
+            Filter::Register(key) => {
+                entry.temporary_registers.contains(key) || entry.permanent_registers.contains(key)
+            }
+            Filter::Name(name) => entry
+                .name
+                .as_ref()
+                .is_some_and(|n| n.to_lowercase().contains(name)),
+            Filter::After(time) => entry.timestamp >= *time,
+            Filter::Before(time) => entry.timestamp < *time,
+            Filter::Size(op, size) => {
+                let actual = content_size();
+                match op {
+                    SizeOp::Greater => actual > *size,
+                    SizeOp::GreaterOrEqual => actual >= *size,
+                    SizeOp::Less => actual < *size,
+                    SizeOp::LessOrEqual => actual <= *size,
+                }
+            }
+            Filter::Mime(mime) => entry.content.mime_type().to_lowercase().starts_with(mime),
+            Filter::Tag(tag) => entry.has_tag(tag),
+        }
+    }
+}
+
+/// Parse yes/no style values
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "yes" | "y" | "true" | "1" => Some(true),
+        "no" | "n" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse a date (`YYYY-MM-DD`, local midnight) or a relative age (`30m`, `12h`, `3d`, `2w`)
+fn parse_time(value: &str) -> Option<SystemTime> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let midnight = date
+            .and_hms_opt(0, 0, 0)?
+            .and_local_timezone(Local)
+            .earliest()?;
+        return Some(midnight.into());
+    }
+
+    let unit_at = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(unit_at);
+    let amount: u64 = amount.parse().ok()?;
+    let secs = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    SystemTime::now().checked_sub(Duration::from_secs(amount.checked_mul(secs)?))
+}
+
+/// Parse a size comparison like `>1k`, `<=2mb` or `500`
+fn parse_size(value: &str) -> Option<(SizeOp, u64)> {
+    let (op, rest) = if let Some(rest) = value.strip_prefix(">=") {
+        (SizeOp::GreaterOrEqual, rest)
+    } else if let Some(rest) = value.strip_prefix("<=") {
+        (SizeOp::LessOrEqual, rest)
+    } else if let Some(rest) = value.strip_prefix('>') {
+        (SizeOp::Greater, rest)
+    } else if let Some(rest) = value.strip_prefix('<') {
+        (SizeOp::Less, rest)
+    } else {
+        (SizeOp::GreaterOrEqual, value)
+    };
+
+    let unit_at = rest
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(rest.len());
+    let (number, unit) = rest.split_at(unit_at);
+    let number: f64 = number.parse().ok()?;
+    let multiplier = match unit {
+        "" | "b" => 1.0,
+        "k" | "kb" => 1024.0,
+        "m" | "mb" => 1024.0 * 1024.0,
+        "g" | "gb" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+
+    Some((op, (number * multiplier) as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_filters_and_fuzzy() {
+        let query = SearchQuery::parse("type:image !pinned:yes select https://x.io #SQL size:>1k");
+        assert_eq!(query.fuzzy, "select https://x.io");
+        assert!(query.invalid.is_empty());
+        assert_eq!(
+            query.conditions,
+            vec![
+                Condition {
+                    filter: Filter::Type(ContentKind::Image),
+                    negated: false,
+                },
+                Condition {
+                    filter: Filter::Pinned(true),
+                    negated: true,
+                },
+                Condition {
+                    filter: Filter::Tag("sql".to_string()),
+                    negated: false,
+                },
+                Condition {
+                    filter: Filter::Size(SizeOp::Greater, 1024),
+                    negated: false,
+                },
+            ]
+        );
+
+        // A lone '#' and unfinished filters are not applied
+        let query = SearchQuery::parse("a # b after:2026-0 reg:");
+        assert!(!query.has_filters());
+        assert_eq!(query.fuzzy, "a # b");
+        assert_eq!(query.invalid, vec!["after:2026-0", "reg:"]);
+    }
+
+    #[test]
+    fn test_matches() {
+        let mut entry = ClipEntry::new_text(1, "x".repeat(2048));
+        entry.name = Some("Deploy Script".to_string());
+        entry.add_temporary_register('a');
+        entry.add_tag("ops");
+
+        let matches = |q: &str| SearchQuery::parse(q).matches(&entry);
+        assert!(matches("type:text name:deploy reg:a #ops"));
+        assert!(matches("size:>1k size:<=2k mime:text !pinned:yes"));
+        assert!(matches("after:1d before:2999-01-01"));
+        assert!(!matches("type:image"));
+        assert!(!matches("!#ops"));
+        assert!(!matches("after:2999-01-01"));
+        assert!(!matches("mime:text/html"));
+    }
+}
@@ -1,5 +1,9 @@
+use nucleo::Nucleo;
 use nucleo_matcher::pattern::{CaseMatching, Normalization, Pattern};
-use nucleo_matcher::{Config, Matcher, Utf32String};
+use nucleo_matcher::{Config, Matcher, Utf32Str, Utf32String};
+use regex::{Regex, RegexBuilder};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
 
 use super::clip::ClipEntry;
 
@@ -14,10 +18,69 @@ pub enum SearchMode {
     CaseSensitive,
 }
 
+/// How the query text is matched against clips
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MatchMode {
+    /// Fuzzy matching with nucleo (supports `!`, `^`, `'` pattern syntax)
+    #[default]
+    Fuzzy,
+    /// Exact substring match of the whole query
+    Exact,
+    /// Regular expression
+    Regex,
+    /// Every query word must start a word in the clip (e.g. "conn ref" matches "connection refused")
+    WordPrefix,
+}
+
+impl MatchMode {
+    /// Short name shown in the search bar indicator
+    pub fn label(self) -> &'static str {
+        match self {
+            MatchMode::Fuzzy => "fuzzy",
+            MatchMode::Exact => "exact",
+            MatchMode::Regex => "regex",
+            MatchMode::WordPrefix => "prefix",
+        }
+    }
+}
+
+/// Milliseconds `poll_results` waits for the background matcher
+const SEARCH_TICK_MS: u64 = 5;
+
+/// Cached searchable text for one clip
+struct Haystack {
+    id: u64,
+    /// Hash of everything the searchable text is built from
+    signature: u64,
+    text: String,
+    /// Lowercased text for case-insensitive exact and word-prefix matching
+    lower: String,
+}
+
+/// Fuzzy matcher running on nucleo's thread pool
+/// Each clip's UTF-32 haystack is built once, when it is injected
+struct BackgroundSearch {
+    nucleo: Nucleo<u64>,
+    /// Query last given to the pattern, to detect narrowing
+    query: String,
+    /// Case mode the pattern was parsed with
+    mode: SearchMode,
+    /// Whether results should be collected (false outside fuzzy searches)
+    active: bool,
+}
+
 /// Wrapper around nucleo for fuzzy searching clipboard entries
+/// Also supports exact, regex and word-prefix matching
 pub struct SearchIndex {
     matcher: Matcher,
     mode: SearchMode,
+    match_mode: MatchMode,
+    /// Error from the last regex search (invalid pattern)
+    regex_error: Option<String>,
+    /// Searchable text per clip, in history order, rebuilt for changed clips only
+    haystacks: Vec<Haystack>,
+    /// Threaded fuzzy matcher for the TUI, started on first use
+    background: Option<BackgroundSearch>,
 }
 
 impl SearchIndex {
@@ -26,6 +89,10 @@ impl SearchIndex {
         SearchIndex {
             matcher: Matcher::new(Config::DEFAULT),
             mode: SearchMode::default(),
+            match_mode: MatchMode::default(),
+            regex_error: None,
+            haystacks: Vec::new(),
+            background: None,
         }
     }
 
@@ -39,8 +106,7 @@ impl SearchIndex {
         self.mode
     }
 
-    /// Toggle between SmartCase and CaseSensitive modes
-    /// (for future Ctrl-? keybinding)
+    /// Toggle between SmartCase and CaseSensitive modes (Ctrl-s in search mode)
     pub fn toggle_mode(&mut self) {
         self.mode = match self.mode {
             SearchMode::SmartCase => SearchMode::CaseSensitive,
@@ -48,44 +114,343 @@ impl SearchIndex {
         };
     }
 
+    /// Get the current match mode
+    pub fn match_mode(&self) -> MatchMode {
+        self.match_mode
+    }
+
+    /// Set the match mode
+    pub fn set_match_mode(&mut self, match_mode: MatchMode) {
+        self.match_mode = match_mode;
+    }
+
+    /// Cycle fuzzy -> exact -> regex -> word prefix (Ctrl-r in search mode)
+    pub fn cycle_match_mode(&mut self) {
+        self.match_mode = match self.match_mode {
+            MatchMode::Fuzzy => MatchMode::Exact,
+            MatchMode::Exact => MatchMode::Regex,
+            MatchMode::Regex => MatchMode::WordPrefix,
+            MatchMode::WordPrefix => MatchMode::Fuzzy,
+        };
+        self.regex_error = None;
+    }
+
+    /// Error message if the last regex query was invalid
+    pub fn regex_error(&self) -> Option<&str> {
+        self.regex_error.as_deref()
+    }
+
+    /// Build a highlighter that finds this query's matches in displayed text
+    /// Returns None for an empty query or an invalid regex
+    pub fn highlighter(&self, query: &str) -> Option<Highlighter> {
+        if query.is_empty() {
+            return None;
+        }
+
+        let ignore_case = self.ignore_case(query);
+        let kind = match self.match_mode {
+            MatchMode::Fuzzy => HighlightKind::Fuzzy(Pattern::parse(
+                query,
+                self.case_matching(),
+                Normalization::Smart,
+            )),
+            MatchMode::Exact => HighlightKind::Exact(
+                RegexBuilder::new(&regex::escape(query))
+                    .case_insensitive(ignore_case)
+                    .build()
+                    .ok()?,
+            ),
+            MatchMode::Regex => HighlightKind::Regex(
+                RegexBuilder::new(query)
+                    .case_insensitive(ignore_case)
+                    .build()
+                    .ok()?,
+            ),
+            MatchMode::WordPrefix => {
+                // Each word start followed by one of the query words
+                let words: Vec<String> = query.split_whitespace().map(regex::escape).collect();
+                let pattern = format!(r"\b(?:{})", words.join("|"));
+                HighlightKind::Regex(
+                    RegexBuilder::new(&pattern)
+                        .case_insensitive(ignore_case)
+                        .build()
+                        .ok()?,
+                )
+            }
+        };
+
+        Some(Highlighter {
+            matcher: Matcher::new(Config::DEFAULT),
+            kind,
+        })
+    }
+
+    /// nucleo case matching for the current search mode
+    fn case_matching(&self) -> CaseMatching {
+        match self.mode {
+            SearchMode::SmartCase => CaseMatching::Smart,
+            SearchMode::CaseSensitive => CaseMatching::Respect,
+        }
+    }
+
+    /// Whether matching should ignore case for this query
+    fn ignore_case(&self, query: &str) -> bool {
+        match self.mode {
+            SearchMode::SmartCase => !query.chars().any(char::is_uppercase),
+            SearchMode::CaseSensitive => false,
+        }
+    }
+
     /// Search clips by query string
     /// Returns vector of (clip_id, score) tuples, sorted by score descending
     pub fn search(&mut self, clips: &[ClipEntry], query: &str) -> Vec<(u64, u32)> {
+        self.regex_error = None;
+
         if query.is_empty() {
             // Return all clips with max score if query is empty
             return clips.iter().map(|c| (c.id, u32::MAX)).collect();
         }
 
-        // Map SearchMode to nucleo's CaseMatching
-        let case_matching = match self.mode {
-            SearchMode::SmartCase => CaseMatching::Smart,
-            SearchMode::CaseSensitive => CaseMatching::Respect,
-        };
+        self.sync_haystacks(clips);
+        let haystacks = &self.haystacks;
+
+        match self.match_mode {
+            MatchMode::Fuzzy => self.search_fuzzy(query),
+            MatchMode::Exact => {
+                let ignore_case = self.ignore_case(query);
+                let needle = if ignore_case {
+                    query.to_lowercase()
+                } else {
+                    query.to_string()
+                };
+                filter_clips(haystacks, ignore_case, |text| text.contains(&needle))
+            }
+            MatchMode::Regex => {
+                let regex = RegexBuilder::new(query)
+                    .case_insensitive(self.ignore_case(query))
+                    .build();
+                match regex {
+                    Ok(regex) => filter_clips(haystacks, false, |text| regex.is_match(text)),
+                    Err(e) => {
+                        self.regex_error = Some(e.to_string());
+                        Vec::new()
+                    }
+                }
+            }
+            MatchMode::WordPrefix => {
+                let ignore_case = self.ignore_case(query);
+                let prefixes: Vec<String> = query
+                    .split_whitespace()
+                    .map(|w| {
+                        if ignore_case {
+                            w.to_lowercase()
+                        } else {
+                            w.to_string()
+                        }
+                    })
+                    .collect();
+                filter_clips(haystacks, ignore_case, |text| {
+                    let words: Vec<&str> = text
+                        .split(|c: char| !c.is_alphanumeric() && c != '_')
+                        .filter(|w| !w.is_empty())
+                        .collect();
+                    prefixes
+                        .iter()
+                        .all(|p| words.iter().any(|w| w.starts_with(p.as_str())))
+                })
+            }
+        }
+    }
 
+    /// Fuzzy search with nucleo, best matches first
+    fn search_fuzzy(&mut self, query: &str) -> Vec<(u64, u32)> {
         // Create pattern from query
-        let pattern = Pattern::parse(query, case_matching, Normalization::Smart);
+        let pattern = Pattern::parse(query, self.case_matching(), Normalization::Smart);
 
-        let mut results: Vec<(u64, u32)> = clips
+        let mut results: Vec<(u64, u32)> = self
+            .haystacks
             .iter()
-            .filter_map(|clip| {
-                // Build searchable text from clip
-                let search_text = clip.searchable_text();
-
+            .filter_map(|haystack| {
                 // Convert to UTF-32 for nucleo
-                let utf32_text = Utf32String::from(search_text.as_str());
+                let utf32_text = Utf32String::from(haystack.text.as_str());
 
                 // Match pattern against clip text
                 pattern
                     .score(utf32_text.slice(..), &mut self.matcher)
-                    .map(|score| (clip.id, score))
+                    .map(|score| (haystack.id, score))
             })
             .collect();
 
         // Sort by score descending (higher scores = better matches at index 0)
-        results.sort_by(|a, b| b.1.cmp(&a.1));
+        results.sort_by_key(|r| std::cmp::Reverse(r.1));
 
         results
     }
+
+    /// Start a search for the TUI without blocking on large histories
+    /// Fuzzy queries run on nucleo's thread pool and return None; collect their
+    /// results with `poll_results`. Other modes and empty queries return results directly
+    pub fn search_background(
+        &mut self,
+        clips: &[ClipEntry],
+        query: &str,
+    ) -> Option<Vec<(u64, u32)>> {
+        if query.is_empty() || self.match_mode != MatchMode::Fuzzy {
+            self.cancel_background();
+            return Some(self.search(clips, query));
+        }
+        self.regex_error = None;
+
+        let changed = self.sync_haystacks(clips);
+        let fresh = self.background.is_none();
+        let case_matching = self.case_matching();
+        let background = self.background.get_or_insert_with(|| BackgroundSearch {
+            nucleo: Nucleo::new(nucleo::Config::DEFAULT, Arc::new(|| {}), None, 1),
+            query: String::new(),
+            mode: SearchMode::default(),
+            active: false,
+        });
+
+        // Re-inject every clip when history changed (nucleo can't remove items)
+        if changed || fresh {
+            background.nucleo.restart(true);
+            let injector = background.nucleo.injector();
+            for haystack in &self.haystacks {
+                injector.push(haystack.id, |_, columns| {
+                    columns[0] = Utf32String::from(haystack.text.as_str());
+                });
+            }
+            background.query.clear();
+        }
+
+        // Typing more of the same query only needs to re-check previous matches
+        let append = !background.query.is_empty()
+            && background.mode == self.mode
+            && query.starts_with(&background.query)
+            && !background.query.ends_with('\\');
+        background
+            .nucleo
+            .pattern
+            .reparse(0, query, case_matching, Normalization::Smart, append);
+        background.query = query.to_string();
+        background.mode = self.mode;
+        background.active = true;
+
+        None
+    }
+
+    /// Collect results of the background search if they changed since the last poll
+    /// Returns clip IDs, best matches first
+    pub fn poll_results(&mut self) -> Option<Vec<u64>> {
+        let background = self.background.as_mut().filter(|b| b.active)?;
+        let status = background.nucleo.tick(SEARCH_TICK_MS);
+        if !status.changed {
+            return None;
+        }
+
+        let snapshot = background.nucleo.snapshot();
+        Some(snapshot.matched_items(..).map(|item| *item.data).collect())
+    }
+
+    /// Stop collecting background results (e.g. when the search is cleared)
+    pub fn cancel_background(&mut self) {
+        if let Some(background) = &mut self.background {
+            background.active = false;
+        }
+    }
+
+    /// Update cached haystacks to match the clips, reusing unchanged text
+    /// Returns true if anything changed
+    fn sync_haystacks(&mut self, clips: &[ClipEntry]) -> bool {
+        let unchanged = self.haystacks.len() == clips.len()
+            && self
+                .haystacks
+                .iter()
+                .zip(clips)
+                .all(|(h, c)| h.id == c.id && h.signature == c.search_signature());
+        if unchanged {
+            return false;
+        }
+
+        let mut old: std::collections::HashMap<u64, Haystack> =
+            self.haystacks.drain(..).map(|h| (h.id, h)).collect();
+        self.haystacks = clips
+            .iter()
+            .map(|clip| {
+                let signature = clip.search_signature();
+                match old.remove(&clip.id) {
+                    Some(h) if h.signature == signature => h,
+                    _ => {
+                        let text = clip.searchable_text();
+                        Haystack {
+                            id: clip.id,
+                            signature,
+                            lower: text.to_lowercase(),
+                            text,
+                        }
+                    }
+                }
+            })
+            .collect();
+        true
+    }
+}
+
+/// How a highlighter locates matches
+enum HighlightKind {
+    Fuzzy(Pattern),
+    Exact(Regex),
+    Regex(Regex),
+}
+
+/// Finds the characters matched by a search query in displayed text
+/// (list previews, preview panel lines), which may differ from the searched text
+pub struct Highlighter {
+    matcher: Matcher,
+    kind: HighlightKind,
+}
+
+impl Highlighter {
+    /// Char indices of matched characters in `text`, sorted and deduplicated
+    /// Fuzzy atoms are matched independently, so a partial match is still shown
+    pub fn indices(&mut self, text: &str) -> Vec<usize> {
+        match &self.kind {
+            HighlightKind::Fuzzy(pattern) => {
+                let chars: Vec<char> = text.chars().collect();
+                let mut indices = Vec::new();
+                for atom in pattern.atoms.iter().filter(|a| !a.negative) {
+                    atom.indices(Utf32Str::Unicode(&chars), &mut self.matcher, &mut indices);
+                }
+                let mut indices: Vec<usize> = indices.into_iter().map(|i| i as usize).collect();
+                indices.sort_unstable();
+                indices.dedup();
+                indices
+            }
+            HighlightKind::Exact(regex) | HighlightKind::Regex(regex) => {
+                let mut indices = Vec::new();
+                for m in regex.find_iter(text) {
+                    let start = text[..m.start()].chars().count();
+                    let len = m.as_str().chars().count();
+                    indices.extend(start..start + len);
+                }
+                indices
+            }
+        }
+    }
+}
+
+/// Keep clips whose searchable text (lowercased if `lowercase`) satisfies a predicate, in history order
+/// All matches get the same score, so results stay sorted by recency
+fn filter_clips(
+    haystacks: &[Haystack],
+    lowercase: bool,
+    is_match: impl Fn(&str) -> bool,
+) -> Vec<(u64, u32)> {
+    haystacks
+        .iter()
+        .filter(|h| is_match(if lowercase { &h.lower } else { &h.text }))
+        .map(|h| (h.id, u32::MAX))
+        .collect()
 }
 
 impl Default for SearchIndex {
@@ -95,6 +460,16 @@ impl Default for SearchIndex {
 }
 
 impl ClipEntry {
+    /// Hash of the fields `searchable_text` is built from
+    fn search_signature(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.content_hash.hash(&mut hasher);
+        self.name.hash(&mut hasher);
+        self.description.hash(&mut hasher);
+        self.tags.hash(&mut hasher);
+        hasher.finish()
+    }
+
     /// Get searchable text representation of this clip
     /// Used by fuzzy search to match against
     fn searchable_text(&self) -> String {
@@ -114,6 +489,13 @@ impl ClipEntry {
             text.push(' ');
         }
 
+        // Include tags
+        for tag in &self.tags {
+            text.push('#');
+            text.push_str(tag);
+            text.push(' ');
+        }
+
         // Include content preview
         match &self.content {
             ClipContent::Text(t) => {
@@ -161,7 +543,7 @@ mod tests {
         ];
 
         let results = index.search(&clips, "hello");
-        assert!(results.len() >= 1);
+        assert!(!results.is_empty());
         assert_eq!(results[0].0, clips[0].id); // First result should be "hello world"
     }
 
@@ -202,4 +584,99 @@ mod tests {
         let results = index.search(&clips, "my_clip");
         assert_eq!(results.len(), 1);
     }
+
+    #[test]
+    fn test_exact_and_regex_modes() {
+        let mut index = SearchIndex::new();
+        let clips = vec![
+            ClipEntry::new_text(1, "id 550e8400-e29b".to_string()),
+            ClipEntry::new_text(2, "5 5 0 e 8 4".to_string()),
+        ];
+
+        // Fuzzy matches both, exact only the real substring
+        assert_eq!(index.search(&clips, "550e84").len(), 2);
+        index.set_match_mode(MatchMode::Exact);
+        let results = index.search(&clips, "550E84");
+        assert!(results.is_empty()); // smart case: uppercase query is case-sensitive
+        assert_eq!(index.search(&clips, "550e84")[0].0, 1);
+
+        index.set_match_mode(MatchMode::Regex);
+        assert_eq!(index.search(&clips, r"[0-9a-f]{8}-").len(), 1);
+        assert!(index.search(&clips, "(unclosed").is_empty());
+        assert!(index.regex_error().is_some());
+    }
+
+    #[test]
+    fn test_word_prefix_mode() {
+        let mut index = SearchIndex::new();
+        let clips = vec![
+            ClipEntry::new_text(1, "Error: connection refused".to_string()),
+            ClipEntry::new_text(2, "reconnect later".to_string()),
+        ];
+
+        index.set_match_mode(MatchMode::WordPrefix);
+        let results = index.search(&clips, "conn ref");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, 1);
+
+        index.cycle_match_mode();
+        assert_eq!(index.match_mode(), MatchMode::Fuzzy);
+    }
+
+    #[test]
+    fn test_highlighter_indices() {
+        let mut index = SearchIndex::new();
+        assert!(index.highlighter("").is_none());
+
+        let mut highlighter = index.highlighter("hlo").unwrap();
+        assert_eq!(highlighter.indices("hello"), vec![0, 3, 4]);
+
+        index.set_match_mode(MatchMode::Exact);
+        let mut highlighter = index.highlighter("lo").unwrap();
+        assert_eq!(highlighter.indices("éllo lo"), vec![2, 3, 5, 6]);
+
+        index.set_match_mode(MatchMode::WordPrefix);
+        let mut highlighter = index.highlighter("con ref").unwrap();
+        assert_eq!(highlighter.indices("reconnect, refused"), vec![11, 12, 13]);
+
+        index.set_match_mode(MatchMode::Regex);
+        assert!(index.highlighter("(").is_none());
+    }
+
+    #[test]
+    fn test_background_search() {
+        let mut index = SearchIndex::new();
+        let mut clips = vec![
+            ClipEntry::new_text(1, "hello world".to_string()),
+            ClipEntry::new_text(2, "goodbye world".to_string()),
+        ];
+
+        let poll = |index: &mut SearchIndex| {
+            for _ in 0..1000 {
+                if let Some(ids) = index.poll_results() {
+                    return ids;
+                }
+            }
+            panic!("background search produced no results");
+        };
+
+        assert!(index.search_background(&clips, "wor").is_none());
+        assert_eq!(poll(&mut index).len(), 2);
+
+        // Narrowing the query
+        index.search_background(&clips, "world hel");
+        assert_eq!(poll(&mut index), vec![1]);
+
+        // Changed clips are re-injected
+        clips.push(ClipEntry::new_text(3, "hello again".to_string()));
+        index.search_background(&clips, "hel");
+        let mut ids = poll(&mut index);
+        ids.sort();
+        assert_eq!(ids, vec![1, 3]);
+
+        // Non-fuzzy modes answer immediately and stop background polling
+        index.set_match_mode(MatchMode::Exact);
+        assert_eq!(index.search_background(&clips, "again").unwrap().len(), 1);
+        assert!(index.poll_results().is_none());
+    }
 }
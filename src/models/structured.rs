@@ -0,0 +1,654 @@
+use std::fmt;
+
+use super::clip::{ClipContent, ClipEntry};
+use super::syntax::Language;
+
+/// Clips larger than this are not parsed for the formatted preview
+pub const MAX_FORMAT_BYTES: usize = 1024 * 1024;
+
+/// Deepest nesting of arrays, objects or elements that is parsed
+/// Keeps the recursive parser and printers well within the stack
+const MAX_NESTING_DEPTH: usize = 256;
+
+/// Indentation used for pretty-printed output
+const INDENT: &str = "  ";
+
+/// Structured text format that can be pretty-printed and minified
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructuredFormat {
+    Json,
+    Xml,
+}
+
+/// Parse error with a 1-based position in the source text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl StructuredFormat {
+    /// Short lowercase name
+    pub fn name(&self) -> &'static str {
+        match self {
+            StructuredFormat::Json => "json",
+            StructuredFormat::Xml => "xml",
+        }
+    }
+
+    /// Language used to syntax highlight the formatted text
+    pub fn language(&self) -> Language {
+        match self {
+            StructuredFormat::Json => Language::Json,
+            StructuredFormat::Xml => Language::Html,
+        }
+    }
+
+    /// Format of a text clip, if it looks like JSON or XML (hints via tags/name apply)
+    pub fn for_entry(entry: &ClipEntry) -> Option<Self> {
+        let ClipContent::Text(text) = &entry.content else {
+            return None;
+        };
+        if text.len() > MAX_FORMAT_BYTES {
+            return None;
+        }
+
+        match Language::hinted(entry).or_else(|| Language::detect(text))? {
+            Language::Json => Some(StructuredFormat::Json),
+            Language::Html => Some(StructuredFormat::Xml),
+            _ => None,
+        }
+    }
+
+    /// Pretty-print with two-space indentation
+    /// Nesting deeper than `fold_depth` is collapsed to `{…}` / `<tag>…</tag>`
+    pub fn pretty(&self, text: &str, fold_depth: Option<usize>) -> Result<String, FormatError> {
+        let mut out = String::with_capacity(text.len() * 2);
+        match self {
+            StructuredFormat::Json => JsonParser::new(text)
+                .parse()?
+                .pretty(&mut out, 0, fold_depth),
+            StructuredFormat::Xml => {
+                for node in XmlParser::new(text).parse()? {
+                    node.pretty(&mut out, 0, fold_depth);
+                }
+            }
+        }
+        if out.ends_with('\n') {
+            out.pop();
+        }
+        Ok(out)
+    }
+
+    /// Remove all insignificant whitespace
+    pub fn minify(&self, text: &str) -> Result<String, FormatError> {
+        let mut out = String::with_capacity(text.len());
+        match self {
+            StructuredFormat::Json => JsonParser::new(text).parse()?.minify(&mut out),
+            StructuredFormat::Xml => {
+                for node in XmlParser::new(text).parse()? {
+                    node.minify(&mut out);
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Convert a byte offset to a 1-based line and column (in chars)
+fn position(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |at| at + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+fn push_indent(out: &mut String, depth: usize) {
+    for _ in 0..depth {
+        out.push_str(INDENT);
+    }
+}
+
+/// Parsed JSON value; scalars keep their source text so numbers aren't reformatted
+enum JsonValue<'a> {
+    Scalar(&'a str),
+    Array(Vec<JsonValue<'a>>),
+    Object(Vec<(&'a str, JsonValue<'a>)>),
+}
+
+impl JsonValue<'_> {
+    fn pretty(&self, out: &mut String, depth: usize, fold_depth: Option<usize>) {
+        let folded = fold_depth.is_some_and(|fold| depth >= fold);
+        match self {
+            JsonValue::Scalar(raw) => out.push_str(raw),
+            JsonValue::Array(items) if items.is_empty() => out.push_str("[]"),
+            JsonValue::Object(fields) if fields.is_empty() => out.push_str("{}"),
+            JsonValue::Array(items) if folded => {
+                out.push_str(&format!("[… {} items]", items.len()))
+            }
+            JsonValue::Object(fields) if folded => {
+                out.push_str(&format!("{{… {} keys}}", fields.len()))
+            }
+            JsonValue::Array(items) => {
+                out.push_str("[\n");
+                for (i, item) in items.iter().enumerate() {
+                    push_indent(out, depth + 1);
+                    item.pretty(out, depth + 1, fold_depth);
+                    out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+                }
+                push_indent(out, depth);
+                out.push(']');
+            }
+            JsonValue::Object(fields) => {
+                out.push_str("{\n");
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    push_indent(out, depth + 1);
+                    out.push_str(key);
+                    out.push_str(": ");
+                    value.pretty(out, depth + 1, fold_depth);
+                    out.push_str(if i + 1 < fields.len() { ",\n" } else { "\n" });
+                }
+                push_indent(out, depth);
+                out.push('}');
+            }
+        }
+    }
+
+    fn minify(&self, out: &mut String) {
+        match self {
+            JsonValue::Scalar(raw) => out.push_str(raw),
+            JsonValue::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    item.minify(out);
+                }
+                out.push(']');
+            }
+            JsonValue::Object(fields) => {
+                out.push('{');
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    out.push_str(key);
+                    out.push(':');
+                    value.minify(out);
+                }
+                out.push('}');
+            }
+        }
+    }
+}
+
+/// Validating recursive-descent JSON parser
+struct JsonParser<'a> {
+    text: &'a str,
+    pos: usize,
+    /// Number of arrays and objects currently open
+    depth: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> FormatError {
+        let (line, column) = position(self.text, self.pos);
+        FormatError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), FormatError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(found) if found == c => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(found) => Err(self.error(format!("expected '{}', found '{}'", c, found))),
+            None => Err(self.error(format!("expected '{}', found end of input", c))),
+        }
+    }
+
+    fn parse(mut self) -> Result<JsonValue<'a>, FormatError> {
+        let value = self.value()?;
+        self.skip_whitespace();
+        match self.peek() {
+            None => Ok(value),
+            Some(c) => Err(self.error(format!("unexpected '{}' after value", c))),
+        }
+    }
+
+    fn value(&mut self) -> Result<JsonValue<'a>, FormatError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c @ ('{' | '[')) => {
+                if self.depth == MAX_NESTING_DEPTH {
+                    return Err(
+                        self.error(format!("nested deeper than {} levels", MAX_NESTING_DEPTH))
+                    );
+                }
+                self.depth += 1;
+                let value = if c == '{' {
+                    self.object()
+                } else {
+                    self.array()
+                };
+                self.depth -= 1;
+                value
+            }
+            Some('"') => Ok(JsonValue::Scalar(self.string()?)),
+            Some(c) if c == '-' || c.is_ascii_digit() => Ok(JsonValue::Scalar(self.number()?)),
+            Some(_) => {
+                let rest = &self.text[self.pos..];
+                for literal in ["true", "false", "null"] {
+                    if rest.starts_with(literal) {
+                        self.pos += literal.len();
+                        return Ok(JsonValue::Scalar(literal));
+                    }
+                }
+                Err(self.error("expected a value"))
+            }
+            None => Err(self.error("expected a value, found end of input")),
+        }
+    }
+
+    fn object(&mut self) -> Result<JsonValue<'a>, FormatError> {
+        self.pos += 1;
+        let mut fields = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(JsonValue::Object(fields));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.error("expected a string key"));
+            }
+            let key = self.string()?;
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::Object(fields));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<JsonValue<'a>, FormatError> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(JsonValue::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::Array(items));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    /// Parse a string, returning its source text including quotes
+    fn string(&mut self) -> Result<&'a str, FormatError> {
+        let start = self.pos;
+        let mut escaped = false;
+        for (at, c) in self.text[start + 1..].char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => {
+                    self.pos = start + 1 + at + 1;
+                    return Ok(&self.text[start..self.pos]);
+                }
+                '\n' => {
+                    self.pos = start + 1 + at;
+                    return Err(self.error("unterminated string"));
+                }
+                _ => {}
+            }
+        }
+        self.pos = self.text.len();
+        Err(self.error("unterminated string"))
+    }
+
+    fn number(&mut self) -> Result<&'a str, FormatError> {
+        let start = self.pos;
+        let rest = &self.text[start..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')))
+            .unwrap_or(rest.len());
+        let raw = &rest[..len];
+        if raw.parse::<f64>().is_err() || raw.ends_with('.') || raw.starts_with('+') {
+            return Err(self.error(format!("invalid number '{}'", raw)));
+        }
+        self.pos += len;
+        Ok(raw)
+    }
+}
+
+/// HTML elements that never have a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Parsed XML node; tags keep their source text
+enum XmlNode<'a> {
+    Element {
+        open: &'a str,
+        name: &'a str,
+        children: Vec<XmlNode<'a>>,
+    },
+    /// Self-closing or void element, comment, declaration or CDATA section
+    Raw(&'a str),
+    Text(&'a str),
+}
+
+impl XmlNode<'_> {
+    fn pretty(&self, out: &mut String, depth: usize, fold_depth: Option<usize>) {
+        push_indent(out, depth);
+        match self {
+            XmlNode::Raw(raw) => out.push_str(raw),
+            XmlNode::Text(text) => out.push_str(text),
+            XmlNode::Element {
+                open,
+                name,
+                children,
+            } => {
+                out.push_str(open);
+                match children.as_slice() {
+                    [] => {}
+                    [XmlNode::Text(text)] => out.push_str(text),
+                    _ if fold_depth.is_some_and(|fold| depth >= fold) => out.push('…'),
+                    children => {
+                        out.push('\n');
+                        for child in children {
+                            child.pretty(out, depth + 1, fold_depth);
+                        }
+                        push_indent(out, depth);
+                    }
+                }
+                out.push_str(&format!("</{}>", name));
+            }
+        }
+        out.push('\n');
+    }
+
+    fn minify(&self, out: &mut String) {
+        match self {
+            XmlNode::Raw(raw) | XmlNode::Text(raw) => out.push_str(raw),
+            XmlNode::Element {
+                open,
+                name,
+                children,
+            } => {
+                out.push_str(open);
+                for child in children {
+                    child.minify(out);
+                }
+                out.push_str(&format!("</{}>", name));
+            }
+        }
+    }
+}
+
+/// Validating XML parser (lenient about HTML void elements)
+struct XmlParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> XmlParser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, pos: 0 }
+    }
+
+    fn error_at(&self, offset: usize, message: impl Into<String>) -> FormatError {
+        let (line, column) = position(self.text, offset);
+        FormatError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    fn parse(mut self) -> Result<Vec<XmlNode<'a>>, FormatError> {
+        let mut stack: Vec<(usize, &'a str, &'a str, Vec<XmlNode<'a>>)> = Vec::new();
+        let mut nodes = Vec::new();
+
+        while self.pos < self.text.len() {
+            let rest = &self.text[self.pos..];
+            let start = self.pos;
+
+            let node = if !rest.starts_with('<') {
+                let len = rest.find('<').unwrap_or(rest.len());
+                self.pos += len;
+                let text = rest[..len].trim();
+                if text.is_empty() {
+                    continue;
+                }
+                XmlNode::Text(text)
+            } else if let Some(close) = [("<!--", "-->"), ("<![CDATA[", "]]>"), ("<?", "?>")]
+                .iter()
+                .find(|(open, _)| rest.starts_with(open))
+                .map(|(_, close)| *close)
+            {
+                let end = rest
+                    .find(close)
+                    .ok_or_else(|| self.error_at(start, "unterminated comment or declaration"))?;
+                self.pos += end + close.len();
+                XmlNode::Raw(&rest[..end + close.len()])
+            } else {
+                let len = self.tag_len(rest, start)?;
+                self.pos += len;
+                let tag = &rest[..len];
+
+                if let Some(closing) = tag.strip_prefix("</") {
+                    let name = closing.trim_end_matches('>').trim();
+                    let Some((open_at, open, open_name, children)) = stack.pop() else {
+                        return Err(self.error_at(start, format!("unexpected </{}>", name)));
+                    };
+                    if open_name != name {
+                        let (line, column) = position(self.text, open_at);
+                        return Err(self.error_at(
+                            start,
+                            format!(
+                                "expected </{}> (opened at line {}, column {}), found </{}>",
+                                open_name, line, column, name
+                            ),
+                        ));
+                    }
+                    XmlNode::Element {
+                        open,
+                        name,
+                        children,
+                    }
+                } else {
+                    let name_len = tag[1..]
+                        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                        .unwrap_or(tag.len() - 1);
+                    let name = &tag[1..1 + name_len];
+                    if name.is_empty() {
+                        return Err(self.error_at(start, "expected a tag name"));
+                    }
+                    if tag.ends_with("/>")
+                        || tag.starts_with("<!")
+                        || VOID_ELEMENTS.contains(&name.to_lowercase().as_str())
+                    {
+                        XmlNode::Raw(tag)
+                    } else if stack.len() == MAX_NESTING_DEPTH {
+                        return Err(self.error_at(
+                            start,
+                            format!("nested deeper than {} levels", MAX_NESTING_DEPTH),
+                        ));
+                    } else {
+                        stack.push((start, tag, name, Vec::new()));
+                        continue;
+                    }
+                }
+            };
+
+            match stack.last_mut() {
+                Some((_, _, _, children)) => children.push(node),
+                None => nodes.push(node),
+            }
+        }
+
+        if let Some((open_at, _, name, _)) = stack.pop() {
+            return Err(self.error_at(open_at, format!("<{}> is never closed", name)));
+        }
+        if nodes.is_empty() {
+            return Err(self.error_at(0, "no elements"));
+        }
+        Ok(nodes)
+    }
+
+    /// Length of the tag at the start of `rest`, skipping `>` inside quoted attributes
+    fn tag_len(&self, rest: &str, start: usize) -> Result<usize, FormatError> {
+        let mut quote = None;
+        for (at, c) in rest.char_indices() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, '"' | '\'') => quote = Some(c),
+                (None, '>') => return Ok(at + 1),
+                _ => {}
+            }
+        }
+        Err(self.error_at(start, "unterminated tag"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_pretty_and_minify() {
+        let json = StructuredFormat::Json;
+        let text = r#"{"b":1.50,"a":[true,null,{}],"s":"x\"y"}"#;
+
+        assert_eq!(
+            json.pretty(text, None).unwrap(),
+            "{\n  \"b\": 1.50,\n  \"a\": [\n    true,\n    null,\n    {}\n  ],\n  \"s\": \"x\\\"y\"\n}"
+        );
+        assert_eq!(
+            json.pretty(text, Some(1)).unwrap(),
+            "{\n  \"b\": 1.50,\n  \"a\": [… 3 items],\n  \"s\": \"x\\\"y\"\n}"
+        );
+        let pretty = json.pretty(text, None).unwrap();
+        assert_eq!(json.minify(&pretty).unwrap(), text);
+    }
+
+    #[test]
+    fn test_json_errors() {
+        let json = StructuredFormat::Json;
+        let error = json.minify("{\n  \"a\": 1,\n  \"b\" 2\n}").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 7));
+        assert_eq!(error.message, "expected ':', found '2'");
+
+        assert!(json.minify("[1, 2,]").is_err());
+        assert!(json.minify("{\"a\": 01x}").is_err());
+        assert!(json.minify("[1] 2").is_err());
+
+        let nested = "[".repeat(MAX_NESTING_DEPTH) + &"]".repeat(MAX_NESTING_DEPTH);
+        assert!(json.pretty(&nested, None).is_ok());
+        let too_deep = "[{\"a\":".repeat(MAX_NESTING_DEPTH);
+        let error = json.pretty(&too_deep, None).unwrap_err();
+        assert_eq!(error.message, "nested deeper than 256 levels");
+    }
+
+    #[test]
+    fn test_xml() {
+        let xml = StructuredFormat::Xml;
+        let text = "<?xml version=\"1.0\"?><a x=\"1>2\"><b>hi</b><br><c/><!-- note --></a>";
+        assert_eq!(
+            xml.pretty(text, None).unwrap(),
+            "<?xml version=\"1.0\"?>\n<a x=\"1>2\">\n  <b>hi</b>\n  <br>\n  <c/>\n  <!-- note -->\n</a>"
+        );
+        assert_eq!(
+            xml.pretty(text, Some(0)).unwrap(),
+            "<?xml version=\"1.0\"?>\n<a x=\"1>2\">…</a>"
+        );
+        assert_eq!(xml.minify(&xml.pretty(text, None).unwrap()).unwrap(), text);
+
+        let error = xml.minify("<a>\n<b></a>").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert!(error.message.starts_with("expected </b>"));
+        assert!(xml.minify("<a><b></b>").is_err());
+
+        let nested = "<a>".repeat(MAX_NESTING_DEPTH) + &"</a>".repeat(MAX_NESTING_DEPTH);
+        assert!(xml.pretty(&nested, None).is_ok());
+        let error = xml.pretty(&"<a>".repeat(100_000), None).unwrap_err();
+        assert_eq!(error.message, "nested deeper than 256 levels");
+    }
+
+    #[test]
+    fn test_for_entry() {
+        let mut entry = ClipEntry::new_text(1, "{\"a\": 1}".to_string());
+        assert_eq!(
+            StructuredFormat::for_entry(&entry),
+            Some(StructuredFormat::Json)
+        );
+
+        entry.content = ClipContent::Text("fn main() {}".to_string());
+        assert_eq!(StructuredFormat::for_entry(&entry), None);
+
+        entry.name = Some("feed.xml".to_string());
+        assert_eq!(
+            StructuredFormat::for_entry(&entry),
+            Some(StructuredFormat::Xml)
+        );
+    }
+}
@@ -0,0 +1,1444 @@
+use super::clip::{ClipContent, ClipEntry};
+
+/// Clips larger than this are previewed as plain text (highlighting runs on every draw)
+pub const MAX_HIGHLIGHT_BYTES: usize = 64 * 1024;
+
+/// Number of leading lines looked at when guessing the language
+const DETECT_LINES: usize = 50;
+
+/// Minimum heuristic score before a clip is treated as code
+const DETECT_MIN_SCORE: usize = 2;
+
+/// Language of a code clip, for syntax highlighting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    Go,
+    C,
+    Shell,
+    Sql,
+    Json,
+    Yaml,
+    Toml,
+    Html,
+}
+
+/// Kind of highlighted token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Keyword,
+    Type,
+    Function,
+    String,
+    Number,
+    Constant,
+    Comment,
+    Variable,
+}
+
+/// A highlighted byte range of a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub start: usize,
+    pub end: usize,
+    pub kind: TokenKind,
+}
+
+/// Lexical rules for a language
+struct Syntax {
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    keywords: &'static [&'static str],
+    constants: &'static [&'static str],
+    types: &'static [&'static str],
+    /// Keywords match regardless of case (SQL)
+    ignore_case: bool,
+    /// Capitalized identifiers are types
+    capitalized_types: bool,
+}
+
+const RUST: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"'],
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+        "pub", "ref", "return", "self", "static", "struct", "super", "trait", "type", "unsafe",
+        "use", "where", "while",
+    ],
+    constants: &["true", "false", "None", "Some", "Ok", "Err"],
+    types: &[
+        "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32",
+        "i64", "i128", "isize", "f32", "f64",
+    ],
+    ignore_case: false,
+    capitalized_types: true,
+};
+
+const PYTHON: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comment: Some(("\"\"\"", "\"\"\"")),
+    quotes: &['"', '\''],
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+        "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is",
+        "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while", "with",
+        "yield",
+    ],
+    constants: &["True", "False", "None", "self"],
+    types: &[
+        "int", "str", "float", "bool", "list", "dict", "set", "tuple", "bytes",
+    ],
+    ignore_case: false,
+    capitalized_types: true,
+};
+
+const JAVASCRIPT: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`'],
+    keywords: &[
+        "async",
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "export",
+        "extends",
+        "finally",
+        "for",
+        "from",
+        "function",
+        "if",
+        "implements",
+        "import",
+        "in",
+        "instanceof",
+        "interface",
+        "let",
+        "new",
+        "of",
+        "return",
+        "static",
+        "super",
+        "switch",
+        "this",
+        "throw",
+        "try",
+        "type",
+        "typeof",
+        "var",
+        "void",
+        "while",
+        "yield",
+    ],
+    constants: &["true", "false", "null", "undefined", "NaN"],
+    types: &["string", "number", "boolean", "any", "unknown", "never"],
+    ignore_case: false,
+    capitalized_types: true,
+};
+
+const GO: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '`'],
+    keywords: &[
+        "break",
+        "case",
+        "chan",
+        "const",
+        "continue",
+        "default",
+        "defer",
+        "else",
+        "fallthrough",
+        "for",
+        "func",
+        "go",
+        "goto",
+        "if",
+        "import",
+        "interface",
+        "map",
+        "package",
+        "range",
+        "return",
+        "select",
+        "struct",
+        "switch",
+        "type",
+        "var",
+    ],
+    constants: &["true", "false", "nil", "iota"],
+    types: &[
+        "bool", "byte", "error", "float32", "float64", "int", "int8", "int16", "int32", "int64",
+        "rune", "string", "uint", "uint8", "uint16", "uint32", "uint64", "any",
+    ],
+    ignore_case: false,
+    capitalized_types: true,
+};
+
+const C: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+    keywords: &[
+        "auto",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "extends",
+        "extern",
+        "final",
+        "for",
+        "if",
+        "implements",
+        "import",
+        "namespace",
+        "new",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "return",
+        "sizeof",
+        "static",
+        "struct",
+        "switch",
+        "template",
+        "this",
+        "throw",
+        "try",
+        "typedef",
+        "union",
+        "using",
+        "virtual",
+        "volatile",
+        "while",
+    ],
+    constants: &["true", "false", "NULL", "nullptr", "null"],
+    types: &[
+        "bool", "char", "double", "float", "int", "long", "short", "signed", "unsigned", "void",
+        "size_t",
+    ],
+    ignore_case: false,
+    capitalized_types: true,
+};
+
+const SHELL: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    keywords: &[
+        "if", "then", "else", "elif", "fi", "for", "while", "until", "do", "done", "case", "esac",
+        "in", "function", "return", "export", "local", "readonly", "unset", "sudo",
+    ],
+    constants: &["true", "false"],
+    types: &[],
+    ignore_case: false,
+    capitalized_types: false,
+};
+
+const SQL: Syntax = Syntax {
+    line_comments: &["--"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['\'', '"'],
+    keywords: &[
+        "select",
+        "from",
+        "where",
+        "insert",
+        "into",
+        "values",
+        "update",
+        "set",
+        "delete",
+        "create",
+        "table",
+        "alter",
+        "drop",
+        "index",
+        "join",
+        "left",
+        "right",
+        "inner",
+        "outer",
+        "on",
+        "and",
+        "or",
+        "not",
+        "as",
+        "group",
+        "by",
+        "order",
+        "having",
+        "limit",
+        "offset",
+        "distinct",
+        "union",
+        "all",
+        "case",
+        "when",
+        "then",
+        "else",
+        "end",
+        "is",
+        "in",
+        "like",
+        "between",
+        "exists",
+        "primary",
+        "key",
+        "foreign",
+        "references",
+        "default",
+        "returning",
+        "with",
+        "asc",
+        "desc",
+    ],
+    constants: &["null", "true", "false"],
+    types: &[
+        "int",
+        "integer",
+        "bigint",
+        "text",
+        "varchar",
+        "char",
+        "boolean",
+        "date",
+        "timestamp",
+        "real",
+        "numeric",
+        "serial",
+        "uuid",
+        "jsonb",
+    ],
+    ignore_case: true,
+    capitalized_types: false,
+};
+
+const JSON: Syntax = Syntax {
+    line_comments: &[],
+    block_comment: None,
+    quotes: &['"'],
+    keywords: &[],
+    constants: &["true", "false", "null"],
+    types: &[],
+    ignore_case: false,
+    capitalized_types: false,
+};
+
+const YAML: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    keywords: &[],
+    constants: &["true", "false", "null", "yes", "no", "on", "off"],
+    types: &[],
+    ignore_case: false,
+    capitalized_types: false,
+};
+
+const TOML: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    keywords: &[],
+    constants: &["true", "false"],
+    types: &[],
+    ignore_case: false,
+    capitalized_types: false,
+};
+
+/// Markup has no keyword rules: tags, attributes and comments are tokenized separately
+const MARKUP: Syntax = Syntax {
+    line_comments: &[],
+    block_comment: None,
+    quotes: &[],
+    keywords: &[],
+    constants: &[],
+    types: &[],
+    ignore_case: false,
+    capitalized_types: false,
+};
+
+/// Words that start a shell command line, used to spot one-line commands
+const SHELL_COMMANDS: &[&str] = &[
+    "sudo",
+    "git",
+    "cargo",
+    "docker",
+    "kubectl",
+    "npm",
+    "npx",
+    "yarn",
+    "pnpm",
+    "pip",
+    "cd",
+    "ls",
+    "cat",
+    "grep",
+    "rg",
+    "find",
+    "curl",
+    "wget",
+    "ssh",
+    "scp",
+    "rsync",
+    "echo",
+    "export",
+    "mkdir",
+    "rm",
+    "cp",
+    "mv",
+    "chmod",
+    "chown",
+    "tar",
+    "systemctl",
+    "journalctl",
+    "apt",
+    "dnf",
+    "pacman",
+    "brew",
+    "make",
+    "go",
+    "python",
+    "python3",
+    "node",
+    "awk",
+    "sed",
+    "xargs",
+    "tail",
+    "head",
+    "wl-copy",
+    "wl-paste",
+];
+
+/// Statement openers and a clause that must also appear, so prose starting with
+/// "update " or "with " is not taken for SQL
+const SQL_STATEMENTS: &[(&str, &str)] = &[
+    ("select ", " from "),
+    ("insert into ", " values"),
+    ("update ", " set "),
+    ("delete from ", ""),
+    ("create table ", "("),
+    ("with ", " as ("),
+];
+
+/// Substrings that suggest a language, worth one point per line they appear on
+const SIGNALS: &[(Language, &[&str])] = &[
+    (
+        Language::Rust,
+        &[
+            "fn ", "let mut ", "impl ", "pub ", "::", "-> ", "#[", "&self", "println!", "match ",
+            "use std", "Option<", "Result<",
+        ],
+    ),
+    (
+        Language::Python,
+        &[
+            "def ", "import ", "self.", "elif ", "print(", "__init__", "None", "lambda ", "):",
+        ],
+    ),
+    (
+        Language::JavaScript,
+        &[
+            "const ",
+            "function ",
+            "=> ",
+            "console.",
+            "===",
+            "require(",
+            "export ",
+            "undefined",
+            "document.",
+        ],
+    ),
+    (
+        Language::Go,
+        &[
+            "func ",
+            "package ",
+            ":= ",
+            "fmt.",
+            "chan ",
+            "defer ",
+            "err != nil",
+        ],
+    ),
+    (
+        Language::C,
+        &[
+            "#include",
+            "int main",
+            "printf(",
+            "std::",
+            "void ",
+            "NULL",
+            "public static",
+            "System.out",
+            "#define",
+        ],
+    ),
+    (
+        Language::Shell,
+        &[" | ", " && ", "$(", "${", " || ", " 2>&1", " > /dev/null"],
+    ),
+];
+
+impl Language {
+    /// Parse a language from a name, alias or file extension (e.g. "rust", "rs", "lang:py")
+    pub fn from_hint(hint: &str) -> Option<Self> {
+        let hint = hint.trim().to_lowercase();
+        let hint = hint.strip_prefix("lang:").unwrap_or(&hint);
+        let language = match hint {
+            "rust" | "rs" => Language::Rust,
+            "python" | "py" => Language::Python,
+            "javascript" | "js" | "jsx" | "mjs" | "typescript" | "ts" | "tsx" => {
+                Language::JavaScript
+            }
+            "go" | "golang" => Language::Go,
+            "c" | "h" | "cpp" | "c++" | "cc" | "hpp" | "java" | "cs" | "csharp" => Language::C,
+            "sh" | "bash" | "zsh" | "shell" | "fish" => Language::Shell,
+            "sql" => Language::Sql,
+            "json" => Language::Json,
+            "yaml" | "yml" => Language::Yaml,
+            "toml" => Language::Toml,
+            "html" | "htm" | "xml" | "svg" => Language::Html,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    /// Short lowercase name (shown in the preview metadata)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::Python => "python",
+            Language::JavaScript => "javascript",
+            Language::Go => "go",
+            Language::C => "c",
+            Language::Shell => "shell",
+            Language::Sql => "sql",
+            Language::Json => "json",
+            Language::Yaml => "yaml",
+            Language::Toml => "toml",
+            Language::Html => "html",
+        }
+    }
+
+    /// Language of a text clip: a tag or name extension hint wins over detection
+    /// Returns None for non-text clips, plain prose and clips too large to highlight
+    pub fn for_entry(entry: &ClipEntry) -> Option<Self> {
+        let ClipContent::Text(text) = &entry.content else {
+            return None;
+        };
+        if text.len() > MAX_HIGHLIGHT_BYTES {
+            return None;
+        }
+
+        Language::hinted(entry).or_else(|| Language::detect(text))
+    }
+
+    /// Language named by a clip's tags (e.g. "rust", "lang:py") or its name's extension
+    pub fn hinted(entry: &ClipEntry) -> Option<Self> {
+        entry
+            .tags
+            .iter()
+            .find_map(|tag| Language::from_hint(tag))
+            .or_else(|| {
+                let name = entry.name.as_deref()?;
+                let (_, extension) = name.rsplit_once('.')?;
+                Language::from_hint(extension)
+            })
+    }
+
+    /// Guess the language of a snippet from shebangs, structure and keyword heuristics
+    pub fn detect(text: &str) -> Option<Self> {
+        let trimmed = text.trim();
+        let first = trimmed.lines().next()?;
+
+        if let Some(interpreter) = first.strip_prefix("#!") {
+            let program = interpreter.split_whitespace().last()?;
+            let program = program.rsplit('/').next()?;
+            return match program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.') {
+                "python" => Some(Language::Python),
+                "node" | "deno" | "bun" => Some(Language::JavaScript),
+                _ => Some(Language::Shell),
+            };
+        }
+
+        if (trimmed.starts_with('{') && trimmed.ends_with('}') && trimmed.contains("\":"))
+            || (trimmed.starts_with('[')
+                && trimmed.ends_with(']')
+                && trimmed[1..].trim_start().starts_with(|c: char| {
+                    matches!(c, '{' | '[' | '"' | ']' | '-') || c.is_ascii_digit()
+                }))
+        {
+            return Some(Language::Json);
+        }
+
+        if trimmed.starts_with('<') && trimmed.ends_with('>') {
+            return Some(Language::Html);
+        }
+
+        let mut scores: Vec<(Language, usize)> = Vec::new();
+        let mut add = |language: Language, points: usize| match scores
+            .iter_mut()
+            .find(|(l, _)| *l == language)
+        {
+            Some((_, score)) => *score += points,
+            None => scores.push((language, points)),
+        };
+
+        // SQL statements span lines, so their clauses are looked for in the whole snippet
+        let flat = trimmed
+            .lines()
+            .take(DETECT_LINES)
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+
+        for line in trimmed.lines().take(DETECT_LINES) {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            for (language, signals) in SIGNALS {
+                let hits = signals.iter().filter(|s| line.contains(*s)).count();
+                add(*language, hits);
+            }
+
+            let command = line.strip_prefix("$ ").unwrap_or(line);
+            if let Some(word) = command.split_whitespace().next()
+                && SHELL_COMMANDS.contains(&word)
+            {
+                add(Language::Shell, 2);
+            }
+
+            let lower = line.to_lowercase();
+            if SQL_STATEMENTS
+                .iter()
+                .any(|(k, clause)| lower.starts_with(k) && flat.contains(clause))
+            {
+                add(Language::Sql, 2);
+            }
+            if [
+                " from ",
+                " where ",
+                " join ",
+                " group by ",
+                " order by ",
+                " values",
+            ]
+            .iter()
+            .any(|k| lower.contains(k))
+            {
+                add(Language::Sql, 1);
+            }
+
+            if line.starts_with('[') && line.ends_with(']') && !line.contains(' ') {
+                add(Language::Toml, 2);
+            } else if leading_key(line, '=').is_some() {
+                add(Language::Toml, 1);
+            } else if leading_key(line.strip_prefix("- ").unwrap_or(line), ':').is_some() {
+                add(Language::Yaml, 1);
+            }
+        }
+
+        scores
+            .into_iter()
+            .filter(|&(_, score)| score >= DETECT_MIN_SCORE)
+            .fold(
+                None,
+                |best: Option<(Language, usize)>, (language, score)| match best {
+                    Some((_, best_score)) if best_score >= score => best,
+                    _ => Some((language, score)),
+                },
+            )
+            .map(|(language, _)| language)
+    }
+
+    fn syntax(&self) -> &'static Syntax {
+        match self {
+            Language::Rust => &RUST,
+            Language::Python => &PYTHON,
+            Language::JavaScript => &JAVASCRIPT,
+            Language::Go => &GO,
+            Language::C => &C,
+            Language::Shell => &SHELL,
+            Language::Sql => &SQL,
+            Language::Json => &JSON,
+            Language::Yaml => &YAML,
+            Language::Toml => &TOML,
+            Language::Html => &MARKUP,
+        }
+    }
+}
+
+/// Find a `key:` / `key =` at the start of a line (after indentation)
+/// Returns the key's byte range
+fn leading_key(line: &str, separator: char) -> Option<(usize, usize)> {
+    let start = line.len() - line.trim_start().len();
+    let rest = &line[start..];
+    let key_len = rest
+        .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.')))
+        .unwrap_or(rest.len());
+    if key_len == 0 {
+        return None;
+    }
+
+    let after = rest[key_len..].trim_start_matches(' ');
+    let value = after.strip_prefix(separator)?;
+    // `key:value` is more likely a URL or time than YAML
+    if separator == ':' && !(value.is_empty() || value.starts_with(' ')) {
+        return None;
+    }
+    Some((start, start + key_len))
+}
+
+/// Splits clip lines into highlighted tokens, carrying multi-line state across lines
+pub struct Tokenizer {
+    language: Language,
+    in_block_comment: bool,
+    in_tag: bool,
+}
+
+impl Tokenizer {
+    pub fn new(language: Language) -> Self {
+        Self {
+            language,
+            in_block_comment: false,
+            in_tag: false,
+        }
+    }
+
+    /// Tokenize the next line of the clip (byte ranges, in order, non-overlapping)
+    pub fn line(&mut self, line: &str) -> Vec<Token> {
+        if self.language == Language::Html {
+            return self.markup_line(line);
+        }
+
+        let syntax = self.language.syntax();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        // Shell: the first word of each command is highlighted as the program
+        let mut command_position = true;
+
+        if !self.in_block_comment {
+            let key = match self.language {
+                Language::Yaml => {
+                    let item = line
+                        .trim_start()
+                        .strip_prefix("- ")
+                        .map_or(0, |_| line.len() - line.trim_start().len() + 2);
+                    leading_key(&line[item..], ':').map(|(s, e)| (s + item, e + item))
+                }
+                Language::Toml => leading_key(line, '='),
+                _ => None,
+            };
+            if let Some((start, end)) = key {
+                tokens.push(Token {
+                    start,
+                    end,
+                    kind: TokenKind::Type,
+                });
+                i = end;
+            } else if self.language == Language::Toml
+                && line.trim_start().starts_with('[')
+                && line.trim_end().ends_with(']')
+            {
+                return vec![Token {
+                    start: 0,
+                    end: line.len(),
+                    kind: TokenKind::Keyword,
+                }];
+            }
+        }
+
+        while i < line.len() {
+            let rest = &line[i..];
+
+            if self.in_block_comment {
+                let (_, close) = syntax
+                    .block_comment
+                    .expect("block comment state without syntax");
+                let end = match rest.find(close) {
+                    Some(at) => {
+                        self.in_block_comment = false;
+                        i + at + close.len()
+                    }
+                    None => line.len(),
+                };
+                tokens.push(Token {
+                    start: i,
+                    end,
+                    kind: TokenKind::Comment,
+                });
+                i = end;
+                continue;
+            }
+
+            if let Some((open, close)) = syntax.block_comment
+                && rest.starts_with(open)
+            {
+                let end = match rest[open.len()..].find(close) {
+                    Some(at) => i + open.len() + at + close.len(),
+                    None => {
+                        self.in_block_comment = true;
+                        line.len()
+                    }
+                };
+                tokens.push(Token {
+                    start: i,
+                    end,
+                    kind: TokenKind::Comment,
+                });
+                i = end;
+                continue;
+            }
+
+            // `#` only starts a comment at a word boundary (not in `${#x}` or URLs)
+            let at_boundary = line[..i].ends_with(char::is_whitespace) || i == 0;
+            if syntax
+                .line_comments
+                .iter()
+                .any(|c| rest.starts_with(c) && (*c != "#" || at_boundary))
+            {
+                tokens.push(Token {
+                    start: i,
+                    end: line.len(),
+                    kind: TokenKind::Comment,
+                });
+                break;
+            }
+
+            let c = rest.chars().next().expect("non-empty rest");
+
+            if syntax.quotes.contains(&c) {
+                let end = string_end(rest, c).map_or(line.len(), |at| i + at);
+                let is_key =
+                    self.language == Language::Json && line[end..].trim_start().starts_with(':');
+                tokens.push(Token {
+                    start: i,
+                    end,
+                    kind: if is_key {
+                        TokenKind::Type
+                    } else {
+                        TokenKind::String
+                    },
+                });
+                command_position = false;
+                i = end;
+                continue;
+            }
+
+            if c.is_ascii_digit() {
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+                    .unwrap_or(rest.len());
+                tokens.push(Token {
+                    start: i,
+                    end: i + len,
+                    kind: TokenKind::Number,
+                });
+                command_position = false;
+                i += len;
+                continue;
+            }
+
+            if c == '$' && self.language == Language::Shell {
+                let len = match rest[1..].strip_prefix('{') {
+                    Some(braced) => braced.find('}').map_or(rest.len(), |at| at + 3),
+                    None => {
+                        1 + rest[1..]
+                            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                            .unwrap_or(rest.len() - 1)
+                    }
+                };
+                tokens.push(Token {
+                    start: i,
+                    end: i + len,
+                    kind: TokenKind::Variable,
+                });
+                i += len;
+                continue;
+            }
+
+            if c == '#' && self.language == Language::C && line[..i].trim().is_empty() {
+                let len = 1 + rest[1..]
+                    .find(|c: char| !c.is_alphabetic())
+                    .unwrap_or(rest.len() - 1);
+                tokens.push(Token {
+                    start: i,
+                    end: i + len,
+                    kind: TokenKind::Keyword,
+                });
+                i += len;
+                continue;
+            }
+
+            if c.is_alphabetic() || c == '_' {
+                let len = rest
+                    .find(|c: char| {
+                        !(c.is_alphanumeric()
+                            || c == '_'
+                            || (c == '-' && self.language == Language::Shell))
+                    })
+                    .unwrap_or(rest.len());
+                let word = &rest[..len];
+                // `name!(` is a macro call, `name != x` a comparison
+                let after = &rest[len..];
+                let next = match after.strip_prefix('!') {
+                    Some(bang) if !bang.starts_with('=') => Some('!'),
+                    _ => after
+                        .trim_start_matches(' ')
+                        .chars()
+                        .next()
+                        .filter(|&c| c != '!'),
+                };
+                if let Some(kind) = classify(syntax, word, next, command_position, self.language) {
+                    tokens.push(Token {
+                        start: i,
+                        end: i + len,
+                        kind,
+                    });
+                }
+                // `sudo ls`, `then echo`: the command follows these keywords
+                command_position = command_position
+                    && matches!(
+                        word,
+                        "sudo" | "then" | "do" | "else" | "if" | "elif" | "while"
+                    );
+                i += len;
+                continue;
+            }
+
+            if matches!(c, '|' | ';' | '&' | '(') {
+                command_position = true;
+            }
+            i += c.len_utf8();
+        }
+
+        tokens
+    }
+
+    /// Tokenize a line of HTML/XML: tag names, attributes, values and comments
+    fn markup_line(&mut self, line: &str) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut i = 0;
+
+        while i < line.len() {
+            let rest = &line[i..];
+
+            if self.in_block_comment {
+                let end = match rest.find("-->") {
+                    Some(at) => {
+                        self.in_block_comment = false;
+                        i + at + 3
+                    }
+                    None => line.len(),
+                };
+                tokens.push(Token {
+                    start: i,
+                    end,
+                    kind: TokenKind::Comment,
+                });
+                i = end;
+                continue;
+            }
+
+            if rest.starts_with("<!--") {
+                self.in_block_comment = true;
+                continue;
+            }
+
+            let c = rest.chars().next().expect("non-empty rest");
+
+            if !self.in_tag {
+                if c == '<' {
+                    self.in_tag = true;
+                    let name_start = i + rest[1..]
+                        .find(|c: char| !matches!(c, '/' | '!' | '?'))
+                        .map_or(rest.len(), |at| at + 1);
+                    let name_len = line[name_start..]
+                        .find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | ':' | '_')))
+                        .unwrap_or(line.len() - name_start);
+                    if name_len > 0 {
+                        tokens.push(Token {
+                            start: name_start,
+                            end: name_start + name_len,
+                            kind: TokenKind::Keyword,
+                        });
+                    }
+                    i = name_start + name_len;
+                } else {
+                    i += c.len_utf8();
+                }
+                continue;
+            }
+
+            if c == '>' {
+                self.in_tag = false;
+                i += 1;
+            } else if c == '"' || c == '\'' {
+                let end = string_end(rest, c).map_or(line.len(), |at| i + at);
+                tokens.push(Token {
+                    start: i,
+                    end,
+                    kind: TokenKind::String,
+                });
+                i = end;
+            } else if c.is_alphabetic() {
+                let len = rest
+                    .find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | ':' | '_')))
+                    .unwrap_or(rest.len());
+                tokens.push(Token {
+                    start: i,
+                    end: i + len,
+                    kind: TokenKind::Type,
+                });
+                i += len;
+            } else {
+                i += c.len_utf8();
+            }
+        }
+
+        tokens
+    }
+}
+
+/// Byte offset just past the closing quote of a string starting at `text[0]`
+/// Returns None when the string runs to the end of the line
+fn string_end(text: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (at, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == quote => return Some(at + c.len_utf8()),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Classify an identifier, None for plain identifiers
+fn classify(
+    syntax: &Syntax,
+    word: &str,
+    next: Option<char>,
+    command_position: bool,
+    language: Language,
+) -> Option<TokenKind> {
+    let is = |list: &[&str]| {
+        if syntax.ignore_case {
+            list.iter().any(|w| w.eq_ignore_ascii_case(word))
+        } else {
+            list.contains(&word)
+        }
+    };
+
+    if is(syntax.keywords) {
+        Some(TokenKind::Keyword)
+    } else if is(syntax.constants) {
+        Some(TokenKind::Constant)
+    } else if is(syntax.types) {
+        Some(TokenKind::Type)
+    } else if language == Language::Shell {
+        command_position.then_some(TokenKind::Function)
+    } else if (next == Some('(') && language != Language::Sql)
+        || (next == Some('!') && language == Language::Rust)
+    {
+        Some(TokenKind::Function)
+    } else if syntax.capitalized_types && word.starts_with(char::is_uppercase) {
+        Some(TokenKind::Type)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(language: Language, line: &str) -> Vec<(&str, TokenKind)> {
+        let mut tokenizer = Tokenizer::new(language);
+        tokenizer
+            .line(line)
+            .into_iter()
+            .map(|t| (&line[t.start..t.end], t.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(
+            Language::detect("fn main() {\n    let mut x = Vec::new();\n}"),
+            Some(Language::Rust)
+        );
+        assert_eq!(
+            Language::detect("def run(self):\n    import os\n    print(os.getcwd())"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            Language::detect("git commit -m 'wip'"),
+            Some(Language::Shell)
+        );
+        assert_eq!(
+            Language::detect("#!/usr/bin/env python3\nx = 1"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            Language::detect("SELECT id, name\nFROM users\nWHERE active"),
+            Some(Language::Sql)
+        );
+        assert_eq!(Language::detect("{\"a\": [1, 2]}"), Some(Language::Json));
+        assert_eq!(
+            Language::detect("<div class=\"x\">hi</div>"),
+            Some(Language::Html)
+        );
+        assert_eq!(
+            Language::detect("[general]\nmax_history = 1000\ntheme = \"x\""),
+            Some(Language::Toml)
+        );
+        assert_eq!(Language::detect("Meeting notes for tomorrow"), None);
+        assert_eq!(Language::detect("https://example.com/a?b=c"), None);
+    }
+
+    #[test]
+    fn test_hints() {
+        let mut entry = ClipEntry::new_text(1, "plain words".to_string());
+        assert_eq!(Language::for_entry(&entry), None);
+
+        entry.name = Some("deploy.sh".to_string());
+        assert_eq!(Language::for_entry(&entry), Some(Language::Shell));
+
+        entry.add_tag("rust");
+        assert_eq!(Language::for_entry(&entry), Some(Language::Rust));
+
+        let big = ClipEntry::new_text(2, "fn x() {}\n".repeat(MAX_HIGHLIGHT_BYTES));
+        assert_eq!(Language::for_entry(&big), None);
+    }
+
+    #[test]
+    fn test_tokenize() {
+        use TokenKind::*;
+
+        assert_eq!(
+            kinds(
+                Language::Rust,
+                "let s: String = format!(\"{}\", 42); // done"
+            ),
+            vec![
+                ("let", Keyword),
+                ("String", Type),
+                ("format", Function),
+                ("\"{}\"", String),
+                ("42", Number),
+                ("// done", Comment),
+            ]
+        );
+        assert_eq!(
+            kinds(Language::Shell, "sudo ls $HOME | grep -v x # hi"),
+            vec![
+                ("sudo", Keyword),
+                ("ls", Function),
+                ("$HOME", Variable),
+                ("grep", Function),
+                ("# hi", Comment),
+            ]
+        );
+        assert_eq!(
+            kinds(Language::Json, "{\"on\": true, \"n\": \"x\"}"),
+            vec![
+                ("\"on\"", Type),
+                ("true", Constant),
+                ("\"n\"", Type),
+                ("\"x\"", String),
+            ]
+        );
+        assert_eq!(
+            kinds(Language::Yaml, "- name: web # app"),
+            vec![("name", Type), ("# app", Comment)]
+        );
+        assert_eq!(
+            kinds(Language::Html, "<a href=\"/x\">link</a>"),
+            vec![
+                ("a", Keyword),
+                ("href", Type),
+                ("\"/x\"", String),
+                ("a", Keyword)
+            ]
+        );
+
+        // Block comments continue across lines
+        let mut tokenizer = Tokenizer::new(Language::C);
+        tokenizer.line("int x; /* start");
+        assert_eq!(
+            tokenizer.line("still */ x"),
+            vec![Token {
+                start: 0,
+                end: 8,
+                kind: TokenKind::Comment
+            }]
+        );
+    }
+
+    #[test]
+    fn test_detect_languages() {
+        assert_eq!(
+            Language::detect("const fs = require('fs');\nconsole.log(fs);"),
+            Some(Language::JavaScript)
+        );
+        assert_eq!(
+            Language::detect("package main\n\nfunc main() {\n\tfmt.Println(\"hi\")\n}"),
+            Some(Language::Go)
+        );
+        assert_eq!(
+            Language::detect("#include <stdio.h>\nint main(void) { return 0; }"),
+            Some(Language::C)
+        );
+        assert_eq!(
+            Language::detect("name: web\nimage: nginx\nports:\n  - 80"),
+            Some(Language::Yaml)
+        );
+        assert_eq!(Language::detect("[1, 2, 3]"), Some(Language::Json));
+        assert_eq!(
+            Language::detect("#!/bin/bash\necho hi"),
+            Some(Language::Shell)
+        );
+        assert_eq!(
+            Language::detect("#!/usr/bin/env node\nlet x = 1"),
+            Some(Language::JavaScript)
+        );
+        assert_eq!(
+            Language::detect("UPDATE users SET active = 1 WHERE id = 2"),
+            Some(Language::Sql)
+        );
+        assert_eq!(
+            Language::detect("WITH recent AS (\n  SELECT * FROM t\n)\nSELECT * FROM recent"),
+            Some(Language::Sql)
+        );
+    }
+
+    #[test]
+    fn test_detect_prose() {
+        // Sentences that merely start with an SQL keyword
+        assert_eq!(Language::detect("update the docs before friday"), None);
+        assert_eq!(Language::detect("with love,\nSam"), None);
+        assert_eq!(Language::detect("Select the files you want to keep"), None);
+        assert_eq!(Language::detect("[draft] notes for the launch"), None);
+        assert_eq!(Language::detect("Time: 10:30"), None);
+        assert_eq!(Language::detect("Café au lait — très bon ✓"), None);
+        assert_eq!(Language::detect(""), None);
+    }
+
+    #[test]
+    fn test_tokenize_languages() {
+        use TokenKind::*;
+
+        assert_eq!(
+            kinds(Language::Python, "def run(self) -> None:  # go"),
+            vec![
+                ("def", Keyword),
+                ("run", Function),
+                ("self", Constant),
+                ("None", Constant),
+                ("# go", Comment),
+            ]
+        );
+        assert_eq!(
+            kinds(
+                Language::Go,
+                "if err != nil { return fmt.Errorf(\"bad: %w\", err) }"
+            ),
+            vec![
+                ("if", Keyword),
+                ("nil", Constant),
+                ("return", Keyword),
+                ("Errorf", Function),
+                ("\"bad: %w\"", String),
+            ]
+        );
+        assert_eq!(
+            kinds(
+                Language::Sql,
+                "SELECT id FROM users WHERE name = 'bob' -- x"
+            ),
+            vec![
+                ("SELECT", Keyword),
+                ("FROM", Keyword),
+                ("WHERE", Keyword),
+                ("'bob'", String),
+                ("-- x", Comment),
+            ]
+        );
+        assert_eq!(
+            kinds(Language::C, "#include <stdio.h>"),
+            vec![("#include", Keyword)]
+        );
+        assert_eq!(
+            kinds(Language::Toml, "[server]"),
+            vec![("[server]", Keyword)]
+        );
+        assert_eq!(
+            kinds(Language::Toml, "port = 8080 # x"),
+            vec![("port", Type), ("8080", Number), ("# x", Comment)]
+        );
+
+        // Python docstrings continue across lines
+        let mut tokenizer = Tokenizer::new(Language::Python);
+        tokenizer.line("\"\"\"Docs");
+        assert_eq!(
+            tokenizer.line("end\"\"\" x"),
+            vec![Token {
+                start: 0,
+                end: 6,
+                kind: Comment
+            }]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_multibyte() {
+        use TokenKind::*;
+
+        assert_eq!(
+            kinds(Language::Rust, "let café = \"héllo ✓\"; // ñ"),
+            vec![("let", Keyword), ("\"héllo ✓\"", String), ("// ñ", Comment)]
+        );
+        assert_eq!(
+            kinds(Language::Shell, "echo \"日本\" $ÜBER"),
+            vec![
+                ("echo", Function),
+                ("\"日本\"", String),
+                ("$ÜBER", Variable)
+            ]
+        );
+        assert_eq!(
+            kinds(Language::Html, "<p title=\"é\">ü</p>"),
+            vec![
+                ("p", Keyword),
+                ("title", Type),
+                ("\"é\"", String),
+                ("p", Keyword)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_unterminated() {
+        use TokenKind::*;
+
+        assert_eq!(
+            kinds(Language::Rust, "let s = \"open"),
+            vec![("let", Keyword), ("\"open", String)]
+        );
+        assert_eq!(
+            kinds(Language::Python, "x = 'it\\'s"),
+            vec![("'it\\'s", String)]
+        );
+        assert_eq!(
+            kinds(Language::Shell, "echo ${HOME"),
+            vec![("echo", Function), ("${HOME", Variable)]
+        );
+
+        // Strings don't carry over to the next line
+        let mut tokenizer = Tokenizer::new(Language::Shell);
+        tokenizer.line("echo \"a");
+        assert_eq!(
+            tokenizer.line("ls"),
+            vec![Token {
+                start: 0,
+                end: 2,
+                kind: Function
+            }]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_interpolation() {
+        use TokenKind::*;
+
+        assert_eq!(
+            kinds(Language::Shell, "echo ${HOME}/bin ${#args} $1"),
+            vec![
+                ("echo", Function),
+                ("${HOME}", Variable),
+                ("${#args}", Variable),
+                ("$1", Variable),
+            ]
+        );
+        assert_eq!(
+            kinds(Language::JavaScript, "const s = `hi ${name}`;"),
+            vec![("const", Keyword), ("`hi ${name}`", String)]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_markup_comments() {
+        use TokenKind::*;
+
+        let mut tokenizer = Tokenizer::new(Language::Html);
+        let first = "<p>x</p> <!-- note";
+        assert_eq!(
+            tokenizer.line(first),
+            vec![
+                Token {
+                    start: 1,
+                    end: 2,
+                    kind: Keyword
+                },
+                Token {
+                    start: 6,
+                    end: 7,
+                    kind: Keyword
+                },
+                Token {
+                    start: 9,
+                    end: first.len(),
+                    kind: Comment
+                },
+            ]
+        );
+        assert_eq!(
+            tokenizer.line("still --> <b>"),
+            vec![
+                Token {
+                    start: 0,
+                    end: 9,
+                    kind: Comment
+                },
+                Token {
+                    start: 11,
+                    end: 12,
+                    kind: Keyword
+                },
+            ]
+        );
+    }
+}
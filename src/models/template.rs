@@ -0,0 +1,232 @@
+use anyhow::{Result, anyhow};
+use chrono::Local;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Default format for `{{date}}`
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Default format for `{{time}}`
+const DEFAULT_TIME_FORMAT: &str = "%H:%M:%S";
+
+/// A placeholder inside snippet template text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Placeholder {
+    /// `{{date}}` or `{{date:<strftime format>}}`
+    Date(Option<String>),
+    /// `{{time}}` or `{{time:<strftime format>}}`
+    Time(Option<String>),
+    /// `{{clipboard}}` - current clipboard text
+    Clipboard,
+    /// `{{input:Label}}` - prompted from the user at paste time
+    Input(String),
+    /// `{{env:NAME}}` - environment variable
+    Env(String),
+}
+
+/// A parsed piece of a template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// Text copied through unchanged
+    Literal(String),
+    /// Placeholder expanded at paste time
+    Placeholder(Placeholder),
+}
+
+/// Snippet template parsed from register content
+/// Unknown `{{...}}` sequences are kept as literal text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+/// Values used to expand a template
+#[derive(Debug, Default, Clone)]
+pub struct TemplateContext {
+    /// Current clipboard text (for `{{clipboard}}`)
+    pub clipboard: Option<String>,
+    /// User-provided values keyed by input label
+    pub inputs: HashMap<String, String>,
+}
+
+impl Placeholder {
+    /// Parse the inside of a `{{...}}` sequence
+    fn parse(inner: &str) -> Option<Self> {
+        let inner = inner.trim();
+        let (name, arg) = match inner.split_once(':') {
+            Some((name, arg)) => (name.trim(), Some(arg)),
+            None => (inner, None),
+        };
+
+        match (name, arg) {
+            ("date", arg) => Some(Placeholder::Date(arg.map(str::to_string))),
+            ("time", arg) => Some(Placeholder::Time(arg.map(str::to_string))),
+            ("clipboard", None) => Some(Placeholder::Clipboard),
+            ("input", Some(label)) if !label.trim().is_empty() => {
+                Some(Placeholder::Input(label.trim().to_string()))
+            }
+            ("env", Some(var)) if !var.trim().is_empty() => {
+                Some(Placeholder::Env(var.trim().to_string()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Template {
+    /// Parse template text into literal and placeholder segments
+    pub fn parse(text: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut rest = text;
+
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+
+            let inner = &rest[start + 2..start + 2 + len];
+            let end = start + 2 + len + 2;
+
+            match Placeholder::parse(inner) {
+                Some(placeholder) => {
+                    literal.push_str(&rest[..start]);
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Placeholder(placeholder));
+                }
+                None => literal.push_str(&rest[..end]),
+            }
+
+            rest = &rest[end..];
+        }
+
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Template { segments }
+    }
+
+    /// Check whether text contains any recognised placeholder
+    pub fn has_placeholders(text: &str) -> bool {
+        text.contains("{{") && Self::parse(text).is_template()
+    }
+
+    /// Check whether this template contains any placeholder
+    pub fn is_template(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|s| matches!(s, Segment::Placeholder(_)))
+    }
+
+    /// Check whether this template needs the current clipboard contents
+    pub fn uses_clipboard(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|s| matches!(s, Segment::Placeholder(Placeholder::Clipboard)))
+    }
+
+    /// Input labels that must be prompted for, in order of first appearance
+    pub fn input_labels(&self) -> Vec<String> {
+        let mut labels: Vec<String> = Vec::new();
+        for segment in &self.segments {
+            if let Segment::Placeholder(Placeholder::Input(label)) = segment
+                && !labels.contains(label)
+            {
+                labels.push(label.clone());
+            }
+        }
+        labels
+    }
+
+    /// Expand all placeholders using the given context
+    pub fn render(&self, ctx: &TemplateContext) -> Result<String> {
+        let now = Local::now();
+        let mut output = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => output.push_str(text),
+                Segment::Placeholder(placeholder) => match placeholder {
+                    Placeholder::Date(format) => {
+                        let format = format.as_deref().unwrap_or(DEFAULT_DATE_FORMAT);
+                        write!(output, "{}", now.format(format))
+                            .map_err(|_| anyhow!("Invalid date format '{}'", format))?;
+                    }
+                    Placeholder::Time(format) => {
+                        let format = format.as_deref().unwrap_or(DEFAULT_TIME_FORMAT);
+                        write!(output, "{}", now.format(format))
+                            .map_err(|_| anyhow!("Invalid time format '{}'", format))?;
+                    }
+                    Placeholder::Clipboard => {
+                        output.push_str(ctx.clipboard.as_deref().unwrap_or(""));
+                    }
+                    Placeholder::Input(label) => {
+                        let value = ctx
+                            .inputs
+                            .get(label)
+                            .ok_or_else(|| anyhow!("Missing template input '{}'", label))?;
+                        output.push_str(value);
+                    }
+                    Placeholder::Env(var) => match std::env::var(var) {
+                        Ok(value) => output.push_str(&value),
+                        Err(_) => log::warn!("Template variable ${} is not set", var),
+                    },
+                },
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_placeholders() {
+        let template = Template::parse("Hi {{input:Name}}, today is {{date}}");
+        assert!(template.is_template());
+        assert_eq!(template.input_labels(), vec!["Name".to_string()]);
+        assert!(!template.uses_clipboard());
+    }
+
+    #[test]
+    fn test_unknown_placeholders_are_literal() {
+        let text = "{{ handlebars }} and {{input:}} and {{ unclosed";
+        let template = Template::parse(text);
+        assert!(!template.is_template());
+        assert!(!Template::has_placeholders(text));
+        assert_eq!(template.render(&TemplateContext::default()).unwrap(), text);
+    }
+
+    #[test]
+    fn test_render_inputs_and_clipboard() {
+        let template = Template::parse("[{{input:Ticket ID}}] {{clipboard}} ({{input:Ticket ID}})");
+        assert_eq!(template.input_labels(), vec!["Ticket ID".to_string()]);
+
+        let mut ctx = TemplateContext {
+            clipboard: Some("fix login".to_string()),
+            ..Default::default()
+        };
+        assert!(template.render(&ctx).is_err());
+
+        ctx.inputs
+            .insert("Ticket ID".to_string(), "ABC-1".to_string());
+        assert_eq!(template.render(&ctx).unwrap(), "[ABC-1] fix login (ABC-1)");
+    }
+
+    #[test]
+    fn test_render_date_format_and_env() {
+        let template = Template::parse("{{date:%Y}}|{{env:CLIPR_TEMPLATE_TEST_UNSET}}|");
+        let rendered = template.render(&TemplateContext::default()).unwrap();
+        assert_eq!(rendered, format!("{}||", Local::now().format("%Y")));
+
+        let template = Template::parse("{{date:%Q}}");
+        assert!(template.render(&TemplateContext::default()).is_err());
+    }
+}
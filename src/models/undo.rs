@@ -0,0 +1,187 @@
+use super::clip::{ClipEntry, ClipboardHistory};
+
+/// Maximum number of undo steps kept per session
+const MAX_UNDO_STEPS: usize = 100;
+
+/// State of the clips touched by an action, captured before it runs
+#[derive(Debug, Clone)]
+pub struct HistorySnapshot {
+    /// Clip ID -> (position, entry) as it was, or None if the clip didn't exist
+    clips: Vec<(u64, Option<(usize, ClipEntry)>)>,
+}
+
+impl HistorySnapshot {
+    /// Capture the current state of the given clips
+    pub fn capture(history: &ClipboardHistory, ids: &[u64]) -> Self {
+        let clips = ids
+            .iter()
+            .map(|&id| {
+                let state = history
+                    .entries()
+                    .iter()
+                    .position(|e| e.id == id)
+                    .map(|pos| (pos, history.entries()[pos].clone()));
+                (id, state)
+            })
+            .collect();
+
+        HistorySnapshot { clips }
+    }
+
+    /// Clip IDs covered by this snapshot
+    fn ids(&self) -> Vec<u64> {
+        self.clips.iter().map(|(id, _)| *id).collect()
+    }
+
+    /// Put the captured clips back into history at their original positions
+    /// Clips that didn't exist when captured are moved to the trash
+    pub fn restore(&self, history: &mut ClipboardHistory) {
+        for (id, state) in &self.clips {
+            match state {
+                Some(_) => history.remove_entry(*id),
+                None => history.trash_entry(*id),
+            };
+        }
+
+        // Insert in ascending position order so earlier inserts don't shift later ones
+        let mut saved: Vec<&(usize, ClipEntry)> =
+            self.clips.iter().filter_map(|(_, s)| s.as_ref()).collect();
+        saved.sort_by_key(|(pos, _)| *pos);
+
+        for (pos, entry) in saved {
+            history.insert_entry(*pos, entry.clone());
+        }
+    }
+}
+
+/// An undoable action with a short description (e.g. "delete 3 clips")
+#[derive(Debug, Clone)]
+struct UndoStep {
+    description: String,
+    snapshot: HistorySnapshot,
+}
+
+/// Undo/redo stacks for history changes made in the TUI
+#[derive(Debug, Default)]
+pub struct UndoStack {
+    undo: Vec<UndoStep>,
+    redo: Vec<UndoStep>,
+}
+
+impl UndoStack {
+    /// Create an empty undo stack
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an action, given the snapshot taken before it ran
+    /// Clears the redo stack
+    pub fn push(&mut self, description: impl Into<String>, snapshot: HistorySnapshot) {
+        self.undo.push(UndoStep {
+            description: description.into(),
+            snapshot,
+        });
+        if self.undo.len() > MAX_UNDO_STEPS {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Revert the most recent action
+    /// Returns its description, or None if there is nothing to undo
+    pub fn undo(&mut self, history: &mut ClipboardHistory) -> Option<String> {
+        let step = self.undo.pop()?;
+        let description = step.description.clone();
+        self.redo.push(Self::apply(step, history));
+        Some(description)
+    }
+
+    /// Re-apply the most recently undone action
+    /// Returns its description, or None if there is nothing to redo
+    pub fn redo(&mut self, history: &mut ClipboardHistory) -> Option<String> {
+        let step = self.redo.pop()?;
+        let description = step.description.clone();
+        self.undo.push(Self::apply(step, history));
+        Some(description)
+    }
+
+    /// Restore a step's snapshot, returning the inverse step
+    fn apply(step: UndoStep, history: &mut ClipboardHistory) -> UndoStep {
+        let inverse = HistorySnapshot::capture(history, &step.snapshot.ids());
+        step.snapshot.restore(history);
+        UndoStep {
+            description: step.description,
+            snapshot: inverse,
+        }
+    }
+
+    /// Check whether there is anything to undo
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ClipContent;
+
+    fn history_with(texts: &[&str]) -> (ClipboardHistory, Vec<u64>) {
+        let mut history = ClipboardHistory::new(10);
+        let ids = texts
+            .iter()
+            .map(|t| history.add_entry(ClipContent::Text(t.to_string())))
+            .collect();
+        (history, ids)
+    }
+
+    fn order(history: &ClipboardHistory) -> Vec<u64> {
+        history.entries().iter().map(|e| e.id).collect()
+    }
+
+    #[test]
+    fn test_undo_redo_delete() {
+        let (mut history, ids) = history_with(&["a", "b", "c"]);
+        let before = order(&history);
+        let mut stack = UndoStack::new();
+
+        let deleted = [ids[0], ids[2]];
+        stack.push(
+            "delete 2 clips",
+            HistorySnapshot::capture(&history, &deleted),
+        );
+        for id in deleted {
+            history.remove_entry(id);
+        }
+        assert_eq!(history.len(), 1);
+
+        assert_eq!(stack.undo(&mut history).as_deref(), Some("delete 2 clips"));
+        assert_eq!(order(&history), before);
+        assert!(
+            history
+                .find_by_hash(ClipContent::Text("a".into()).content_hash())
+                .is_some()
+        );
+
+        assert_eq!(stack.redo(&mut history).as_deref(), Some("delete 2 clips"));
+        assert_eq!(order(&history), vec![ids[1]]);
+        assert_eq!(history.trash.len(), 2);
+        assert!(stack.redo(&mut history).is_none());
+    }
+
+    #[test]
+    fn test_undo_edit_and_new_action_clears_redo() {
+        let (mut history, ids) = history_with(&["a"]);
+        let mut stack = UndoStack::new();
+
+        stack.push("pin", HistorySnapshot::capture(&history, &ids));
+        history.toggle_pin(ids[0]).unwrap();
+
+        stack.undo(&mut history);
+        assert!(!history.get_entry(ids[0]).unwrap().pinned);
+
+        stack.push("tag", HistorySnapshot::capture(&history, &ids));
+        assert!(stack.redo(&mut history).is_none());
+        assert!(stack.can_undo());
+    }
+}
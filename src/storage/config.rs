@@ -10,6 +10,9 @@ pub struct Config {
     pub general: GeneralConfig,
     #[serde(rename = "permanent-registers", default)]
     pub permanent_registers: HashMap<char, PermanentRegisterValue>,
+    /// Key bindings per mode: `[keys.normal]` with `copy = "Enter"` or `delete = ["d", "Del"]`
+    #[serde(default)]
+    pub keys: HashMap<String, HashMap<String, KeyBindingValue>>,
 }
 
 impl Config {
@@ -87,10 +90,47 @@ pub struct GeneralConfig {
     #[serde(default = "default_view_mode")]
     pub view_mode: String,
 
+    /// Default clip list order for TUI
+    /// (recent, oldest, most-used, frecency, size, name or type)
+    #[serde(default = "default_sort_mode")]
+    pub sort_mode: String,
+
+    /// Show the TUI clip list in sections (Pinned, Registers, Today, Yesterday, Older)
+    #[serde(default)]
+    pub group_clips: bool,
+
     /// Show metadata section in preview pane (name, size, mime-type, description)
     #[serde(default = "default_show_preview_metadata")]
     pub show_preview_metadata: bool,
 
+    /// Show line numbers in the preview pane
+    #[serde(default)]
+    pub preview_line_numbers: bool,
+
+    /// Wrap long lines in the preview pane
+    #[serde(default = "default_preview_wrap")]
+    pub preview_wrap: bool,
+
+    /// Pretty-print JSON/XML clips in the preview pane
+    #[serde(default = "default_pretty_print")]
+    pub pretty_print: bool,
+
+    /// Show tabs, CRs, zero-width and control characters in the preview pane
+    #[serde(default = "default_show_invisibles")]
+    pub show_invisibles: bool,
+
+    /// Show small image thumbnails in the comfortable view
+    #[serde(default)]
+    pub list_thumbnails: bool,
+
+    /// Capture the mouse in the TUI (off leaves text selection to the terminal)
+    #[serde(default = "default_mouse")]
+    pub mouse: bool,
+
+    /// Quit the TUI when its terminal loses focus (for floating popup terminals)
+    #[serde(default)]
+    pub quit_on_focus_lost: bool,
+
     /// Enable theme development mode (auto-reload theme file on changes)
     #[serde(default)]
     pub theme_dev_mode: bool,
@@ -106,6 +146,38 @@ pub struct GeneralConfig {
     /// Flash message duration in milliseconds
     #[serde(default = "default_flash_message_duration_ms")]
     pub flash_message_duration_ms: u64,
+
+    /// Protect tagged clips from history rotation and clear-all (like pinned clips)
+    #[serde(default)]
+    pub protect_tagged: bool,
+
+    /// Maximum number of deleted/rotated-out clips kept in the trash (0 disables the trash)
+    #[serde(default = "default_max_trash")]
+    pub max_trash: usize,
+
+    /// Maximum bytes of text and images kept in the trash (oldest clips are dropped first)
+    #[serde(default = "default_max_trash_bytes")]
+    pub max_trash_bytes: u64,
+
+    /// Days deleted clips are kept in the trash before being purged
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u64,
+
+    /// Treat new images that look like an existing image clip as duplicates
+    #[serde(default)]
+    pub image_dedup: bool,
+
+    /// Perceptual hash bits (out of 64) that may differ for images to count as duplicates
+    #[serde(default = "default_image_dedup_threshold")]
+    pub image_dedup_threshold: u32,
+
+    /// Separator inserted between clips when joining a multi-selection
+    #[serde(default = "default_join_separator")]
+    pub join_separator: String,
+
+    /// Directory for exported clips (default: <data dir>/exports)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_dir: Option<PathBuf>,
 }
 
 impl Default for GeneralConfig {
@@ -121,11 +193,28 @@ impl Default for GeneralConfig {
             theme: default_theme(),
             image_cache_size: default_image_cache_size(),
             view_mode: default_view_mode(),
+            sort_mode: default_sort_mode(),
+            group_clips: false,
             show_preview_metadata: default_show_preview_metadata(),
+            preview_line_numbers: false,
+            preview_wrap: default_preview_wrap(),
+            pretty_print: default_pretty_print(),
+            show_invisibles: default_show_invisibles(),
+            list_thumbnails: false,
+            mouse: default_mouse(),
+            quit_on_focus_lost: false,
             theme_dev_mode: false,
             file_log_level: default_file_log_level(),
             flash_message_level: default_flash_message_level(),
             flash_message_duration_ms: default_flash_message_duration_ms(),
+            protect_tagged: false,
+            max_trash: default_max_trash(),
+            max_trash_bytes: default_max_trash_bytes(),
+            trash_retention_days: default_trash_retention_days(),
+            image_dedup: false,
+            image_dedup_threshold: default_image_dedup_threshold(),
+            join_separator: default_join_separator(),
+            export_dir: None,
         }
     }
 }
@@ -167,10 +256,30 @@ fn default_view_mode() -> String {
     "compact".to_string()
 }
 
+fn default_sort_mode() -> String {
+    "recent".to_string()
+}
+
 fn default_show_preview_metadata() -> bool {
     false // Opt-in: metadata hidden by default
 }
 
+fn default_preview_wrap() -> bool {
+    true
+}
+
+fn default_pretty_print() -> bool {
+    true
+}
+
+fn default_show_invisibles() -> bool {
+    true
+}
+
+fn default_mouse() -> bool {
+    true
+}
+
 fn default_file_log_level() -> String {
     "info".to_string()
 }
@@ -183,6 +292,26 @@ fn default_flash_message_duration_ms() -> u64 {
     5000 // 5 seconds
 }
 
+fn default_max_trash() -> usize {
+    crate::models::clip::DEFAULT_MAX_TRASH
+}
+
+fn default_max_trash_bytes() -> u64 {
+    crate::models::clip::DEFAULT_MAX_TRASH_BYTES
+}
+
+fn default_trash_retention_days() -> u64 {
+    crate::models::clip::DEFAULT_TRASH_RETENTION_DAYS
+}
+
+fn default_image_dedup_threshold() -> u32 {
+    4
+}
+
+fn default_join_separator() -> String {
+    "\n".to_string()
+}
+
 /// Value for a permanent register entry
 /// Supports both inline content and file references
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -239,6 +368,23 @@ impl PermanentRegisterValue {
     }
 }
 
+/// Keys bound to an action: one chord or a list (empty unbinds the action)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyBindingValue {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyBindingValue {
+    pub fn keys(&self) -> &[String] {
+        match self {
+            KeyBindingValue::One(key) => std::slice::from_ref(key),
+            KeyBindingValue::Many(keys) => keys,
+        }
+    }
+}
+
 /// Trait for configuration storage
 pub trait ConfigStorage: Send + Sync {
     /// Load configuration from file
@@ -358,7 +504,7 @@ mod tests {
         assert_eq!(config.max_image_size_bytes, 52_428_800);
         assert_eq!(config.max_image_memory_size_bytes, 5_242_880);
         assert_eq!(config.max_image_preview_size_bytes, 10_485_760);
-        assert_eq!(config.exit_on_select, true);
+        assert!(config.exit_on_select);
     }
 
     #[test]
@@ -1,9 +1,19 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow};
 use std::fs;
 use std::path::PathBuf;
 
+use super::legacy::ClipboardHistoryV0;
 use crate::models::ClipboardHistory;
 
+/// Magic prefix identifying a versioned history file
+/// Files without it are the original unversioned format (v0)
+const HISTORY_MAGIC: &[u8; 4] = b"CLPR";
+
+/// Current history file format version
+/// v1: added tags, use count, last-used time and perceptual image hashes to clip
+/// entries, and the trash for deleted and rotated-out clips
+pub const HISTORY_FORMAT_VERSION: u32 = 1;
+
 /// Trait for clipboard history persistence
 pub trait HistoryStorage: Send + Sync {
     /// Load clipboard history from storage
@@ -33,6 +43,47 @@ impl BincodeHistoryStorage {
     }
 }
 
+/// Encode history with the versioned file header
+pub fn encode_history(history: &ClipboardHistory) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(HISTORY_MAGIC.len() + 4);
+    bytes.extend_from_slice(HISTORY_MAGIC);
+    bytes.extend_from_slice(&HISTORY_FORMAT_VERSION.to_le_bytes());
+
+    let body = bincode::encode_to_vec(history, bincode::config::standard())
+        .with_context(|| "Failed to serialize clipboard history")?;
+    bytes.extend_from_slice(&body);
+
+    Ok(bytes)
+}
+
+/// Decode history from any supported file format version, migrating old formats
+pub fn decode_history(bytes: &[u8]) -> Result<ClipboardHistory> {
+    let config = bincode::config::standard();
+
+    let Some(rest) = bytes.strip_prefix(HISTORY_MAGIC) else {
+        // Unversioned (v0) file: migrate to the current model
+        let (old, _) = bincode::decode_from_slice::<ClipboardHistoryV0, _>(bytes, config)?;
+        log::info!("Migrating history file from format v0");
+        return Ok(old.into());
+    };
+
+    let (version_bytes, body) = rest
+        .split_first_chunk::<4>()
+        .ok_or_else(|| anyhow!("History file header is truncated"))?;
+
+    match u32::from_le_bytes(*version_bytes) {
+        HISTORY_FORMAT_VERSION => {
+            let (history, _) = bincode::decode_from_slice::<ClipboardHistory, _>(body, config)?;
+            Ok(history)
+        }
+        version => Err(anyhow!(
+            "Unsupported history format version {} (newest supported is {})",
+            version,
+            HISTORY_FORMAT_VERSION
+        )),
+    }
+}
+
 impl HistoryStorage for BincodeHistoryStorage {
     fn load(&self) -> Result<ClipboardHistory> {
         // If file doesn't exist, return empty history
@@ -49,9 +100,8 @@ impl HistoryStorage for BincodeHistoryStorage {
         let bytes = fs::read(&self.path)
             .with_context(|| format!("Failed to read history from {:?}", self.path))?;
 
-        match bincode::decode_from_slice::<ClipboardHistory, _>(&bytes, bincode::config::standard())
-        {
-            Ok((mut history, _bytes_read)) => {
+        match decode_history(&bytes) {
+            Ok(mut history) => {
                 // Rebuild hash_to_id index after deserialization
                 history.rebuild_hash_map();
                 log::info!("Loaded {} clips from {:?}", history.len(), self.path);
@@ -77,8 +127,7 @@ impl HistoryStorage for BincodeHistoryStorage {
 
     fn save(&self, history: &ClipboardHistory) -> Result<()> {
         // Serialize to bytes
-        let bytes = bincode::encode_to_vec(history, bincode::config::standard())
-            .with_context(|| "Failed to serialize clipboard history")?;
+        let bytes = encode_history(history)?;
 
         // Atomic write pattern: write to .tmp, then rename
         let tmp_path = self.path.with_extension("bin.tmp");
@@ -106,3 +155,83 @@ impl HistoryStorage for BincodeHistoryStorage {
         &self.path
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ClipContent;
+    use crate::storage::legacy::ClipEntryV0;
+    use std::collections::HashMap;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_history_roundtrip() {
+        let mut history = ClipboardHistory::new(10);
+        let id = history.add_entry(ClipContent::Text("hello".to_string()));
+        history.get_entry_mut(id).unwrap().add_tag("greeting");
+
+        let bytes = encode_history(&history).unwrap();
+        let decoded = decode_history(&bytes).unwrap();
+
+        assert_eq!(decoded.entries, history.entries);
+        assert_eq!(decoded.next_id(), history.next_id());
+        assert_eq!(
+            decoded.find_by_hash(ClipContent::Text("hello".to_string()).content_hash()),
+            Some(id)
+        );
+    }
+
+    #[test]
+    fn test_settings_not_persisted() {
+        let mut history = ClipboardHistory::new(10);
+        history.protect_tagged = true;
+        history.max_trash = 3;
+        history.image_dedup_threshold = Some(4);
+        let id = history.add_entry(ClipContent::Text("gone".to_string()));
+        history.trash_entry(id);
+
+        let decoded = decode_history(&encode_history(&history).unwrap()).unwrap();
+        assert!(!decoded.protect_tagged);
+        assert_eq!(decoded.max_trash, crate::models::clip::DEFAULT_MAX_TRASH);
+        assert_eq!(decoded.image_dedup_threshold, None);
+        assert!(decoded.get_trashed(id).is_some());
+    }
+
+    #[test]
+    fn test_migrate_unversioned_history() {
+        let content = ClipContent::Text("old clip".to_string());
+        let old = ClipboardHistoryV0 {
+            entries: vec![ClipEntryV0 {
+                id: 7,
+                content_hash: content.content_hash(),
+                content,
+                timestamp: SystemTime::now(),
+                pinned: true,
+                name: Some("legacy".to_string()),
+                description: None,
+                temporary_registers: vec!['a'],
+                permanent_registers: Vec::new(),
+            }],
+            max_entries: 50,
+            next_id: 8,
+            hash_to_id: HashMap::new(),
+        };
+        let bytes = bincode::encode_to_vec(&old, bincode::config::standard()).unwrap();
+
+        let history = decode_history(&bytes).unwrap();
+        let entry = history.get_entry(7).unwrap();
+        assert!(entry.pinned);
+        assert!(entry.tags.is_empty());
+        assert_eq!(entry.temporary_registers, vec!['a']);
+        assert_eq!(history.next_id(), 8);
+        assert_eq!(history.max_entries, 50);
+        assert!(history.find_by_hash(entry.content_hash).is_some());
+    }
+
+    #[test]
+    fn test_unknown_version_rejected() {
+        let mut bytes = HISTORY_MAGIC.to_vec();
+        bytes.extend_from_slice(&(HISTORY_FORMAT_VERSION + 1).to_le_bytes());
+        assert!(decode_history(&bytes).is_err());
+    }
+}
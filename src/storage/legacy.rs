@@ -0,0 +1,64 @@
+//! Legacy history file formats
+//!
+//! Bincode has no notion of optional or defaulted fields, so every change to
+//! `ClipEntry` or `ClipboardHistory` needs a frozen copy of the previous layout
+//! here, plus a conversion into the current model.
+
+use bincode::{Decode, Encode};
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use crate::models::{ClipContent, ClipEntry, ClipboardHistory};
+
+/// Clip entry layout before tags were added (unversioned files)
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct ClipEntryV0 {
+    pub id: u64,
+    pub content: ClipContent,
+    pub timestamp: SystemTime,
+    pub pinned: bool,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub temporary_registers: Vec<char>,
+    pub permanent_registers: Vec<char>,
+    pub content_hash: u64,
+}
+
+/// History layout before tags were added (unversioned files)
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct ClipboardHistoryV0 {
+    pub entries: Vec<ClipEntryV0>,
+    pub max_entries: usize,
+    pub next_id: u64,
+    pub hash_to_id: HashMap<u64, u64>,
+}
+
+impl From<ClipEntryV0> for ClipEntry {
+    fn from(old: ClipEntryV0) -> Self {
+        ClipEntry {
+            id: old.id,
+            content: old.content,
+            timestamp: old.timestamp,
+            pinned: old.pinned,
+            name: old.name,
+            description: old.description,
+            temporary_registers: old.temporary_registers,
+            permanent_registers: old.permanent_registers,
+            tags: Vec::new(),
+            use_count: 0,
+            last_used: None,
+            content_hash: old.content_hash,
+            image_hash: None,
+        }
+    }
+}
+
+impl From<ClipboardHistoryV0> for ClipboardHistory {
+    fn from(old: ClipboardHistoryV0) -> Self {
+        ClipboardHistory::from_parts(
+            old.entries.into_iter().map(ClipEntry::from).collect(),
+            old.max_entries,
+            old.next_id,
+        )
+    }
+}
@@ -1,5 +1,6 @@
 pub mod config;
 pub mod history;
+pub mod legacy;
 pub mod registers;
 
 use anyhow::{Context, Result};
@@ -7,10 +8,22 @@ use std::env;
 use std::fs;
 use std::path::PathBuf;
 
-pub use config::{Config, ConfigStorage, GeneralConfig, PermanentRegisterValue, TomlConfigStorage};
+pub use config::{
+    Config, ConfigStorage, GeneralConfig, KeyBindingValue, PermanentRegisterValue,
+    TomlConfigStorage,
+};
 pub use history::{BincodeHistoryStorage, HistoryStorage};
 pub use registers::RegisterStorage;
 
+/// Get the XDG data directory: $XDG_DATA_HOME/clipr (default: ~/.local/share/clipr)
+pub fn data_dir() -> Result<PathBuf> {
+    if let Ok(xdg_data) = env::var("XDG_DATA_HOME") {
+        return Ok(PathBuf::from(xdg_data).join("clipr"));
+    }
+    let home = env::var("HOME").context("HOME environment variable not set")?;
+    Ok(PathBuf::from(home).join(".local/share/clipr"))
+}
+
 /// Ensure XDG data and config directories exist
 /// Returns (data_dir, config_dir)
 ///
@@ -22,11 +35,7 @@ pub fn ensure_directories() -> Result<(PathBuf, PathBuf)> {
     let home_path = PathBuf::from(home);
 
     // Get XDG data directory
-    let data_dir = if let Ok(xdg_data) = env::var("XDG_DATA_HOME") {
-        PathBuf::from(xdg_data).join("clipr")
-    } else {
-        home_path.join(".local/share/clipr")
-    };
+    let data_dir = data_dir()?;
 
     // Get XDG config directory
     let config_dir = if let Ok(xdg_config) = env::var("XDG_CONFIG_HOME") {
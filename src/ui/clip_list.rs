@@ -1,11 +1,17 @@
+use lru::LruCache;
 use ratatui::layout::{Constraint, Direction, Layout, Position};
 use ratatui::prelude::*;
 use ratatui::widgets::{Cell, Paragraph, Row, Table};
+use ratatui_image::StatefulImage;
+use ratatui_image::protocol::StatefulProtocol;
+use std::collections::HashSet;
+use std::ops::Range;
 use tui_input::Input;
 use unicode_width::UnicodeWidthStr;
 
-use crate::app::{AppMode, RegisterFilter, ViewMode};
-use crate::models::ClipEntry;
+use crate::app::{AppMode, ClipGroup, RegisterFilter, SortMode, ViewMode};
+use crate::image::{THUMBNAIL_COLS, THUMBNAIL_ROWS};
+use crate::models::{ClipEntry, Highlighter};
 use chrono::{DateTime, Local};
 
 /// Format timestamp relative to now
@@ -32,11 +38,108 @@ fn format_timestamp(timestamp: i64) -> String {
     }
 }
 
+/// Maximum width of the tag section in the compact register column
+const MAX_COMPACT_TAG_WIDTH: usize = 16;
+
+/// Format a clip's tags for display (e.g. "#sql #work")
+fn format_tags(entry: &ClipEntry) -> String {
+    entry
+        .tags
+        .iter()
+        .map(|t| format!("#{}", t))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Table row of the clip at `index`, accounting for group section headers
+/// `groups` holds (index of the group's first clip, group), in order
+pub fn list_row(index: usize, rows_per_entry: usize, groups: &[(usize, ClipGroup)]) -> usize {
+    let headers = groups.iter().filter(|(start, _)| *start <= index).count();
+    index * rows_per_entry + headers
+}
+
+/// Clip shown at a list row (None for section headers and rows past the end)
+/// Inverse of `list_row`; spacer rows belong to the clip above them
+pub fn clip_at_row(
+    row: usize,
+    rows_per_entry: usize,
+    groups: &[(usize, ClipGroup)],
+    count: usize,
+) -> Option<usize> {
+    (0..count).find(|&index| {
+        let start = list_row(index, rows_per_entry, groups);
+        (start..start + rows_per_entry).contains(&row)
+    })
+}
+
+/// Insert group section header rows before the first clip of each group
+/// `rows_per_entry` table rows are produced per clip
+fn insert_group_headers<'a>(
+    rows: Vec<Row<'a>>,
+    rows_per_entry: usize,
+    groups: &[(usize, ClipGroup)],
+    header_column: usize,
+    theme: &'a super::Theme,
+) -> Vec<Row<'a>> {
+    if groups.is_empty() {
+        return rows;
+    }
+
+    let mut with_headers = Vec::with_capacity(rows.len() + groups.len());
+    let mut groups = groups.iter().peekable();
+    for (row_index, row) in rows.into_iter().enumerate() {
+        if row_index % rows_per_entry == 0
+            && let Some((_, group)) =
+                groups.next_if(|(start, _)| *start == row_index / rows_per_entry)
+        {
+            let mut cells = vec![Cell::from(""); header_column];
+            cells.push(Cell::from(Span::styled(
+                group.label(),
+                theme.clip_list_header,
+            )));
+            with_headers.push(Row::new(cells));
+        }
+        with_headers.push(row);
+    }
+    with_headers
+}
+
+/// Search match highlighting for the rows currently on screen
+struct RowHighlight<'a> {
+    highlighter: &'a mut Highlighter,
+    /// Entry indices in view (rows outside aren't worth matching)
+    visible: Range<usize>,
+}
+
+/// Style a row's preview text, highlighting search matches if the row is in view
+fn preview_line(
+    preview: String,
+    index: usize,
+    style: Style,
+    highlight: &mut Option<RowHighlight>,
+    theme: &super::Theme,
+) -> Line<'static> {
+    match highlight {
+        Some(h) if h.visible.contains(&index) => {
+            let indices = h.highlighter.indices(&preview);
+            Line::from(super::highlight_spans(
+                &preview,
+                &indices,
+                style,
+                theme.search_match,
+            ))
+        }
+        _ => Line::from(Span::styled(preview, style)),
+    }
+}
+
 /// Render table rows for compact mode (two columns: content and registers)
 fn render_compact_table_rows<'a>(
     entries: &[&ClipEntry],
     selected: usize,
+    marked: &HashSet<u64>,
     content_col_width: usize,
+    mut highlight: Option<RowHighlight>,
     theme: &'a super::Theme,
 ) -> Vec<Row<'a>> {
     entries
@@ -64,7 +167,13 @@ fn render_compact_table_rows<'a>(
             } else {
                 theme.clip_text
             };
-            let preview_cell = Cell::from(Span::styled(preview, preview_style));
+            let preview_cell = Cell::from(preview_line(
+                preview,
+                i,
+                preview_style,
+                &mut highlight,
+                theme,
+            ));
 
             // Column 4: Registers
             let mut register_spans = Vec::new();
@@ -99,12 +208,27 @@ fn render_compact_table_rows<'a>(
                 register_spans.push(Span::styled(format!("\"{}", reg), style));
             }
 
+            // Tags after registers
+            if !entry.tags.is_empty() {
+                if !register_spans.is_empty() {
+                    register_spans.push(Span::raw(" "));
+                }
+                let style = if is_selected {
+                    theme.tag.bg(theme.selection_bg)
+                } else {
+                    theme.tag
+                };
+                register_spans.push(Span::styled(format_tags(entry), style));
+            }
+
             let register_cell = Cell::from(Line::from(register_spans));
 
             // Create row with 4 columns
             let row = Row::new(vec![number_cell, pin_cell, preview_cell, register_cell]);
             if is_selected {
                 row.style(Style::default().bg(theme.selection_bg))
+            } else if marked.contains(&entry.id) {
+                row.style(Style::default().bg(theme.marked_bg))
             } else {
                 row
             }
@@ -116,7 +240,9 @@ fn render_compact_table_rows<'a>(
 fn render_comfortable_table_rows<'a>(
     entries: &[&ClipEntry],
     selected: usize,
+    marked: &HashSet<u64>,
     available_width: usize,
+    mut highlight: Option<RowHighlight>,
     theme: &'a super::Theme,
 ) -> Vec<Row<'a>> {
     // Pre-create pin spans to avoid cloning in the loop
@@ -134,6 +260,7 @@ fn render_comfortable_table_rows<'a>(
 
     for (i, entry) in entries.iter().enumerate() {
         let is_selected = i == selected;
+        let is_marked = marked.contains(&entry.id);
 
         // Use clip_text or clip_text_selected for preview
         let preview_style = if is_selected {
@@ -171,12 +298,15 @@ fn render_comfortable_table_rows<'a>(
         // Get preview text (full width minus number, indicator, and spacing)
         let max_preview_len = available_width.saturating_sub(6 + indicator_width);
         let preview = entry.preview(max_preview_len);
-        row1_spans.push(Span::styled(preview, preview_style));
+        let preview = preview_line(preview, i, preview_style, &mut highlight, theme);
+        row1_spans.extend(preview.spans);
 
         let row1_cell = Cell::from(Line::from(row1_spans));
         let row1 = Row::new(vec![row1_cell]);
         let row1 = if is_selected {
             row1.style(Style::default().bg(theme.selection_bg))
+        } else if is_marked {
+            row1.style(Style::default().bg(theme.marked_bg))
         } else {
             row1
         };
@@ -235,10 +365,18 @@ fn render_comfortable_table_rows<'a>(
             }
         }
 
+        // Add tags after registers
+        if !entry.tags.is_empty() {
+            line2_spans.push(Span::raw("  "));
+            line2_spans.push(Span::styled(format_tags(entry), theme.tag));
+        }
+
         let row2_cell = Cell::from(Line::from(line2_spans));
         let row2 = Row::new(vec![row2_cell]);
         let row2 = if is_selected {
             row2.style(Style::default().bg(theme.selection_bg))
+        } else if is_marked {
+            row2.style(Style::default().bg(theme.marked_bg))
         } else {
             row2
         };
@@ -260,11 +398,55 @@ pub struct ClipListRenderContext<'a> {
     pub search_input: &'a Input,
     pub numeric_prefix: &'a str,
     pub register_filter: RegisterFilter,
+    /// Inline prompt shown in the header (label, input), e.g. tag editing
+    pub prompt: Option<(String, &'a Input)>,
+    /// Clips marked for batch actions
+    pub marked: &'a HashSet<u64>,
+    /// Section headers for the grouped view (empty when not grouping)
+    pub groups: &'a [(usize, ClipGroup)],
+    pub sort_mode: SortMode,
+    /// Search mode indicator, shown at the right of the search line
+    pub search_indicator: Span<'a>,
+    /// Highlights search matches in row previews (None when not searching)
+    pub highlighter: Option<&'a mut Highlighter>,
     pub view_mode: ViewMode,
     pub scroll_offset: usize,
+    /// Loaded image thumbnails, shown at the right of comfortable rows (None disables them)
+    pub thumbnails: Option<&'a mut LruCache<u64, StatefulProtocol>>,
     pub theme: &'a super::Theme,
 }
 
+/// Draw loaded thumbnails over the right edge of the first two rows of each visible clip
+fn render_thumbnails(
+    frame: &mut Frame,
+    list_area: Rect,
+    entries: &[&ClipEntry],
+    thumbnails: &mut LruCache<u64, StatefulProtocol>,
+    groups: &[(usize, ClipGroup)],
+    scroll_offset: usize,
+) {
+    if list_area.width <= THUMBNAIL_COLS {
+        return;
+    }
+    for (i, entry) in entries.iter().enumerate() {
+        let Some(row) = list_row(i, 3, groups).checked_sub(scroll_offset) else {
+            continue;
+        };
+        if row + THUMBNAIL_ROWS as usize > list_area.height as usize {
+            break;
+        }
+        if let Some(thumbnail) = thumbnails.get_mut(&entry.id) {
+            let area = Rect {
+                x: list_area.right() - THUMBNAIL_COLS,
+                y: list_area.y + row as u16,
+                width: THUMBNAIL_COLS,
+                height: THUMBNAIL_ROWS,
+            };
+            frame.render_stateful_widget(StatefulImage::new(None), area, thumbnail);
+        }
+    }
+}
+
 /// Render the clip list widget showing clipboard history entries
 /// Always shows header at top (search or title or numeric prefix) with item count
 pub fn render_clip_list(
@@ -294,11 +476,29 @@ pub fn render_clip_list(
 
     // Render header with item count (right-aligned)
     let item_count = entries.len();
-    let count_text = format!("{} items", item_count);
+    let mut count_text = if ctx.marked.is_empty() {
+        format!("{} items", item_count)
+    } else {
+        format!("{} items, {} marked", item_count, ctx.marked.len())
+    };
+    if ctx.sort_mode != SortMode::Recent && !matches!(ctx.mode, AppMode::Trash) {
+        count_text.push_str(&format!(", by {}", ctx.sort_mode.name()));
+    }
 
     // Determine header text and style based on mode and filter
     let search_query = ctx.search_input.value();
-    let (header_left, header_style) = if !ctx.numeric_prefix.is_empty() {
+    let mut show_indicator = false;
+    let (header_left, header_style) = if let Some((label, input)) = &ctx.prompt {
+        // Inline prompt (tag editing or template input)
+        let style = if matches!(ctx.mode, AppMode::TagEdit) {
+            ctx.theme.tag
+        } else {
+            ctx.theme.search_input
+        };
+        (format!("{}{}", label, input.value()), style)
+    } else if matches!(ctx.mode, AppMode::Trash) {
+        ("Trash".to_string(), ctx.theme.clip_list_header)
+    } else if !ctx.numeric_prefix.is_empty() {
         // Numeric prefix mode: show the prefix being typed with space
         (format!(": {}", ctx.numeric_prefix), ctx.theme.temp_register)
     } else if matches!(ctx.mode, AppMode::Search) || !search_query.is_empty() {
@@ -312,6 +512,7 @@ pub fn render_clip_list(
 
         // Show search text (cursor will be set via frame.set_cursor_position)
         let header_text = format!("{}{}", prefix, search_query);
+        show_indicator = true;
 
         (header_text, style)
     } else {
@@ -331,6 +532,11 @@ pub fn render_clip_list(
         (header, style)
     };
 
+    // Search mode indicator, right-aligned over the search line
+    let indicator = show_indicator.then(|| {
+        Paragraph::new(Line::from(ctx.search_indicator.clone())).alignment(Alignment::Right)
+    });
+
     // Determine background for search/title text (focused when in search mode)
     let search_line_bg = if matches!(ctx.mode, AppMode::Search) {
         ctx.theme.search_focused_bg
@@ -356,6 +562,9 @@ pub fn render_clip_list(
             let search_para = Paragraph::new(Line::from(Span::styled(header_left, header_style)))
                 .style(Style::default().bg(search_line_bg));
             frame.render_widget(search_para, header_chunks[0]);
+            if let Some(indicator) = indicator {
+                frame.render_widget(indicator, header_chunks[0]);
+            }
 
             // Render count line with normal background
             let count_para = Paragraph::new(Line::from(Span::styled(
@@ -380,6 +589,9 @@ pub fn render_clip_list(
             let search_para = Paragraph::new(Line::from(Span::styled(header_left, header_style)))
                 .style(Style::default().bg(search_line_bg));
             frame.render_widget(search_para, header_chunks[0]);
+            if let Some(indicator) = indicator {
+                frame.render_widget(indicator, header_chunks[0]);
+            }
 
             // Render count with normal background
             let count_para = Paragraph::new(Line::from(Span::styled(
@@ -404,9 +616,17 @@ pub fn render_clip_list(
                 .unwrap_or(0)
                 .min(4);
 
-            // Calculate register column width: ~3 chars per register + spacing
-            let register_col_width = if max_register_count > 0 {
-                (max_register_count * 3 + 2) as u16
+            // Calculate max tag text width (capped)
+            let max_tag_width = entries
+                .iter()
+                .map(|e| format_tags(e).width())
+                .max()
+                .unwrap_or(0)
+                .min(MAX_COMPACT_TAG_WIDTH);
+
+            // Calculate register column width: ~3 chars per register + spacing, plus tags
+            let register_col_width = if max_register_count > 0 || max_tag_width > 0 {
+                (max_register_count * 3 + 2 + max_tag_width) as u16
             } else {
                 0
             };
@@ -435,12 +655,22 @@ pub fn render_clip_list(
                 .saturating_sub(6); // Table spacing
 
             // Use Table for compact mode with 4 columns
+            // Header rows only shift clips down, so widen the range by their count
+            let visible_rows = list_area.height as usize;
+            let first_visible = ctx.scroll_offset.saturating_sub(ctx.groups.len());
+            let highlight = ctx.highlighter.map(|highlighter| RowHighlight {
+                highlighter,
+                visible: first_visible..ctx.scroll_offset + visible_rows,
+            });
             let rows = render_compact_table_rows(
                 entries,
                 ctx.selected,
+                ctx.marked,
                 content_col_width as usize,
+                highlight,
                 ctx.theme,
             );
+            let rows = insert_group_headers(rows, 1, ctx.groups, 2, ctx.theme);
 
             // Table with 4 columns: number, pin, preview (fills space), registers
             let widths = [
@@ -459,7 +689,7 @@ pub fn render_clip_list(
                 );
 
             let mut table_state = ratatui::widgets::TableState::default();
-            table_state.select(Some(ctx.selected));
+            table_state.select(Some(list_row(ctx.selected, 1, ctx.groups)));
             *table_state.offset_mut() = ctx.scroll_offset;
 
             frame.render_stateful_widget(table, list_area, &mut table_state);
@@ -467,8 +697,28 @@ pub fn render_clip_list(
         ViewMode::Comfortable => {
             // Use Table for comfortable mode
             // Indicators are manually added to row content based on selection_indicator_repeats_comfortable
-            let rows =
-                render_comfortable_table_rows(entries, ctx.selected, available_width, ctx.theme);
+            // Each clip takes 3 table rows (header rows only shift clips down)
+            let first_visible = (ctx.scroll_offset / 3).saturating_sub(ctx.groups.len());
+            let visible_clips = (list_area.height as usize).div_ceil(3) + 1;
+            let highlight = ctx.highlighter.map(|highlighter| RowHighlight {
+                highlighter,
+                visible: first_visible..first_visible + visible_clips,
+            });
+            // Leave room for thumbnails at the right of the preview row
+            let text_width = if ctx.thumbnails.is_some() {
+                available_width.saturating_sub(THUMBNAIL_COLS as usize + 1)
+            } else {
+                available_width
+            };
+            let rows = render_comfortable_table_rows(
+                entries,
+                ctx.selected,
+                ctx.marked,
+                text_width,
+                highlight,
+                ctx.theme,
+            );
+            let rows = insert_group_headers(rows, 3, ctx.groups, 0, ctx.theme);
 
             let table = Table::new(rows, [Constraint::Min(10)])
                 .style(Style::default().bg(ctx.theme.clip_list_bg));
@@ -476,13 +726,30 @@ pub fn render_clip_list(
             // Select the first row of the selected clip for scrolling purposes
             // Each clip has 3 rows (preview, metadata, spacing)
             let mut table_state = ratatui::widgets::TableState::default();
-            table_state.select(Some(ctx.selected * 3));
+            table_state.select(Some(list_row(ctx.selected, 3, ctx.groups)));
             *table_state.offset_mut() = ctx.scroll_offset;
 
             frame.render_stateful_widget(table, list_area, &mut table_state);
+
+            if let Some(thumbnails) = ctx.thumbnails {
+                render_thumbnails(
+                    frame,
+                    list_area,
+                    entries,
+                    thumbnails,
+                    ctx.groups,
+                    ctx.scroll_offset,
+                );
+            }
         }
     }
 
+    // Set cursor position when a prompt is active
+    if let Some((label, input)) = &ctx.prompt {
+        let cursor_x = header_area.x + label.width() as u16 + input.visual_cursor() as u16;
+        frame.set_cursor_position(Position::new(cursor_x, header_area.y));
+    }
+
     // Set cursor position when in search mode
     if matches!(ctx.mode, AppMode::Search) {
         // Calculate prefix width based on register filter
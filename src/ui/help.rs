@@ -4,73 +4,272 @@ use unicode_width::UnicodeWidthStr;
 
 use super::Theme;
 use super::layout::centered_rect;
+use crate::models::{Action, KeyScope, Keymap, key_labels};
 
 /// Height reserved for help modal padding (2px top + 2px bottom)
 const HELP_MODAL_PADDING: u16 = 4;
 
+/// Keys listed on a help line
+enum HelpKeys {
+    /// Every key bound to the actions in the list (or the preview or search), from the keymap
+    Normal(&'static [Action]),
+    Preview(&'static [Action]),
+    Search(&'static [Action]),
+    /// Keys that can't be rebound (prompts, numeric prefixes, the mouse)
+    Fixed(&'static [&'static str]),
+}
+
+use HelpKeys::{Fixed, Normal, Preview, Search};
+
 struct HelpSection {
     title: &'static str,
-    items: &'static [(&'static [&'static str], &'static str)],
+    items: &'static [(HelpKeys, &'static str)],
 }
 
 const HELP_SECTIONS: &[HelpSection] = &[
     HelpSection {
         title: "Navigation",
         items: &[
-            (&["k", "↑", "j", "↓"], "Move up/down"),
-            (&["Home", "End"], "Jump to top/bottom"),
-            (&["PgUp", "PgDn"], "Page up/down"),
-            (&["Ctrl-u", "d"], "Half-page up/down"),
-            (&[], ""),
-            (&["<number>"], "Enter numeric mode (shows command palette)"),
-            (&["  5j"], "move down 5 lines"),
-            (&["  3Ctrl-d"], "3 half-pages down"),
-            (&["  15Enter"], "jump to line 15"),
+            (Normal(&[Action::Up, Action::Down]), "Move up/down"),
+            (Normal(&[Action::Top, Action::Bottom]), "Jump to top/bottom"),
+            (Normal(&[Action::PageUp, Action::PageDown]), "Page up/down"),
+            (
+                Normal(&[Action::HalfPageUp, Action::HalfPageDown]),
+                "Half-page up/down",
+            ),
+            (Fixed(&[]), ""),
+            (
+                Fixed(&["<number>"]),
+                "Enter numeric mode (shows command palette)",
+            ),
+            (Fixed(&["  5j"]), "move down 5 lines"),
+            (Fixed(&["  3Ctrl-d"]), "3 half-pages down"),
+            (Fixed(&["  15Enter"]), "jump to line 15"),
+        ],
+    },
+    HelpSection {
+        title: "Preview",
+        items: &[
+            (Normal(&[Action::FocusPreview]), "Focus preview"),
+            (Preview(&[Action::Back]), "Back to the list"),
+            (
+                Preview(&[Action::Down, Action::Up]),
+                "Scroll preview by line",
+            ),
+            (
+                Preview(&[Action::HalfPageUp, Action::HalfPageDown]),
+                "Half-page up/down",
+            ),
+            (
+                Preview(&[Action::Top, Action::Bottom]),
+                "Jump to top/bottom",
+            ),
+            (
+                Preview(&[Action::Left, Action::Right]),
+                "Scroll sideways (when not wrapping)",
+            ),
+            (Preview(&[Action::LineNumbers]), "Toggle line numbers"),
+            (Preview(&[Action::Wrap]), "Toggle line wrap"),
+            (Preview(&[Action::Format]), "Toggle pretty-printed JSON/XML"),
+            (
+                Preview(&[Action::Fold, Action::Unfold]),
+                "Fold/unfold JSON/XML nesting levels",
+            ),
+            (
+                Preview(&[Action::CopyPretty, Action::CopyMinified]),
+                "Copy JSON/XML pretty-printed/minified (also in list)",
+            ),
+            (Preview(&[Action::Hex]), "Toggle hex dump"),
+            (
+                Preview(&[Action::Invisibles]),
+                "Toggle visible tabs/CR/zero-width/control chars",
+            ),
+            (
+                Preview(&[Action::ZoomIn, Action::ZoomOut]),
+                "Zoom image in/out (scroll keys pan when zoomed)",
+            ),
+            (
+                Preview(&[Action::ZoomFit, Action::ZoomActual]),
+                "Fit image to pane / actual size",
+            ),
         ],
     },
     HelpSection {
         title: "Look & Feel",
         items: &[
-            (&["v"], "Toggle between Compact/Comfortable view modes"),
-            (&["T"], "Open theme picker"),
-            (&["Ctrl-T"], "Cycle to next theme"),
-            (&["Alt-T"], "Save current theme as default"),
+            (
+                Normal(&[Action::ToggleView]),
+                "Toggle between Compact/Comfortable view modes",
+            ),
+            (
+                Normal(&[Action::CycleSort]),
+                "Cycle sort order (recent/oldest/used/size/...)",
+            ),
+            (
+                Normal(&[Action::ToggleGroups]),
+                "Toggle grouped view (Pinned/Registers/Today/...)",
+            ),
+            (Normal(&[Action::ThemePicker]), "Open theme picker"),
+            (Normal(&[Action::CycleTheme]), "Cycle to next theme"),
+            (
+                Normal(&[Action::SaveTheme]),
+                "Save current theme as default",
+            ),
+            (Normal(&[Action::ReloadTheme]), "Reload theme file"),
         ],
     },
     HelpSection {
         title: "Search & Filter",
         items: &[
-            (&["/"], "Start fuzzy search"),
-            (&["'"], "Filter by temporary registers"),
-            (&["\""], "Filter by permanent registers"),
-            (&["P"], "Toggle pin filter"),
-            (&["Esc"], "Clear search/filter"),
+            (Normal(&[Action::Search]), "Start fuzzy search"),
+            (
+                Search(&[Action::Up, Action::Down]),
+                "Move through results while typing",
+            ),
+            (Search(&[Action::Copy]), "Copy the selected result"),
+            (Search(&[Action::Paste]), "Paste the selected result"),
+            (
+                Search(&[Action::MatchMode]),
+                "Cycle fuzzy/exact/regex/prefix match",
+            ),
+            (
+                Search(&[Action::CaseSensitive]),
+                "Toggle case-sensitive search",
+            ),
+            (Search(&[Action::Back]), "Leave search, keeping the results"),
+            (
+                Normal(&[Action::TagSearch]),
+                "Filter by tag (e.g. /#sql query)",
+            ),
+            (
+                Fixed(&[]),
+                "Filters: type:text|image|file  pinned:yes|no  reg:a",
+            ),
+            (
+                Fixed(&[]),
+                "  name:foo  mime:image/png  size:>1k  after:/before:",
+            ),
+            (Fixed(&[]), "  (YYYY-MM-DD or 3d/12h)  prefix ! to negate"),
+            (
+                Normal(&[Action::TemporaryFilter]),
+                "Filter by temporary registers",
+            ),
+            (
+                Normal(&[Action::PermanentFilter]),
+                "Filter by permanent registers",
+            ),
+            (Normal(&[Action::PinnedFilter]), "Toggle pin filter"),
+            (Normal(&[Action::Back]), "Clear search/filter"),
         ],
     },
     HelpSection {
         title: "Actions",
         items: &[
-            (&["Enter"], "Copy to clipboard"),
-            (&["Ctrl-Space"], "Paste via Ctrl-V"),
+            (Normal(&[Action::Copy]), "Copy to clipboard"),
+            (Normal(&[Action::Paste]), "Paste via Ctrl-V"),
+            (
+                Normal(&[Action::AssignRegister]),
+                "Then a letter: assign to temporary register (like vim marks)",
+            ),
+            (Normal(&[Action::TogglePin]), "Toggle pin"),
             (
-                &["m<letter>"],
-                "Assign to temporary register (like vim marks)",
+                Normal(&[Action::EditTags]),
+                "Add/remove tags (space-separated)",
+            ),
+            (
+                Normal(&[Action::Convert]),
+                "Copy image converted (e.g. jpeg max=1920 quality=80)",
+            ),
+            (Normal(&[Action::ClearMessages]), "Clear flash messages"),
+            (Normal(&[Action::Delete]), "Delete entry"),
+            (
+                Normal(&[Action::ClearAll]),
+                "Clear all unpinned (with confirmation)",
+            ),
+            (
+                Normal(&[Action::Trash]),
+                "Open trash (restore deleted clips)",
+            ),
+            (
+                Normal(&[Action::Undo, Action::Redo]),
+                "Undo/redo delete, clear, pin, register and tag changes",
+            ),
+            (Normal(&[Action::Quit, Action::Back]), "Quit"),
+        ],
+    },
+    HelpSection {
+        title: "Multi-select",
+        items: &[
+            (
+                Normal(&[Action::ToggleMark]),
+                "Mark/unmark clip and move down",
+            ),
+            (
+                Normal(&[Action::Visual]),
+                "Visual mode: select a range (Space marks it)",
+            ),
+            (Fixed(&[]), "p, t, d act on all marked clips"),
+            (
+                Normal(&[Action::Join]),
+                "Join marked text clips into a new clip and copy",
+            ),
+            (Normal(&[Action::Export]), "Export marked clips to files"),
+            (Normal(&[Action::Back]), "Clear marks"),
+        ],
+    },
+    HelpSection {
+        title: "Mouse",
+        items: &[
+            (
+                Fixed(&["Click"]),
+                "Select clip (preview: focus, hints: press key)",
+            ),
+            (Fixed(&["Double-click"]), "Copy to clipboard"),
+            (Fixed(&["Wheel"]), "Move in list, scroll preview"),
+            (
+                Fixed(&[]),
+                "Set mouse = false in config for terminal selection",
             ),
-            (&["p"], "Toggle pin"),
-            (&["c"], "Clear flash messages"),
-            (&["d"], "Delete entry"),
-            (&["D"], "Clear all unpinned (with confirmation)"),
-            (&["q", "Esc"], "Quit"),
         ],
     },
     HelpSection {
         title: "Help",
-        items: &[(&["?"], "Show/hide this help")],
+        items: &[
+            (Normal(&[Action::Help]), "Show/hide this help"),
+            (
+                Fixed(&[]),
+                "Rebind keys under [keys] in clipr.toml (see the example config)",
+            ),
+        ],
     },
 ];
 
+impl HelpKeys {
+    /// Labels for the keys, all bound keys of each action in order
+    fn labels(&self, keymap: &Keymap) -> Vec<String> {
+        let (scope, actions) = match self {
+            Normal(actions) => (KeyScope::Normal, actions),
+            Preview(actions) => (KeyScope::Preview, actions),
+            Search(actions) => (KeyScope::Search, actions),
+            Fixed(keys) => return keys.iter().map(|key| key.to_string()).collect(),
+        };
+        let chords: Vec<_> = actions
+            .iter()
+            .flat_map(|action| keymap.keys(scope, *action))
+            .copied()
+            .collect();
+        key_labels(&chords)
+    }
+}
+
 /// Add help content with proper styling and fixed-width columns
-fn add_help_content<'a>(content: &mut Vec<Line<'a>>, section: &HelpSection, theme: &Theme) {
+/// Lines whose actions have no keys bound are left out
+fn add_help_content<'a>(
+    content: &mut Vec<Line<'a>>,
+    section: &HelpSection,
+    keymap: &Keymap,
+    theme: &Theme,
+) {
     const KEY_COLUMN_WIDTH: usize = 20; // Fixed width for keys column
 
     // Add section title
@@ -81,7 +280,11 @@ fn add_help_content<'a>(content: &mut Vec<Line<'a>>, section: &HelpSection, them
     content.push(Line::default());
 
     // Add section items
-    for (keys, description) in section.items {
+    for (help_keys, description) in section.items {
+        let keys = help_keys.labels(keymap);
+        if keys.is_empty() && !matches!(help_keys, Fixed(_)) {
+            continue;
+        }
         if keys.is_empty() && description.is_empty() {
             // Empty line for spacing
             content.push(Line::default());
@@ -104,16 +307,12 @@ fn add_help_content<'a>(content: &mut Vec<Line<'a>>, section: &HelpSection, them
                         theme.help_desc.add_modifier(Modifier::DIM),
                     ));
                 }
-                line_spans.push(Span::styled(*key, theme.help_key));
+                line_spans.push(Span::styled(key.clone(), theme.help_key));
             }
 
             // Calculate padding needed to align descriptions
-            let keys_str: String = keys
-                .iter()
-                .map(|k| k.to_string())
-                .collect::<Vec<_>>()
-                .join("/");
-            let padding = KEY_COLUMN_WIDTH.saturating_sub(keys_str.width());
+            let keys_str = keys.join("/");
+            let padding = KEY_COLUMN_WIDTH.saturating_sub(keys_str.width()).max(1);
 
             line_spans.push(Span::raw(" ".repeat(padding)));
             line_spans.push(Span::styled(*description, theme.help_desc));
@@ -130,6 +329,7 @@ fn add_help_content<'a>(content: &mut Vec<Line<'a>>, section: &HelpSection, them
 pub fn render_help_overlay(
     frame: &mut Frame,
     area: Rect,
+    keymap: &Keymap,
     theme: &Theme,
     scroll: usize,
 ) -> (usize, usize) {
@@ -147,14 +347,15 @@ pub fn render_help_overlay(
 
     // Add sections
     for section in HELP_SECTIONS {
-        add_help_content(&mut content, section, theme);
+        add_help_content(&mut content, section, keymap, theme);
     }
 
     // Calculate available height and determine if scrolling is needed
     let available_height = overlay_area.height.saturating_sub(HELP_MODAL_PADDING) as usize;
 
     // Build footer with scroll indicators (BEFORE calculating max_scroll)
-    let footer_text = "Press ?/Esc to close";
+    let close_keys = key_labels(keymap.keys(KeyScope::Help, Action::Back));
+    let footer_text = format!("Press {} to close", close_keys.join("/"));
 
     content.push(Line::from(vec![Span::styled(
         footer_text,
@@ -64,6 +64,20 @@ pub fn create_main_layout(area: Rect, view_mode: ViewMode) -> Vec<Rect> {
     ]
 }
 
+/// Create the picker layout: a full-width clip list and keyboard hints, no preview
+/// Returns [clip_list_area, keyboard_hints_area]
+pub fn create_pick_layout(area: Rect) -> Vec<Rect> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),    // Clip list
+            Constraint::Length(1), // Keyboard hints bar
+        ])
+        .split(area);
+
+    vec![chunks[0], chunks[1]]
+}
+
 /// Create centered rectangle for popups/overlays
 pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -14,9 +14,9 @@ use ratatui::widgets::{Block, Clear, Paragraph};
 pub use clip_list::render_clip_list;
 pub use error_modal::render_error_modal;
 pub use help::render_help_overlay;
-pub use layout::{centered_rect, create_main_layout};
+pub use layout::{centered_rect, create_main_layout, create_pick_layout};
 pub use preview::render_preview;
-pub use search::render_search_input;
+pub use search::{highlight_spans, render_search_input, search_mode_indicator};
 pub use status::render_keyboard_hints;
 pub use theme::{BuiltInTheme, Theme};
 pub use theme_picker::render_theme_picker;
@@ -1,23 +1,98 @@
 use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::prelude::*;
 use ratatui::widgets::{Paragraph, Wrap};
-use ratatui_image::StatefulImage;
+use ratatui_image::picker::Picker;
 use ratatui_image::protocol::StatefulProtocol;
+use ratatui_image::{Resize, StatefulImage};
 
 use super::Theme;
+use crate::image::{ImageInfo, ImageZoom, ZoomView};
 use crate::logging::FlashMessage;
-use crate::models::{ClipContent, ClipEntry};
+use crate::models::inspect::{self, HEX_ROW_BYTES, MAX_HEX_BYTES};
+use crate::models::syntax::MAX_HIGHLIGHT_BYTES;
+use crate::models::{
+    ClipContent, ClipEntry, FileHead, FormatError, Highlighter, Invisible, Invisibles, Language,
+    StructuredFormat, Token, Tokenizer,
+};
+
+/// Text clips larger than this aren't scanned for invisible characters
+const MAX_INVISIBLES_SCAN_BYTES: usize = 1024 * 1024;
+
+/// Context for rendering the preview panel
+pub struct PreviewRenderContext<'a> {
+    /// Decoded image for the selected clip, if loaded
+    pub cached_image: Option<&'a mut StatefulProtocol>,
+    /// Highlights search matches in text content (None when not searching)
+    pub highlighter: Option<&'a mut Highlighter>,
+    pub show_metadata: bool,
+    /// Scroll offset in rows (None scrolls to the first search match)
+    pub scroll: Option<usize>,
+    /// Horizontal scroll in columns (only used without wrapping)
+    pub hscroll: usize,
+    pub line_numbers: bool,
+    pub wrap: bool,
+    /// Whether the preview has keyboard focus
+    pub focused: bool,
+    /// Pretty-printed JSON/XML for the clip, or why it couldn't be parsed
+    pub formatted: Option<(StructuredFormat, &'a Result<String, FormatError>)>,
+    /// Show a hex dump instead of the content
+    pub hex: bool,
+    /// Why the clip's image couldn't be loaded (image data is then shown as a hex dump)
+    pub image_error: Option<&'a str>,
+    /// Leading bytes of a file clip, for the hex dump
+    pub file_head: Option<&'a anyhow::Result<FileHead>>,
+    /// Render tabs, CRs, zero-width and control characters visibly
+    pub invisibles: bool,
+    /// Dimensions and pixel format of the clip's image, once decoded
+    pub image_info: Option<ImageInfo>,
+    /// Zoomed view of the clip's image and its scale (None fits the image to the pane)
+    pub zoom: Option<(&'a mut ZoomView, u32)>,
+    /// Terminal graphics capabilities, for rendering zoomed images
+    pub picker: &'a mut Picker,
+    pub flash_messages: &'a [FlashMessage],
+    pub theme: &'a Theme,
+}
+
+/// Scroll state of the rendered preview, fed back into the app for key handling
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PreviewScroll {
+    /// Offset in rows actually used
+    pub offset: usize,
+    /// Largest useful offset
+    pub max: usize,
+    /// Height of the content area in rows
+    pub height: usize,
+}
 
 /// Render preview panel with content at top and metadata at bottom
 pub fn render_preview(
     frame: &mut Frame,
     area: Rect,
     entry: Option<&ClipEntry>,
-    cached_image: Option<&mut StatefulProtocol>,
-    show_metadata: bool,
-    flash_messages: &[FlashMessage],
-    theme: &Theme,
-) {
+    ctx: PreviewRenderContext,
+) -> PreviewScroll {
+    let PreviewRenderContext {
+        cached_image,
+        highlighter,
+        show_metadata,
+        scroll,
+        hscroll,
+        line_numbers,
+        wrap,
+        focused,
+        formatted,
+        hex,
+        image_error,
+        file_head,
+        invisibles,
+        image_info,
+        zoom,
+        picker,
+        flash_messages,
+        theme,
+    } = ctx;
+    let mut position = PreviewScroll::default();
+
     if let Some(entry) = entry {
         // Calculate metadata height if metadata is enabled
         let (content_area, metadata_area) = if show_metadata {
@@ -52,21 +127,59 @@ pub fn render_preview(
         // === RENDER CONTENT ===
         let mut content_lines = Vec::new();
         let mut image_rendered = false;
+        let mut first_match_line = None;
+        let (formatted_text, format_error) = match formatted {
+            Some((_, Ok(text))) => (Some(text.as_str()), None),
+            Some((_, Err(error))) => (None, Some(error)),
+            None => (None, None),
+        };
+        let language = match (formatted, formatted_text) {
+            (Some((format, _)), Some(text)) => {
+                Some(format.language()).filter(|_| text.len() <= MAX_HIGHLIGHT_BYTES)
+            }
+            _ => Language::for_entry(entry),
+        };
+
+        let file_data = file_head.and_then(|head| head.as_ref().ok());
+        let hex_data: Option<(&[u8], u64)> = match &entry.content {
+            ClipContent::Text(text) if hex => Some((text.as_bytes(), text.len() as u64)),
+            ClipContent::Image { data, .. } if hex || image_error.is_some() => {
+                Some((data, data.len() as u64))
+            }
+            ClipContent::File { mime_type, .. } if hex || inspect::is_binary_mime(mime_type) => {
+                file_data.map(|head| (head.data.as_slice(), head.size))
+            }
+            _ => None,
+        };
 
-        match &entry.content {
-            ClipContent::Text(text) => {
-                for line in text.lines() {
+        match (&entry.content, hex_data) {
+            (_, Some((data, size))) => {
+                content_lines = hex_lines(data, size, theme);
+            }
+            (ClipContent::Text(text), None)
+                if language.is_some() || highlighter.is_some() || invisibles =>
+            {
+                let text = formatted_text.unwrap_or(text);
+                (content_lines, first_match_line) =
+                    highlight_lines(text, language, highlighter, invisibles, theme);
+            }
+            (ClipContent::Text(text), None) => {
+                for line in formatted_text.unwrap_or(text).lines() {
                     content_lines.push(Line::from(line.to_string()));
                 }
             }
-            ClipContent::Image { .. } => {
-                // Check if we have a cached protocol image for this clip
-                if let Some(protocol_image) = cached_image {
-                    // Render the cached image
-                    let image_widget = StatefulImage::new(None);
-                    frame.render_stateful_widget(image_widget, content_area, protocol_image);
-                    image_rendered = true;
-                } else {
+            (ClipContent::Image { .. }, None) => {
+                // Render the cached image (or the zoomed view) if it's loaded
+                image_rendered = render_image(
+                    frame,
+                    content_area,
+                    cached_image,
+                    zoom,
+                    picker,
+                    focused,
+                    theme,
+                );
+                if !image_rendered {
                     // No cached image yet - show loading message
                     content_lines.push(Line::from(Span::styled(
                         "[Loading image...]",
@@ -74,20 +187,114 @@ pub fn render_preview(
                     )));
                 }
             }
-            ClipContent::File { path, .. } => {
-                content_lines.push(Line::from(vec![
-                    Span::styled("File: ", theme.preview_metadata_label),
-                    Span::raw(path.to_string_lossy()),
-                ]));
+            (ClipContent::File { path, mime_type }, None) => {
+                // Image file decoded by the background loader
+                image_rendered = render_image(
+                    frame,
+                    content_area,
+                    cached_image,
+                    zoom,
+                    picker,
+                    focused,
+                    theme,
+                );
+                if !image_rendered {
+                    content_lines.push(Line::from(vec![
+                        Span::styled("File: ", theme.preview_metadata_label),
+                        Span::raw(path.to_string_lossy()),
+                    ]));
+                    if let Some(Err(e)) = file_head {
+                        content_lines.push(Line::from(Span::styled(
+                            format!("{:#}", e),
+                            theme.flash_error,
+                        )));
+                    } else if let Some(error) = image_error {
+                        content_lines.push(Line::from(Span::styled(
+                            format!("[No image preview: {}]", error),
+                            theme.flash_error,
+                        )));
+                    } else if mime_type.starts_with("image/") {
+                        content_lines.push(Line::from(Span::styled(
+                            "[Loading image...]",
+                            theme.preview_metadata_label,
+                        )));
+                    }
+                }
             }
         }
 
         // Only render text content if we didn't render an image
         if !image_rendered {
-            let content_para = Paragraph::new(content_lines)
+            if line_numbers && hex_data.is_none() {
+                add_line_numbers(&mut content_lines, theme);
+            }
+
+            // Rows each line takes (approximate when wrapping: word wrapping may use more)
+            let width = content_area.width.max(1) as usize;
+            let line_rows: Vec<usize> = content_lines
+                .iter()
+                .map(|line| {
+                    if wrap {
+                        line.width().div_ceil(width).max(1)
+                    } else {
+                        1
+                    }
+                })
+                .collect();
+            let total_rows: usize = line_rows.iter().sum();
+            let height = content_area.height as usize;
+            position.height = height;
+            position.max = total_rows.saturating_sub(height);
+
+            // Without an explicit scroll, put the first search match a third of the way down
+            position.offset = match scroll {
+                Some(offset) => offset.min(position.max),
+                None => first_match_line
+                    .map(|line| line_rows[..line].iter().sum::<usize>())
+                    .filter(|&row| row >= height)
+                    .map_or(0, |row| row.saturating_sub(height / 3).min(position.max)),
+            };
+
+            let first_visible_line = line_rows
+                .iter()
+                .scan(0, |row, &rows| {
+                    *row += rows;
+                    Some(*row)
+                })
+                .take_while(|&end| end <= position.offset)
+                .count();
+            let line_count = content_lines.len();
+
+            let mut content_para = Paragraph::new(content_lines)
                 .style(theme.preview_text.bg(theme.preview_bg))
-                .wrap(Wrap { trim: false });
+                .scroll((
+                    position.offset.min(u16::MAX as usize) as u16,
+                    hscroll.min(u16::MAX as usize) as u16,
+                ));
+            if wrap {
+                content_para = content_para.wrap(Wrap { trim: false });
+            }
             frame.render_widget(content_para, content_area);
+
+            // Position indicator when the clip doesn't fit or the preview has focus
+            if position.max > 0 || focused {
+                let indicator = format!(" {}/{} ", first_visible_line + 1, line_count.max(1));
+                let style = if focused {
+                    theme.search_input.bg(theme.preview_bg)
+                } else {
+                    theme.clip_list_item_count.bg(theme.preview_bg)
+                };
+                let indicator_area = Rect {
+                    x: content_area.x,
+                    y: content_area.y,
+                    width: content_area.width,
+                    height: 1.min(content_area.height),
+                };
+                frame.render_widget(
+                    Paragraph::new(Span::styled(indicator, style)).alignment(Alignment::Right),
+                    indicator_area,
+                );
+            }
         }
 
         // === RENDER METADATA (if enabled) ===
@@ -99,7 +306,10 @@ pub fn render_preview(
             let size_info = match &entry.content {
                 ClipContent::Text(text) => format!("{} bytes", text.len()),
                 ClipContent::Image { data, .. } => format!("{} bytes", data.len()),
-                ClipContent::File { .. } => "file".to_string(),
+                ClipContent::File { .. } => match file_data {
+                    Some(head) => format!("{} bytes", head.size),
+                    None => "file".to_string(),
+                },
             };
 
             let available_width = area.width as usize;
@@ -120,16 +330,40 @@ pub fn render_preview(
                 Span::styled(size_info, theme.preview_metadata_label),
             ]));
 
-            // Line 2: Mime-type
-            let mime_type = match &entry.content {
-                ClipContent::Text(_) => "text/plain",
-                ClipContent::Image { mime_type, .. } => mime_type,
-                ClipContent::File { mime_type, .. } => mime_type,
-            };
-            metadata_lines.push(Line::from(Span::styled(
-                mime_type,
+            // Line 2: Mime-type, plus the detected language and format problems
+            let mut mime_spans = vec![Span::styled(
+                match language.or(formatted.map(|(format, _)| format.language())) {
+                    Some(language) => {
+                        format!("{} ({})", entry.content.mime_type(), language.name())
+                    }
+                    None => entry.content.mime_type().to_string(),
+                },
                 theme.preview_metadata_label,
-            )));
+            )];
+            if let (Some((format, _)), Some(error)) = (formatted, format_error) {
+                mime_spans.push(Span::styled(
+                    format!("  invalid {}: {}", format.name(), error),
+                    theme.flash_error,
+                ));
+            }
+            if let Some(info) = image_info {
+                mime_spans.push(Span::styled(
+                    format!("  {}", info.describe()),
+                    theme.preview_metadata_label,
+                ));
+            }
+            if let ClipContent::Text(text) = &entry.content
+                && text.len() <= MAX_INVISIBLES_SCAN_BYTES
+            {
+                let found = Invisibles::scan(text).summary();
+                if !found.is_empty() {
+                    mime_spans.push(Span::styled(
+                        format!("  contains {}", found.join(", ")),
+                        theme.flash_warn,
+                    ));
+                }
+            }
+            metadata_lines.push(Line::from(mime_spans));
 
             // Line 3: Description (always present, may be empty or multiline)
             if let Some(desc) = &entry.description {
@@ -143,8 +377,11 @@ pub fn render_preview(
                 metadata_lines.push(Line::from(""));
             }
 
-            // Line 4: Registers (always present, may be empty)
-            if !entry.temporary_registers.is_empty() || !entry.permanent_registers.is_empty() {
+            // Line 4: Registers and tags (always present, may be empty)
+            if !entry.temporary_registers.is_empty()
+                || !entry.permanent_registers.is_empty()
+                || !entry.tags.is_empty()
+            {
                 let mut register_spans = Vec::new();
 
                 // Temporary registers with single quotes
@@ -167,6 +404,14 @@ pub fn render_preview(
                     register_spans.push(Span::styled(format!("\"{}", reg), theme.perm_register));
                 }
 
+                // Tags after registers
+                for tag in &entry.tags {
+                    if !register_spans.is_empty() {
+                        register_spans.push(Span::raw(" "));
+                    }
+                    register_spans.push(Span::styled(format!("#{}", tag), theme.tag));
+                }
+
                 metadata_lines.push(Line::from(register_spans));
             } else {
                 // Empty line to maintain 4-row height
@@ -187,6 +432,192 @@ pub fn render_preview(
     if !flash_messages.is_empty() {
         render_flash_messages_overlay(frame, area, flash_messages, theme);
     }
+
+    position
+}
+
+/// Render the clip's image, zoomed and panned or fitted to the area
+/// Returns false when there's nothing decoded to show yet
+fn render_image(
+    frame: &mut Frame,
+    area: Rect,
+    cached_image: Option<&mut StatefulProtocol>,
+    zoom: Option<(&mut ZoomView, u32)>,
+    picker: &mut Picker,
+    focused: bool,
+    theme: &Theme,
+) -> bool {
+    let indicator = if let Some((view, scale)) = zoom {
+        // The view is already cropped and scaled to the area
+        let protocol = view.protocol(picker, area, scale);
+        frame.render_stateful_widget(
+            StatefulImage::new(None).resize(Resize::Crop(None)),
+            area,
+            protocol,
+        );
+        let (x, y) = view.position();
+        format!(" {} @ {},{} ", ImageZoom::Scale(scale).label(), x, y)
+    } else if let Some(protocol_image) = cached_image {
+        frame.render_stateful_widget(StatefulImage::new(None), area, protocol_image);
+        " fit ".to_string()
+    } else {
+        return false;
+    };
+
+    if focused {
+        let indicator_area = Rect {
+            height: 1.min(area.height),
+            ..area
+        };
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                indicator,
+                theme.search_input.bg(theme.preview_bg),
+            ))
+            .alignment(Alignment::Right),
+            indicator_area,
+        );
+    }
+    true
+}
+
+/// Build preview lines with syntax and search match highlighting
+/// Also returns the index of the first line with a match, for auto-scrolling
+fn highlight_lines(
+    text: &str,
+    language: Option<Language>,
+    mut highlighter: Option<&mut Highlighter>,
+    invisibles: bool,
+    theme: &Theme,
+) -> (Vec<Line<'static>>, Option<usize>) {
+    let mut tokenizer = language.map(Tokenizer::new);
+    let mut lines = Vec::new();
+    let mut first_match_line = None;
+
+    // Keep carriage returns when showing invisibles (lines() drops them)
+    let mut text_lines: Vec<&str> = if invisibles {
+        text.split('\n').collect()
+    } else {
+        text.lines().collect()
+    };
+    if invisibles && text.ends_with('\n') {
+        text_lines.pop();
+    }
+
+    for (index, line) in text_lines.into_iter().enumerate() {
+        let tokens = tokenizer
+            .as_mut()
+            .map(|tokenizer| tokenizer.line(line))
+            .unwrap_or_default();
+        let indices = highlighter
+            .as_mut()
+            .map(|highlighter| highlighter.indices(line))
+            .unwrap_or_default();
+        if first_match_line.is_none() && !indices.is_empty() {
+            first_match_line = Some(index);
+        }
+        lines.push(styled_line(line, &tokens, &indices, invisibles, theme));
+    }
+
+    (lines, first_match_line)
+}
+
+/// Style a line from its syntax tokens (byte ranges) and search matches (char indices)
+/// With `invisibles`, hidden characters and trailing whitespace are replaced by markers
+fn styled_line(
+    line: &str,
+    tokens: &[Token],
+    indices: &[usize],
+    invisibles: bool,
+    theme: &Theme,
+) -> Line<'static> {
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_style = Style::default();
+    let mut tokens = tokens.iter().peekable();
+    let mut matches = indices.iter().peekable();
+    let trailing_start = line.trim_end_matches([' ', '\t', '\r']).len();
+
+    for (char_index, (byte, c)) in line.char_indices().enumerate() {
+        while tokens.next_if(|token| token.end <= byte).is_some() {}
+        let mut style = match tokens.peek() {
+            Some(token) if token.start <= byte => theme.syntax_style(token.kind),
+            _ => Style::default(),
+        };
+        if matches.next_if_eq(&&char_index).is_some() {
+            style = style.patch(theme.preview_match);
+        }
+
+        let marker = match inspect::invisible(c) {
+            _ if !invisibles => None,
+            Some(Invisible::Whitespace(shown)) if byte < trailing_start => {
+                Some((shown, theme.preview_metadata_label))
+            }
+            Some(Invisible::Whitespace(shown) | Invisible::Hazard(shown)) => {
+                Some((shown, theme.flash_warn))
+            }
+            None if byte >= trailing_start => Some(("·", theme.flash_warn)),
+            None => None,
+        };
+        if let Some((shown, marker_style)) = marker {
+            if !run.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut run), run_style));
+            }
+            spans.push(Span::styled(shown, style.patch(marker_style)));
+            continue;
+        }
+
+        if style != run_style && !run.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut run), run_style));
+        }
+        run_style = style;
+        run.push(c);
+    }
+
+    if !run.is_empty() {
+        spans.push(Span::styled(run, run_style));
+    }
+    Line::from(spans)
+}
+
+/// Hex dump lines (offset, hex bytes, ASCII), truncated to MAX_HEX_BYTES
+fn hex_lines(data: &[u8], size: u64, theme: &Theme) -> Vec<Line<'static>> {
+    let shown = &data[..data.len().min(MAX_HEX_BYTES)];
+    let mut lines: Vec<Line> = shown
+        .chunks(HEX_ROW_BYTES)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let (offset, hex, ascii) = inspect::hex_row(row * HEX_ROW_BYTES, chunk);
+            Line::from(vec![
+                Span::styled(offset, theme.preview_metadata_label),
+                Span::raw("  "),
+                Span::raw(hex),
+                Span::styled("  |", theme.divider_style),
+                Span::styled(ascii, theme.preview_metadata_label),
+                Span::styled("|", theme.divider_style),
+            ])
+        })
+        .collect();
+
+    if size > shown.len() as u64 {
+        lines.push(Line::from(Span::styled(
+            format!("… {} more bytes", size - shown.len() as u64),
+            theme.preview_metadata_label,
+        )));
+    }
+    lines
+}
+
+/// Prefix each line with its right-aligned line number
+fn add_line_numbers(lines: &mut [Line], theme: &Theme) {
+    let digits = lines.len().max(1).to_string().len();
+    for (index, line) in lines.iter_mut().enumerate() {
+        let number = Span::styled(
+            format!("{:>digits$} ", index + 1),
+            theme.preview_metadata_label,
+        );
+        line.spans.insert(0, number);
+    }
 }
 
 /// Render flash messages as an overlay at the bottom of the preview area
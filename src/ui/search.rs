@@ -2,6 +2,7 @@ use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph};
 
 use super::Theme;
+use crate::models::{MatchMode, SearchMode};
 
 /// Render search input bar at bottom of screen when in search mode
 pub fn render_search_input(frame: &mut Frame, area: Rect, query: &str, theme: &Theme) {
@@ -25,3 +26,64 @@ pub fn render_search_input(frame: &mut Frame, area: Rect, query: &str, theme: &T
 
     frame.render_widget(input, input_area);
 }
+
+/// Search mode indicator shown at the right of the search line, e.g. `[regex Aa]`
+/// `Aa` marks case-sensitive search; an invalid regex is shown in the error style
+pub fn search_mode_indicator(
+    match_mode: MatchMode,
+    case_mode: SearchMode,
+    invalid: bool,
+    theme: &Theme,
+) -> Span<'static> {
+    let case = match case_mode {
+        SearchMode::SmartCase => "",
+        SearchMode::CaseSensitive => " Aa",
+    };
+    let error = if invalid { " invalid" } else { "" };
+    let style = if invalid {
+        theme.flash_error
+    } else {
+        theme.clip_list_item_count
+    };
+
+    Span::styled(format!("[{}{}{}]", match_mode.label(), case, error), style)
+}
+
+/// Split text into spans, patching `highlight` onto the characters at `indices`
+/// `indices` are sorted char indices, as returned by `Highlighter::indices`
+pub fn highlight_spans(
+    text: &str,
+    indices: &[usize],
+    base: Style,
+    highlight: Style,
+) -> Vec<Span<'static>> {
+    if indices.is_empty() {
+        return vec![Span::styled(text.to_string(), base)];
+    }
+
+    let matched = base.patch(highlight);
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    let mut next = indices.iter().peekable();
+
+    for (i, c) in text.chars().enumerate() {
+        let is_match = next.peek() == Some(&&i);
+        if is_match {
+            next.next();
+        }
+        if is_match != run_matched && !run.is_empty() {
+            let style = if run_matched { matched } else { base };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        run_matched = is_match;
+        run.push(c);
+    }
+
+    if !run.is_empty() {
+        let style = if run_matched { matched } else { base };
+        spans.push(Span::styled(run, style));
+    }
+
+    spans
+}
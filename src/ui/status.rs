@@ -1,102 +1,281 @@
+use ratatui::crossterm::event::KeyEvent;
 use ratatui::prelude::*;
 use ratatui::widgets::Paragraph;
 
 use super::Theme;
 use crate::app::{App, AppMode, RegisterFilter};
+use crate::models::{Action, KeyChord, KeyScope, Keymap, key_labels};
 
-const NORMAL_HINTS: &[(&[&str], &str)] = &[
-    (&["j", "k"], "move"),
-    (&["/"], "search"),
-    (&["'", "\""], "filter"),
-    (&["m"], "mark"),
-    (&["p"], "pin"),
-    (&["d"], "delete"),
-    (&["Enter"], "copy"),
-    (&["?"], "help"),
+/// Hints for modes with configurable keys: actions (showing their first key) and a description
+type BoundHints = &'static [(&'static [Action], &'static str)];
+
+/// Hints for keys that can't be rebound (prompt syntax, numeric prefixes)
+type FixedHints = &'static [(&'static [&'static str], &'static str)];
+
+const NORMAL_HINTS: BoundHints = &[
+    (&[Action::Down, Action::Up], "move"),
+    (&[Action::Search], "search"),
+    (
+        &[Action::TemporaryFilter, Action::PermanentFilter],
+        "filter",
+    ),
+    (&[Action::AssignRegister], "mark"),
+    (&[Action::TogglePin], "pin"),
+    (&[Action::EditTags], "tag"),
+    (&[Action::Delete], "delete"),
+    (&[Action::Undo], "undo"),
+    (&[Action::Copy], "copy"),
+    (&[Action::Help], "help"),
+];
+
+const VISUAL_HINTS: BoundHints = &[
+    (&[Action::Down, Action::Up], "extend"),
+    (&[Action::ToggleMark], "mark"),
+    (&[Action::TogglePin], "pin"),
+    (&[Action::EditTags], "tag"),
+    (&[Action::Delete], "delete"),
+    (&[Action::Join], "join"),
+    (&[Action::Export], "export"),
+    (&[Action::Back], "cancel"),
+];
+
+const TRASH_HINTS: BoundHints = &[
+    (&[Action::Down, Action::Up], "move"),
+    (&[Action::Restore], "restore"),
+    (&[Action::Delete], "delete forever"),
+    (&[Action::Back], "back"),
+];
+
+const SEARCH_HINTS: BoundHints = &[
+    (&[Action::Up, Action::Down], "move"),
+    (&[Action::Back], "cancel"),
+    (&[Action::Copy], "select"),
+    (&[Action::MatchMode], "mode"),
+    (&[Action::CaseSensitive], "case"),
+    (&[Action::Paste], "paste"),
+];
+
+const PREVIEW_HINTS: BoundHints = &[
+    (&[Action::Down, Action::Up], "scroll"),
+    (&[Action::HalfPageDown, Action::HalfPageUp], "half-page"),
+    (&[Action::Top, Action::Bottom], "top/bottom"),
+    (&[Action::LineNumbers], "line numbers"),
+    (&[Action::Wrap], "wrap"),
+    (&[Action::Format], "format"),
+    (&[Action::Hex], "hex"),
+    (&[Action::ZoomIn, Action::ZoomOut], "zoom"),
+    (&[Action::Back], "back"),
 ];
 
-const SEARCH_HINTS: &[(&[&str], &str)] = &[
-    (&["↑", "↓"], "move"),
-    (&["Esc"], "cancel"),
-    (&["Enter"], "select"),
-    (&["Ctrl-Spc"], "paste"),
+const REGISTER_ASSIGN_HINTS: BoundHints = &[(&[Action::Back], "cancel")];
+
+const TAG_EDIT_HINTS: BoundHints = &[
+    (&[Action::Select], "toggle tags"),
+    (&[Action::Back], "cancel"),
 ];
 
-const REGISTER_ASSIGN_HINTS: &[(&[&str], &str)] = &[(&["Esc"], "cancel")];
+/// Conversion options typed into the prompt
+const CONVERT_FIXED_HINTS: FixedHints = &[
+    (&["png", "jpeg", "webp", "bmp"], "format"),
+    (&["max=N"], "shrink"),
+    (&["quality=Q"], "jpeg quality"),
+];
 
-const CONFIRM_HINTS: &[(&[&str], &str)] = &[(&["y"], "confirm"), (&["n", "Esc"], "cancel")];
+const CONVERT_HINTS: BoundHints = &[(&[Action::Select], "copy"), (&[Action::Back], "cancel")];
 
-const HELP_HINTS: &[(&[&str], &str)] = &[
-    (&["j", "k"], "scroll"),
-    (&["PgDn", "PgUp"], "page"),
-    (&["?", "Esc"], "close"),
+const TEMPLATE_INPUT_HINTS: BoundHints = &[
+    (&[Action::Select], "next field"),
+    (&[Action::Back], "cancel"),
 ];
 
-const NUMERIC_HINTS: &[(&[&str], &str)] = &[
-    (&["j", "k"], "move"),
-    (&["Ctrl-d", "u"], "half-page"),
-    (&["Enter"], "jump to line"),
-    (&["Esc"], "cancel"),
+const CONFIRM_HINTS: BoundHints = &[(&[Action::Select], "confirm"), (&[Action::Back], "cancel")];
+
+const HELP_HINTS: BoundHints = &[
+    (&[Action::Down, Action::Up], "scroll"),
+    (&[Action::PageDown, Action::PageUp], "page"),
+    (&[Action::Back], "close"),
 ];
 
-const THEME_PICKER_HINTS: &[(&[&str], &str)] = &[
-    (&["j", "k"], "navigate"),
-    (&["Enter"], "select"),
-    (&["Esc"], "cancel"),
+/// Numeric mode moves with the Normal mode keys
+const NUMERIC_HINTS: BoundHints = &[
+    (&[Action::Down, Action::Up], "move"),
+    (&[Action::HalfPageDown, Action::HalfPageUp], "half-page"),
 ];
 
-/// Add a hint with keys and description to the hints vector
-fn add_hint<'a>(hints: &mut Vec<Span<'a>>, keys: &[&'a str], description: &'a str, theme: &Theme) {
+const NUMERIC_FIXED_HINTS: FixedHints = &[(&["Enter"], "jump to line"), (&["Esc"], "cancel")];
+
+const THEME_PICKER_HINTS: BoundHints = &[
+    (&[Action::Down, Action::Up], "navigate"),
+    (&[Action::Select], "select"),
+    (&[Action::Back], "cancel"),
+];
+
+/// Clickable part of the hints bar and the key a click sends
+#[derive(Debug, Clone, Copy)]
+pub struct HintTarget {
+    pub area: Rect,
+    pub key: KeyEvent,
+}
+
+/// A hint's key labels (with the key a click sends) and description
+type Hint = (Vec<(String, Option<KeyEvent>)>, &'static str);
+
+/// Hints with the first key bound to each action; hints with nothing bound are left out
+fn bound_hints(keymap: &Keymap, scope: KeyScope, hints: BoundHints) -> Vec<Hint> {
+    hints
+        .iter()
+        .filter_map(|(actions, description)| {
+            let chords: Vec<_> = actions
+                .iter()
+                .filter_map(|action| keymap.keys(scope, *action).first().copied())
+                .collect();
+            let keys: Vec<_> = key_labels(&chords)
+                .into_iter()
+                .zip(chords.into_iter().map(|chord| Some(KeyEvent::from(chord))))
+                .collect();
+            (!keys.is_empty()).then_some((keys, *description))
+        })
+        .collect()
+}
+
+/// Hints with fixed key labels; labels that name a key send it when clicked
+fn fixed_hints(hints: FixedHints) -> Vec<Hint> {
+    hints
+        .iter()
+        .map(|(keys, description)| {
+            let keys = keys
+                .iter()
+                .map(|key| {
+                    let event = KeyChord::parse(key).ok().map(KeyEvent::from);
+                    (key.to_string(), event)
+                })
+                .collect();
+            (keys, *description)
+        })
+        .collect()
+}
+
+/// Hints bar being built: spans plus where each clickable key ended up
+struct HintBar<'a> {
+    area: Rect,
+    x: u16,
+    spans: Vec<Span<'a>>,
+    targets: Vec<HintTarget>,
+}
+
+impl<'a> HintBar<'a> {
+    fn push(&mut self, span: Span<'a>, key: Option<KeyEvent>) {
+        let width = span.width() as u16;
+        if let Some(key) = key {
+            let x = self.area.x.saturating_add(self.x);
+            let right = self.area.right().min(x.saturating_add(width));
+            if x < right {
+                self.targets.push(HintTarget {
+                    area: Rect::new(x, self.area.y, right - x, 1),
+                    key,
+                });
+            }
+        }
+        self.x = self.x.saturating_add(width);
+        self.spans.push(span);
+    }
+}
+
+/// Add a hint with keys and description to the hints bar
+/// Clicking a key sends it; clicking the description sends the first key
+fn add_hint<'a>(bar: &mut HintBar<'a>, hint: Hint, theme: &Theme) {
+    let (keys, description) = hint;
+    let first_key = keys.iter().find_map(|(_, event)| *event);
+
     // Add keys with styled separators
-    for (i, key) in keys.iter().enumerate() {
+    for (i, (label, event)) in keys.into_iter().enumerate() {
         if i > 0 {
-            hints.push(Span::styled(
-                "/",
-                theme.status_desc.add_modifier(Modifier::DIM),
-            ));
+            bar.push(
+                Span::styled("/", theme.status_desc.add_modifier(Modifier::DIM)),
+                None,
+            );
         }
-        hints.push(Span::styled(*key, theme.status_key));
+        bar.push(Span::styled(label, theme.status_key), event);
     }
 
-    hints.push(Span::raw(" "));
-    hints.push(Span::styled(description, theme.status_desc));
-    hints.push(Span::raw("  "));
+    bar.push(Span::raw(" "), None);
+    bar.push(Span::styled(description, theme.status_desc), first_key);
+    bar.push(Span::raw("  "), None);
 }
 
 /// Render keyboard hints bar showing mode-specific shortcuts
-pub fn render_keyboard_hints(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
-    let mut hints = Vec::new();
+/// Returns the clickable hints
+pub fn render_keyboard_hints(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    theme: &Theme,
+) -> Vec<HintTarget> {
+    let mut hints = HintBar {
+        area,
+        x: 0,
+        spans: Vec::new(),
+        targets: Vec::new(),
+    };
 
+    let keymap = &app.keymap;
     let hint_data = match app.mode {
-        AppMode::Normal => NORMAL_HINTS,
-        AppMode::Search => SEARCH_HINTS,
-        AppMode::RegisterAssign => REGISTER_ASSIGN_HINTS,
-        AppMode::Confirm => CONFIRM_HINTS,
-        AppMode::Help => HELP_HINTS,
-        AppMode::Numeric => NUMERIC_HINTS,
-        AppMode::ThemePicker => THEME_PICKER_HINTS,
+        AppMode::Normal => bound_hints(keymap, KeyScope::Normal, NORMAL_HINTS),
+        AppMode::Search => bound_hints(keymap, KeyScope::Search, SEARCH_HINTS),
+        AppMode::RegisterAssign => {
+            bound_hints(keymap, KeyScope::RegisterAssign, REGISTER_ASSIGN_HINTS)
+        }
+        AppMode::Confirm => bound_hints(keymap, KeyScope::Confirm, CONFIRM_HINTS),
+        AppMode::Help => bound_hints(keymap, KeyScope::Help, HELP_HINTS),
+        AppMode::Numeric => {
+            let mut hints = bound_hints(keymap, KeyScope::Normal, NUMERIC_HINTS);
+            hints.extend(fixed_hints(NUMERIC_FIXED_HINTS));
+            hints
+        }
+        AppMode::ThemePicker => bound_hints(keymap, KeyScope::ThemePicker, THEME_PICKER_HINTS),
+        AppMode::TagEdit => bound_hints(keymap, KeyScope::TagEdit, TAG_EDIT_HINTS),
+        AppMode::TemplateInput => {
+            bound_hints(keymap, KeyScope::TemplateInput, TEMPLATE_INPUT_HINTS)
+        }
+        AppMode::Visual => bound_hints(keymap, KeyScope::Visual, VISUAL_HINTS),
+        AppMode::Trash => bound_hints(keymap, KeyScope::Trash, TRASH_HINTS),
+        AppMode::Preview => bound_hints(keymap, KeyScope::Preview, PREVIEW_HINTS),
+        AppMode::Convert => {
+            let mut hints = fixed_hints(CONVERT_FIXED_HINTS);
+            hints.extend(bound_hints(keymap, KeyScope::Convert, CONVERT_HINTS));
+            hints
+        }
     };
 
     // Add static hints
-    for (keys, description) in hint_data {
-        add_hint(&mut hints, keys, description, theme);
+    for hint in hint_data {
+        add_hint(&mut hints, hint, theme);
     }
 
     // Add dynamic q/Esc behavior for normal mode
     if app.mode == AppMode::Normal {
-        if !app.search_input.value().is_empty() {
-            add_hint(&mut hints, &["q"], "quit", theme);
-            add_hint(&mut hints, &["Esc"], "clear search", theme);
+        let dynamic: BoundHints = if !app.marked.is_empty() {
+            &[
+                (&[Action::Join], "join"),
+                (&[Action::Export], "export"),
+                (&[Action::Quit], "quit"),
+                (&[Action::Back], "clear marks"),
+            ]
+        } else if !app.search_input.value().is_empty() {
+            &[(&[Action::Quit], "quit"), (&[Action::Back], "clear search")]
         } else if app.register_filter != RegisterFilter::None {
-            add_hint(&mut hints, &["q"], "quit", theme);
-            add_hint(&mut hints, &["Esc"], "clear filter", theme);
+            &[(&[Action::Quit], "quit"), (&[Action::Back], "clear filter")]
         } else {
-            add_hint(&mut hints, &["q", "Esc"], "quit", theme);
+            &[(&[Action::Quit, Action::Back], "quit")]
+        };
+        for hint in bound_hints(keymap, KeyScope::Normal, dynamic) {
+            add_hint(&mut hints, hint, theme);
         }
     }
 
     let paragraph =
-        Paragraph::new(Line::from(hints)).style(theme.status_desc.bg(theme.status_bar_bg));
+        Paragraph::new(Line::from(hints.spans)).style(theme.status_desc.bg(theme.status_bar_bg));
 
     frame.render_widget(paragraph, area);
+    hints.targets
 }
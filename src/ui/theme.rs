@@ -6,6 +6,8 @@ use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::models::TokenKind;
+
 /// Default pin indicator character
 const DEFAULT_PIN_INDICATOR: &str = " ";
 
@@ -55,6 +57,7 @@ pub struct Theme {
     pub status_bar_bg: Color,
     pub search_bg: Color,
     pub search_focused_bg: Color,
+    pub marked_bg: Color,
 
     // === Indicators ===
     pub selection_indicator_compact: Option<String>,
@@ -70,6 +73,7 @@ pub struct Theme {
     pub clip_text_selected: Style,
     pub temp_register: Style,
     pub perm_register: Style,
+    pub tag: Style,
     pub timestamp: Style,
     pub clip_list_header: Style,
     pub clip_list_item_count: Style,
@@ -85,6 +89,10 @@ pub struct Theme {
 
     // === Search Input ===
     pub search_input: Style,
+    /// Matched characters in clip list rows
+    pub search_match: Style,
+    /// Matched characters in the preview panel
+    pub preview_match: Style,
 
     // === Help Modal ===
     pub help_title: Style,
@@ -145,6 +153,7 @@ impl Theme {
             status_bar_bg: Color::Rgb(214, 219, 237),
             search_bg: bg,
             search_focused_bg: Color::Rgb(227, 232, 250),
+            marked_bg: Color::Rgb(69, 71, 90),
 
             // Indicators
             selection_indicator_compact: None,
@@ -164,6 +173,7 @@ impl Theme {
                 .add_modifier(Modifier::BOLD),
             temp_register: Style::default().fg(Color::Rgb(137, 220, 235)),
             perm_register: Style::default().fg(Color::Rgb(245, 194, 231)),
+            tag: Style::default().fg(Color::Rgb(166, 227, 161)),
             timestamp: Style::default().fg(Color::Rgb(166, 173, 200)),
             clip_list_header: Style::default().fg(Color::Rgb(166, 173, 200)),
             clip_list_item_count: Style::default()
@@ -181,6 +191,10 @@ impl Theme {
 
             // Search
             search_input: Style::default().fg(Color::Rgb(249, 226, 175)),
+            search_match: Style::default()
+                .fg(Color::Rgb(249, 226, 175))
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            preview_match: Style::default().fg(bg).bg(Color::Rgb(249, 226, 175)),
 
             // Help modal
             help_title: Style::default()
@@ -243,6 +257,7 @@ impl Theme {
             status_bar_bg: Color::Rgb(188, 192, 204),
             search_bg: bg,
             search_focused_bg: Color::Rgb(214, 219, 237),
+            marked_bg: Color::Rgb(204, 208, 218),
 
             selection_indicator_compact: None,
             selection_indicator_comfortable: None,
@@ -260,6 +275,7 @@ impl Theme {
                 .add_modifier(Modifier::BOLD),
             temp_register: Style::default().fg(Color::Rgb(4, 165, 229)),
             perm_register: Style::default().fg(Color::Rgb(234, 118, 203)),
+            tag: Style::default().fg(Color::Rgb(64, 160, 43)),
             timestamp: Style::default().fg(Color::Rgb(108, 111, 133)),
             clip_list_header: Style::default().fg(Color::Rgb(108, 111, 133)),
             clip_list_item_count: Style::default()
@@ -274,6 +290,10 @@ impl Theme {
             status_desc: Style::default().fg(Color::Rgb(108, 111, 133)),
 
             search_input: Style::default().fg(Color::Rgb(223, 142, 29)),
+            search_match: Style::default()
+                .fg(Color::Rgb(223, 142, 29))
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            preview_match: Style::default().fg(bg).bg(Color::Rgb(223, 142, 29)),
 
             help_title: Style::default()
                 .fg(Color::Rgb(30, 102, 245))
@@ -333,6 +353,7 @@ impl Theme {
             status_bar_bg: Color::Rgb(36, 40, 59),
             search_bg: bg,
             search_focused_bg: Color::Rgb(49, 50, 68),
+            marked_bg: Color::Rgb(41, 46, 66),
 
             selection_indicator_compact: None,
             selection_indicator_comfortable: None,
@@ -350,6 +371,7 @@ impl Theme {
                 .add_modifier(Modifier::BOLD),
             temp_register: Style::default().fg(Color::Rgb(125, 207, 255)),
             perm_register: Style::default().fg(Color::Rgb(187, 154, 247)),
+            tag: Style::default().fg(Color::Rgb(158, 206, 106)),
             timestamp: Style::default().fg(Color::Rgb(169, 177, 214)),
             clip_list_header: Style::default().fg(Color::Rgb(169, 177, 214)),
             clip_list_item_count: Style::default()
@@ -364,6 +386,10 @@ impl Theme {
             status_desc: Style::default().fg(Color::Rgb(169, 177, 214)),
 
             search_input: Style::default().fg(Color::Rgb(224, 175, 104)),
+            search_match: Style::default()
+                .fg(Color::Rgb(224, 175, 104))
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            preview_match: Style::default().fg(bg).bg(Color::Rgb(224, 175, 104)),
 
             help_title: Style::default()
                 .fg(Color::Rgb(125, 207, 255))
@@ -435,6 +461,7 @@ impl Theme {
             status_bar_bg: bg,
             search_bg: bg,
             search_focused_bg: modal_bg,
+            marked_bg: Color::Rgb(54, 58, 79),
 
             selection_indicator_compact: Some("│".to_string()),
             selection_indicator_comfortable: Some("│".to_string()),
@@ -450,6 +477,7 @@ impl Theme {
             clip_text_selected: Style::default().fg(teal).add_modifier(Modifier::BOLD),
             temp_register: Style::default().fg(orange),
             perm_register: Style::default().fg(green),
+            tag: Style::default().fg(cyan),
             timestamp: Style::default().fg(secondary_text),
             clip_list_header: Style::default()
                 .fg(secondary_text)
@@ -464,6 +492,10 @@ impl Theme {
             status_desc: Style::default().fg(secondary_text),
 
             search_input: Style::default().fg(green),
+            search_match: Style::default()
+                .fg(green)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            preview_match: Style::default().fg(bg).bg(green),
 
             help_title: Style::default().fg(purple).add_modifier(Modifier::BOLD),
             help_header: Style::default().fg(yellow).add_modifier(Modifier::BOLD),
@@ -514,6 +546,7 @@ impl Theme {
             status_bar_bg: Color::Rgb(214, 219, 237),
             search_bg: bg,
             search_focused_bg: Color::Rgb(227, 232, 250),
+            marked_bg: Color::Rgb(196, 200, 218),
 
             selection_indicator_compact: None,
             selection_indicator_comfortable: None,
@@ -531,6 +564,7 @@ impl Theme {
                 .add_modifier(Modifier::BOLD),
             temp_register: Style::default().fg(Color::Rgb(34, 94, 168)),
             perm_register: Style::default().fg(Color::Rgb(136, 57, 239)),
+            tag: Style::default().fg(Color::Rgb(88, 117, 57)),
             timestamp: Style::default().fg(Color::Rgb(78, 89, 131)),
             clip_list_header: Style::default().fg(Color::Rgb(78, 89, 131)),
             clip_list_item_count: Style::default()
@@ -545,6 +579,10 @@ impl Theme {
             status_desc: Style::default().fg(Color::Rgb(78, 89, 131)),
 
             search_input: Style::default().fg(Color::Rgb(150, 80, 0)),
+            search_match: Style::default()
+                .fg(Color::Rgb(150, 80, 0))
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            preview_match: Style::default().fg(bg).bg(Color::Rgb(150, 80, 0)),
 
             help_title: Style::default()
                 .fg(Color::Rgb(34, 94, 168))
@@ -583,6 +621,20 @@ impl Theme {
         }
     }
 
+    /// Style for a syntax token in the preview
+    /// Colors come from the theme's own palette so code matches the rest of the UI
+    pub fn syntax_style(&self, kind: TokenKind) -> Style {
+        let fg = |style: Style| Style::default().fg(style.fg.unwrap_or(self.default_fg));
+        match kind {
+            TokenKind::Keyword => fg(self.perm_register).add_modifier(Modifier::BOLD),
+            TokenKind::Type | TokenKind::Variable => fg(self.temp_register),
+            TokenKind::Function => fg(self.flash_info),
+            TokenKind::String => fg(self.tag),
+            TokenKind::Number | TokenKind::Constant => fg(self.pin_indicator_style),
+            TokenKind::Comment => fg(self.preview_metadata_label).add_modifier(Modifier::ITALIC),
+        }
+    }
+
     /// Load theme by name
     /// - If name starts with '#', force load built-in theme (e.g., "#tokyonight-storm")
     /// - Otherwise, try custom theme first, then fall back to built-in
@@ -669,6 +721,7 @@ impl Theme {
                 "status_bar_bg" => theme.status_bar_bg = color,
                 "search_bg" => theme.search_bg = color,
                 "search_focused_bg" => theme.search_focused_bg = color,
+                "marked_bg" => theme.marked_bg = color,
                 _ => {} // Ignore unknown backgrounds
             }
         }
@@ -702,6 +755,7 @@ impl Theme {
                 "clip_text_selected" => theme.clip_text_selected = style,
                 "temp_register" => theme.temp_register = style,
                 "perm_register" => theme.perm_register = style,
+                "tag" => theme.tag = style,
                 "timestamp" => theme.timestamp = style,
                 "clip_list_header" => theme.clip_list_header = style,
                 "clip_list_item_count" => theme.clip_list_item_count = style,
@@ -711,6 +765,8 @@ impl Theme {
                 "status_key" => theme.status_key = style,
                 "status_desc" => theme.status_desc = style,
                 "search_input" => theme.search_input = style,
+                "search_match" => theme.search_match = style,
+                "preview_match" => theme.preview_match = style,
                 "help_title" => theme.help_title = style,
                 "help_header" => theme.help_header = style,
 
@@ -818,6 +874,7 @@ impl Theme {
             "search_focused_bg = {}\n",
             fmt_rgb(self.search_focused_bg)
         ));
+        output.push_str(&format!("marked_bg = {}\n", fmt_rgb(self.marked_bg)));
         output.push('\n');
 
         // Indicators section
@@ -865,6 +922,7 @@ impl Theme {
             "perm_register = {}\n",
             fmt_style(self.perm_register)
         ));
+        output.push_str(&format!("tag = {}\n", fmt_style(self.tag)));
         output.push_str(&format!("timestamp = {}\n", fmt_style(self.timestamp)));
         output.push_str(&format!(
             "clip_list_header = {}\n",
@@ -892,6 +950,14 @@ impl Theme {
             "search_input = {}\n",
             fmt_style(self.search_input)
         ));
+        output.push_str(&format!(
+            "search_match = {}\n",
+            fmt_style(self.search_match)
+        ));
+        output.push_str(&format!(
+            "preview_match = {}\n",
+            fmt_style(self.preview_match)
+        ));
         output.push_str(&format!("help_title = {}\n", fmt_style(self.help_title)));
         output.push_str(&format!("help_header = {}\n", fmt_style(self.help_header)));
         output.push_str(&format!("help_header = {}\n", fmt_style(self.help_header)));
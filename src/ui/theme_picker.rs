@@ -5,6 +5,7 @@ use super::Theme;
 use super::layout::centered_rect;
 
 /// Render theme picker modal
+/// Returns the area the entries are drawn in and the first visible entry, for mouse clicks
 pub fn render_theme_picker(
     frame: &mut Frame,
     area: Rect,
@@ -12,7 +13,7 @@ pub fn render_theme_picker(
     selected: usize,
     current_theme: &str,
     theme: &Theme,
-) {
+) -> (Rect, usize) {
     let overlay_area = centered_rect(60, 70, area);
 
     // Clear background
@@ -42,4 +43,5 @@ pub fn render_theme_picker(
     list_state.select(Some(selected));
 
     frame.render_stateful_widget(list, overlay_area, &mut list_state);
+    (overlay_area.inner(Margin::new(1, 1)), list_state.offset())
 }